@@ -0,0 +1,53 @@
+// build.rs
+// Zapisuje rewizje gita i parametry kompilacji (rustc, profil, cel, cechy celu),
+// zeby kazdy wynik wskazywal mierzony kod i sposob jego zbudowania
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    let wynik = Command::new("git").args(args).output().ok()?;
+    if !wynik.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&wynik.stdout).trim().to_string())
+}
+
+fn main() {
+    let rewizja = match git(&["rev-parse", "--short=12", "HEAD"]) {
+        Some(hash) => {
+            // Niezatwierdzone zmiany w sledzonych plikach oznaczane sufiksem
+            let zmiany = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| !s.is_empty());
+            if zmiany {
+                format!("{}-zmiany", hash)
+            } else {
+                hash
+            }
+        }
+        None => "nieznana".to_string(),
+    };
+    println!("cargo:rustc-env=GIT_REWIZJA={}", rewizja);
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let wersja = Command::new(rustc)
+        .arg("-V")
+        .output()
+        .ok()
+        .map(|w| String::from_utf8_lossy(&w.stdout).trim().to_string())
+        .unwrap_or_else(|| "nieznana".to_string());
+    let zmienna = |nazwa: &str| std::env::var(nazwa).unwrap_or_default();
+    println!("cargo:rustc-env=RUSTC_WERSJA={}", wersja);
+    println!(
+        "cargo:rustc-env=PROFIL_KOMPILACJI={} opt-level={}",
+        zmienna("PROFILE"),
+        zmienna("OPT_LEVEL")
+    );
+    println!("cargo:rustc-env=CEL_KOMPILACJI={}", zmienna("TARGET"));
+    println!("cargo:rustc-env=CECHY_CELU={}", zmienna("CARGO_CFG_TARGET_FEATURE"));
+    // Sufiks -zmiany zalezy od drzewa roboczego, nie tylko od indeksu - edycja sledzonych plikow
+    // przed `git add` tez musi odswiezyc rewizje
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=scenariusze");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=.git/index");
+}
@@ -0,0 +1,243 @@
+// src/alokacje.rs
+// Licznik alokacji sterty (GlobalAlloc) - wlaczany cecha `alokacje`, bez niej nic nie zlicza.
+// Liczniki sa statyczne (atomiki + staly zestaw slotow na watki, zwalnianych po zakonczeniu watku),
+// wiec sam alokator nie alokuje
+use serde::{Deserialize, Serialize};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use winapi::um::processthreadsapi::GetCurrentThreadId;
+
+// Watki ponad ten limit (jednoczesnie zyjace) trafiaja do ostatniego, wspolnego slotu
+const MAKS_WATKOW: usize = 256;
+const WSPOLNY_SLOT: usize = MAKS_WATKOW - 1;
+
+struct Licznik {
+    liczba: AtomicU64,
+    zaalokowane: AtomicU64,
+    zwolnione: AtomicU64,
+    // Najwieksza zajetosc (zaalokowane - zwolnione) od ostatniego Region::start
+    szczyt: AtomicU64,
+}
+
+impl Licznik {
+    const fn nowy() -> Self {
+        Licznik {
+            liczba: AtomicU64::new(0),
+            zaalokowane: AtomicU64::new(0),
+            zwolnione: AtomicU64::new(0),
+            szczyt: AtomicU64::new(0),
+        }
+    }
+
+    fn alokacja(&self, bajty: u64) {
+        self.liczba.fetch_add(1, Ordering::Relaxed);
+        let zaalokowane = self.zaalokowane.fetch_add(bajty, Ordering::Relaxed) + bajty;
+        let zajete = zaalokowane.saturating_sub(self.zwolnione.load(Ordering::Relaxed));
+        self.szczyt.fetch_max(zajete, Ordering::Relaxed);
+    }
+
+    fn zwolnienie(&self, bajty: u64) {
+        self.zwolnione.fetch_add(bajty, Ordering::Relaxed);
+    }
+
+    fn zajete(&self) -> u64 {
+        self.zaalokowane.load(Ordering::Relaxed).saturating_sub(self.zwolnione.load(Ordering::Relaxed))
+    }
+
+    fn migawka(&self) -> [u64; 3] {
+        [
+            self.liczba.load(Ordering::Relaxed),
+            self.zaalokowane.load(Ordering::Relaxed),
+            self.zwolnione.load(Ordering::Relaxed),
+        ]
+    }
+}
+
+static AKTYWNY: AtomicBool = AtomicBool::new(false);
+static CALOSC: Licznik = Licznik::nowy();
+static WATKI: [Licznik; MAKS_WATKOW] = [const { Licznik::nowy() }; MAKS_WATKOW];
+// Systemowy identyfikator watku, ktory ostatnio zajal slot
+static ID_WATKOW: [AtomicU32; MAKS_WATKOW] = [const { AtomicU32::new(0) }; MAKS_WATKOW];
+// Sloty zajete przez zyjace watki; zwalniane w destruktorze TLS konczacego sie watku
+static ZAJETE: [AtomicBool; WSPOLNY_SLOT] = [const { AtomicBool::new(false) }; WSPOLNY_SLOT];
+
+// Zwalnia slot watku przy jego zakonczeniu. Liczniki slotu nie sa zerowane - migawki Region pozostaja
+// poprawne, a watek, ktory przejmie slot w trakcie obszaru, dolicza sie do tego samego wpisu
+struct ZwolnienieSlotu(Cell<usize>);
+
+impl Drop for ZwolnienieSlotu {
+    fn drop(&mut self) {
+        let slot = self.0.get();
+        // Alokacje z pozniejszych destruktorow TLS ida do slotu wspolnego
+        let _ = SLOT.try_with(|s| s.set(WSPOLNY_SLOT));
+        if slot < WSPOLNY_SLOT {
+            ZAJETE[slot].store(false, Ordering::Release);
+        }
+    }
+}
+
+thread_local! {
+    static SLOT: Cell<usize> = const { Cell::new(usize::MAX) };
+    static ZWOLNIENIE: ZwolnienieSlotu = const { ZwolnienieSlotu(Cell::new(usize::MAX)) };
+}
+
+// Slot biezacego watku; przy niszczeniu watku (brak TLS) - slot wspolny
+fn slot() -> usize {
+    SLOT.try_with(|s| {
+        if s.get() == usize::MAX {
+            let nowy = ZAJETE
+                .iter()
+                .position(|z| z.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok())
+                .unwrap_or(WSPOLNY_SLOT);
+            ID_WATKOW[nowy].store(unsafe { GetCurrentThreadId() }, Ordering::Relaxed);
+            // SLOT ustawiony przed pierwszym dostepem do ZWOLNIENIE - rejestracja jego destruktora moze
+            // alokowac, a zagniezdzone wywolanie slot() musi juz znalezc przydzielony slot
+            s.set(nowy);
+            let _ = ZWOLNIENIE.try_with(|z| z.0.set(nowy));
+        }
+        s.get()
+    })
+    .unwrap_or(WSPOLNY_SLOT)
+}
+
+// Alokator systemowy z licznikami; instalowany w main.rs przy wlaczonej cesze `alokacje`
+pub struct LicznikAlokacji;
+
+unsafe impl GlobalAlloc for LicznikAlokacji {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc(layout);
+        if !p.is_null() {
+            zapisz_alokacje(layout.size());
+        }
+        p
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let p = System.alloc_zeroed(layout);
+        if !p.is_null() {
+            zapisz_alokacje(layout.size());
+        }
+        p
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        zapisz_zwolnienie(layout.size());
+    }
+
+    // Realokacja liczona jako nowa alokacja i zwolnienie starego bloku
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let p = System.realloc(ptr, layout, new_size);
+        if !p.is_null() {
+            zapisz_alokacje(new_size);
+            zapisz_zwolnienie(layout.size());
+        }
+        p
+    }
+}
+
+fn zapisz_alokacje(bajty: usize) {
+    AKTYWNY.store(true, Ordering::Relaxed);
+    CALOSC.alokacja(bajty as u64);
+    WATKI[slot()].alokacja(bajty as u64);
+}
+
+fn zapisz_zwolnienie(bajty: usize) {
+    CALOSC.zwolnienie(bajty as u64);
+    WATKI[slot()].zwolnienie(bajty as u64);
+}
+
+// Czy program dziala z licznikiem alokacji (cecha `alokacje`)
+pub fn aktywny() -> bool {
+    AKTYWNY.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AlokacjeWatku {
+    // Systemowy identyfikator watku (jak w menedzerze zadan)
+    pub watek: u32,
+    pub liczba: u64,
+    pub zaalokowane: u64,
+    pub zwolnione: u64,
+    pub szczyt: u64,
+}
+
+// Alokacje w mierzonym obszarze; szczyt to najwiekszy przyrost zajetej sterty ponad stan z poczatku
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StatystykiAlokacji {
+    pub liczba: u64,
+    pub zaalokowane: u64,
+    pub zwolnione: u64,
+    pub szczyt: u64,
+    // Tylko watki, ktore alokowaly lub zwalnialy w tym obszarze
+    pub watki: Vec<AlokacjeWatku>,
+}
+
+// Stan licznikow z poczatku obszaru. Obszary nie powinny sie nakladac w czasie -
+// start kolejnego zeruje szczyt poprzedniego
+pub struct Region {
+    calosc: [u64; 3],
+    zajete: u64,
+    watki: Vec<([u64; 3], u64)>,
+}
+
+impl Region {
+    pub fn start() -> Option<Self> {
+        if !aktywny() {
+            return None;
+        }
+        let mut watki = Vec::with_capacity(MAKS_WATKOW);
+        let zajete = CALOSC.zajete();
+        CALOSC.szczyt.store(zajete, Ordering::Relaxed);
+        for licznik in &WATKI {
+            let zajete_watku = licznik.zajete();
+            licznik.szczyt.store(zajete_watku, Ordering::Relaxed);
+            watki.push((licznik.migawka(), zajete_watku));
+        }
+        Some(Region {
+            calosc: CALOSC.migawka(),
+            zajete,
+            watki,
+        })
+    }
+
+    pub fn zakoncz(self) -> StatystykiAlokacji {
+        let [liczba, zaalokowane, zwolnione] = CALOSC.migawka();
+        let watki = WATKI
+            .iter()
+            .zip(&self.watki)
+            .zip(&ID_WATKOW)
+            .filter_map(|((licznik, &(poczatek, zajete)), id)| {
+                let [l, z, w] = licznik.migawka();
+                (l != poczatek[0] || w != poczatek[2]).then(|| AlokacjeWatku {
+                    watek: id.load(Ordering::Relaxed),
+                    liczba: l - poczatek[0],
+                    zaalokowane: z - poczatek[1],
+                    zwolnione: w - poczatek[2],
+                    szczyt: licznik.szczyt.load(Ordering::Relaxed).saturating_sub(zajete),
+                })
+            })
+            .collect();
+        StatystykiAlokacji {
+            liczba: liczba - self.calosc[0],
+            zaalokowane: zaalokowane - self.calosc[1],
+            zwolnione: zwolnione - self.calosc[2],
+            szczyt: CALOSC.szczyt.load(Ordering::Relaxed).saturating_sub(self.zajete),
+            watki,
+        }
+    }
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+
+    #[test]
+    fn sloty_zakonczonych_watkow_wracaja_do_puli() {
+        for _ in 0..2 * MAKS_WATKOW {
+            let slot = std::thread::spawn(slot).join().unwrap();
+            assert!(slot < WSPOLNY_SLOT);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// src/blad.rs
+// Wspolny typ bledu programu - kazdy wariant ma wlasny kod wyjscia w trybie wsadowym
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Blad {
+    // Blad wejscia/wyjscia, np. brak pliku; kontekst mowi czego dotyczyl
+    Io { kontekst: String, zrodlo: io::Error },
+    // Wartosc nie daje sie odczytac jako liczba itp.
+    Parsowanie { pole: String, wartosc: String, zrodlo: String },
+    // Wartosc odczytana, ale spoza dozwolonego zakresu
+    NiepoprawnyParametr { pole: String, wartosc: String, oczekiwano: String },
+    // Blad zglaszany przez biblioteke kryptograficzna
+    Kryptografia(String),
+    // Blad bazy historii wynikow (SQLite)
+    BazaDanych(String),
+}
+
+pub type Wynik<T> = Result<T, Blad>;
+
+impl Blad {
+    pub fn io(kontekst: impl Into<String>, zrodlo: io::Error) -> Self {
+        Blad::Io {
+            kontekst: kontekst.into(),
+            zrodlo,
+        }
+    }
+
+    pub fn parametr(pole: &str, wartosc: impl fmt::Display, oczekiwano: impl Into<String>) -> Self {
+        Blad::NiepoprawnyParametr {
+            pole: pole.to_string(),
+            wartosc: wartosc.to_string(),
+            oczekiwano: oczekiwano.into(),
+        }
+    }
+
+    // 1 jest zarezerwowane dla niezgodnosci w selftescie, 5 dla regresji wzgledem baseline
+    pub fn kod_wyjscia(&self) -> i32 {
+        match self {
+            Blad::Parsowanie { .. } | Blad::NiepoprawnyParametr { .. } => 2,
+            Blad::Io { .. } | Blad::BazaDanych(_) => 3,
+            Blad::Kryptografia(_) => 4,
+        }
+    }
+}
+
+impl fmt::Display for Blad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Blad::Io { kontekst, zrodlo } => write!(f, "blad wejscia/wyjscia ({}): {}", kontekst, zrodlo),
+            Blad::Parsowanie { pole, wartosc, zrodlo } => {
+                write!(f, "nieprawidlowa wartosc '{}' dla pola '{}': {}", wartosc, pole, zrodlo)
+            }
+            Blad::NiepoprawnyParametr { pole, wartosc, oczekiwano } => {
+                write!(f, "niedozwolona wartosc '{}' dla pola '{}', oczekiwano: {}", wartosc, pole, oczekiwano)
+            }
+            Blad::Kryptografia(opis) => write!(f, "blad kryptograficzny: {}", opis),
+            Blad::BazaDanych(opis) => write!(f, "blad bazy historii: {}", opis),
+        }
+    }
+}
+
+impl std::error::Error for Blad {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Blad::Io { zrodlo, .. } => Some(zrodlo),
+            _ => None,
+        }
+    }
+}
+
+impl From<ctr::cipher::InvalidLength> for Blad {
+    fn from(_: ctr::cipher::InvalidLength) -> Self {
+        Blad::Kryptografia("niepoprawna dlugosc klucza lub IV".to_string())
+    }
+}
+
+impl From<rsa::Error> for Blad {
+    fn from(e: rsa::Error) -> Self {
+        Blad::Kryptografia(e.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for Blad {
+    fn from(e: rusqlite::Error) -> Self {
+        Blad::BazaDanych(e.to_string())
+    }
+}
@@ -0,0 +1,263 @@
+// src/cli.rs
+// Argumenty wiersza polecen i wykonanie wybranego polecenia; main.rs tylko zamienia wynik na kod wyjscia.
+// --seed <liczba> ustala ziarno generatora kluczy, IV i tekstow, dzieki czemu dwa uruchomienia przetwarzaja
+// te same dane; selftest uruchamia wektory testowe bez menu (kod wyjscia 1 przy niezgodnosci),
+// scenariusz <plik.toml> wykonuje caly plan pomiarow bez pytan; --porownaj <nazwa> zestawia wynik scenariusza
+// z zapisanym baseline (kod wyjscia 5 przy regresji ponad --prog procent), --zapisz-baseline <nazwa> go zapisuje;
+// wyniki trafiaja do bazy historii (--baza <plik>, --bez-historii wylacza zapis), historia lista|pokaz|trend
+// przeglada zapisane przebiegi; --html <plik> zapisuje wyniki z menu lub scenariusza jako raport HTML z wykresami;
+// --encoding <nazwa> ustala kodowanie plikow scenariusza zamiast wykrywania;
+// korpus <specyfikacja.toml> generuje plik testowy o znanej zawartosci i manifest oczekiwanych wynikow
+use crate::blad::{Blad, Wynik};
+use crate::kodowanie::Kodowanie;
+use crate::wyniki::{FormatRaportu, Raport};
+use crate::{czestosc, historia, korpus, liczenie_slow, porownanie, rsa_aes, scenariusz, selftest, wejscie, wyciek_czasowy};
+
+const UZYCIE: &str = "--seed <liczba>, selftest, scenariusz <plik.toml>, historia ..., korpus <plik.toml>, \
+                      --porownaj/--zapisz-baseline <nazwa>, --prog <procent>, --baza <plik>, --bez-historii, \
+                      --html <plik>, --encoding <kodowanie>";
+
+// Kod wyjscia przy niezgodnosci wektorow selftestu
+pub const KOD_SELFTESTU: i32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Polecenie {
+    // Menu interaktywne
+    Menu,
+    Selftest,
+    Scenariusz(String),
+    Korpus(String),
+    // Argumenty po slowie historia
+    Historia(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Argumenty {
+    pub polecenie: Polecenie,
+    pub seed: Option<u64>,
+    pub zapisz_baseline: Option<String>,
+    pub porownaj_z: Option<String>,
+    // Prog regresji w procentach
+    pub prog: f64,
+    pub baza: String,
+    pub zapis_historii: bool,
+    pub plik_html: Option<String>,
+    pub kodowanie: Option<Kodowanie>,
+}
+
+impl Default for Argumenty {
+    fn default() -> Self {
+        Argumenty {
+            polecenie: Polecenie::Menu,
+            seed: None,
+            zapisz_baseline: None,
+            porownaj_z: None,
+            prog: 5.0,
+            baza: historia::BAZA_DOMYSLNA.to_string(),
+            zapis_historii: true,
+            plik_html: None,
+            kodowanie: None,
+        }
+    }
+}
+
+impl Argumenty {
+    // Argumenty bez nazwy programu. Przy kilku poleceniach pierwszenstwo ma historia, potem korpus,
+    // selftest i scenariusz
+    pub fn parsuj<I: IntoIterator<Item = String>>(argumenty: I) -> Wynik<Self> {
+        let mut wynik = Argumenty::default();
+        let mut selftest = false;
+        let mut scenariusz: Option<String> = None;
+        let mut korpus: Option<String> = None;
+        let mut historia: Option<Vec<String>> = None;
+        let mut args = argumenty.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let wartosc = args.next().unwrap_or_default();
+                    wynik.seed = Some(wartosc.parse().map_err(|e| Blad::Parsowanie {
+                        pole: "--seed".to_string(),
+                        wartosc: wartosc.clone(),
+                        zrodlo: format!("{}", e),
+                    })?);
+                }
+                "--zapisz-baseline" | "--porownaj" => match args.next() {
+                    Some(nazwa) if arg == "--porownaj" => wynik.porownaj_z = Some(nazwa),
+                    Some(nazwa) => wynik.zapisz_baseline = Some(nazwa),
+                    None => return Err(Blad::parametr(&arg, "", "nazwa baseline")),
+                },
+                "--prog" => {
+                    let wartosc = args.next().unwrap_or_default();
+                    match wartosc.parse::<f64>() {
+                        Ok(p) if p >= 0.0 && p.is_finite() => wynik.prog = p,
+                        Ok(p) => return Err(Blad::parametr("--prog", p, "procent >= 0")),
+                        Err(e) => {
+                            return Err(Blad::Parsowanie {
+                                pole: "--prog".to_string(),
+                                wartosc,
+                                zrodlo: format!("{}", e),
+                            })
+                        }
+                    }
+                }
+                "--baza" => wynik.baza = args.next().ok_or_else(|| Blad::parametr("--baza", "", "sciezka do pliku bazy"))?,
+                "--bez-historii" => wynik.zapis_historii = false,
+                "--html" => {
+                    wynik.plik_html = Some(args.next().ok_or_else(|| Blad::parametr("--html", "", "sciezka do pliku .html"))?)
+                }
+                "--encoding" => wynik.kodowanie = Some(Kodowanie::z_nazwy(&args.next().unwrap_or_default())?),
+                // Wszystko po slowie historia nalezy do polecenia historii
+                "historia" => historia = Some(args.by_ref().collect()),
+                "selftest" => selftest = true,
+                "scenariusz" => {
+                    scenariusz =
+                        Some(args.next().ok_or_else(|| Blad::parametr("scenariusz", "", "sciezka do pliku .toml"))?)
+                }
+                "korpus" => {
+                    korpus = Some(args.next().ok_or_else(|| Blad::parametr("korpus", "", "sciezka do specyfikacji .toml"))?)
+                }
+                _ => return Err(Blad::parametr("argument", arg, UZYCIE)),
+            }
+        }
+
+        wynik.polecenie = match (historia, korpus, selftest, scenariusz) {
+            (Some(argumenty), _, _, _) => Polecenie::Historia(argumenty),
+            (None, Some(plik), _, _) => Polecenie::Korpus(plik),
+            (None, None, true, _) => Polecenie::Selftest,
+            (None, None, false, Some(plik)) => Polecenie::Scenariusz(plik),
+            (None, None, false, None) => Polecenie::Menu,
+        };
+        if wynik.polecenie == Polecenie::Menu {
+            if wynik.zapisz_baseline.is_some() || wynik.porownaj_z.is_some() {
+                return Err(Blad::parametr("argument", "--porownaj/--zapisz-baseline", "razem z scenariusz <plik.toml>"));
+            }
+            if wynik.kodowanie.is_some() {
+                return Err(Blad::parametr("argument", "--encoding", "razem z scenariusz <plik.toml>"));
+            }
+        }
+        Ok(wynik)
+    }
+
+    // Wykonuje polecenie; Ok z kodem wyjscia: 0, KOD_SELFTESTU albo porownanie::KOD_REGRESJI
+    pub fn uruchom(&self) -> Wynik<i32> {
+        match &self.polecenie {
+            Polecenie::Historia(argumenty) => historia::polecenie(&self.baza, argumenty).map(|_| 0),
+            Polecenie::Korpus(plik) => korpus::uruchom_korpus(plik, self.seed).map(|_| 0),
+            Polecenie::Selftest => Ok(if selftest::selftest(true) { 0 } else { KOD_SELFTESTU }),
+            Polecenie::Scenariusz(plik) => self.scenariusz(plik),
+            Polecenie::Menu => self.menu(),
+        }
+    }
+
+    // Nieudany zapis historii nie uniewaznia wykonanego pomiaru - tylko ostrzezenie
+    fn zapisz_w_historii(&self, raport: &Raport) {
+        if self.zapis_historii {
+            match historia::zapisz_przebieg(&self.baza, raport) {
+                Ok(id) => eprintln!("Przebieg zapisany w historii {} (id {})", self.baza, id),
+                Err(blad) => eprintln!("Ostrzezenie: nie zapisano historii: {}", blad),
+            }
+        }
+    }
+
+    fn scenariusz(&self, plik: &str) -> Wynik<i32> {
+        let raport = scenariusz::uruchom_scenariusz(plik, self.seed, self.kodowanie)?;
+        self.zapisz_w_historii(&raport);
+        self.zapisz_html(&raport)?;
+
+        // Porownanie przed zapisem, zeby ta sama nazwa mogla sluzyc jako kroczacy baseline
+        let mut regresje = 0;
+        if let Some(nazwa) = &self.porownaj_z {
+            let baseline = porownanie::wczytaj_baseline(nazwa)?;
+            let wynik = porownanie::porownaj(&baseline, &raport, nazwa, self.prog);
+            eprint!("{}", wynik.jako_tekst());
+            regresje = wynik.regresje();
+        }
+        if let Some(nazwa) = &self.zapisz_baseline {
+            let sciezka = porownanie::zapisz_baseline(nazwa, &raport)?;
+            eprintln!("Baseline '{}' zapisany do {}", nazwa, sciezka.display());
+        }
+        Ok(if regresje > 0 { porownanie::KOD_REGRESJI } else { 0 })
+    }
+
+    // --html: dodatkowa kopia raportu jako HTML, niezaleznie od formatu wyjscia scenariusza
+    fn zapisz_html(&self, raport: &Raport) -> Wynik<()> {
+        if let Some(plik) = &self.plik_html {
+            std::fs::write(plik, raport.formatuj(FormatRaportu::Html)?)
+                .map_err(|e| Blad::io(format!("zapis raportu {}", plik), e))?;
+            eprintln!("Raport HTML zapisany do {}", plik);
+        }
+        Ok(())
+    }
+
+    // Raport z trybu konsolowego: historia i opcjonalnie plik HTML
+    fn zapisz_raport_konsoli(&self, raport: Raport) -> Wynik<i32> {
+        self.zapisz_w_historii(&raport);
+        self.zapisz_html(&raport)?;
+        Ok(0)
+    }
+
+    fn menu(&self) -> Wynik<i32> {
+        let wybor: i32 = wejscie::zapytaj(
+            "Wybierz opcje:\n\
+             1. Liczenie słów w pliku\n\
+             2. Szyfrowanie RSA/AES\n\
+             3. Test poprawnosci szyfrow (wektory NIST/RFC)\n\
+             4. Analiza wycieku czasowego (dudect)\n\
+             5. Histogram czestosci slow",
+            "opcja menu",
+            |&x| if (1..=5).contains(&x) { Ok(()) } else { Err("liczba 1-5".to_string()) },
+        )?;
+        match wybor {
+            1 => self.zapisz_raport_konsoli(liczenie_slow::liczenie_slow()?),
+            2 => self.zapisz_raport_konsoli(rsa_aes::szyfrowanie(self.seed)?),
+            3 => Ok(if selftest::selftest(true) { 0 } else { KOD_SELFTESTU }),
+            4 => self.zapisz_raport_konsoli(wyciek_czasowy::analiza_wycieku(self.seed)?),
+            _ => self.zapisz_raport_konsoli(czestosc::czestosc_slow()?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+
+    fn parsuj(argumenty: &[&str]) -> Wynik<Argumenty> {
+        Argumenty::parsuj(argumenty.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn scenariusz_z_opcjami() {
+        let a = parsuj(&["--seed", "7", "scenariusz", "s.toml", "--porownaj", "glowny", "--prog", "2.5", "--bez-historii"])
+            .unwrap();
+        assert_eq!(a.polecenie, Polecenie::Scenariusz("s.toml".to_string()));
+        assert_eq!(a.seed, Some(7));
+        assert_eq!(a.porownaj_z.as_deref(), Some("glowny"));
+        assert_eq!(a.prog, 2.5);
+        assert!(!a.zapis_historii);
+    }
+
+    #[test]
+    fn historia_przejmuje_reszte_argumentow() {
+        let a = parsuj(&["--baza", "h.sqlite", "historia", "trend", "czas", "--benchmark", "x"]).unwrap();
+        assert_eq!(a.baza, "h.sqlite");
+        assert_eq!(
+            a.polecenie,
+            Polecenie::Historia(["trend", "czas", "--benchmark", "x"].map(str::to_string).to_vec())
+        );
+    }
+
+    #[test]
+    fn bledne_argumenty() {
+        for argumenty in [
+            &["--seed", "x"][..],
+            &["--prog", "-1"],
+            &["--html"],
+            &["scenariusz"],
+            &["--nieznany"],
+            &["--porownaj", "glowny"],
+            &["--encoding", "utf-8"],
+        ] {
+            assert!(parsuj(argumenty).is_err(), "{:?}", argumenty);
+        }
+    }
+}
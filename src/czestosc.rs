@@ -0,0 +1,337 @@
+// src/czestosc.rs
+// Histogram czestosci slow: tokenizacja Unicode (granice slow UAX #29), opcjonalnie male litery
+// i lista stop-slow; kazdy fragment tekstu liczony do wlasnej mapy, mapy scalane (map-reduce)
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::blad::{Blad, Wynik};
+use crate::kompresja;
+use crate::liczenie_slow::{sprawdz_watki, Implementacja, ROZMIAR_FRAGMENTU};
+use crate::srodowisko::Srodowisko;
+use crate::wejscie::{co_najmniej, jedna_z, wczytaj_linie, zapytaj};
+use crate::wyniki::{Raport, RekordWyniku};
+use crate::zasoby::{Metrics, Pomiar};
+
+// Silniki obslugujace histogram
+pub const IMPLEMENTACJE: [Implementacja; 3] = [Implementacja::Sekwencyjny, Implementacja::ArcThreads, Implementacja::Rayon];
+
+#[derive(Debug, Clone)]
+pub struct UstawieniaCzestosci {
+    pub male_litery: bool,
+    // Slowa pomijane w histogramie (juz po zamianie na male litery, jesli wlaczona)
+    pub stop_slowa: HashSet<String>,
+    // Dlugosc listy najczestszych slow
+    pub top: usize,
+}
+
+impl UstawieniaCzestosci {
+    pub fn nowe(male_litery: bool, stop_slowa: impl IntoIterator<Item = String>, top: usize) -> Self {
+        let stop_slowa = stop_slowa
+            .into_iter()
+            .map(|s| if male_litery { s.to_lowercase() } else { s })
+            .collect();
+        UstawieniaCzestosci {
+            male_litery,
+            stop_slowa,
+            top,
+        }
+    }
+}
+
+impl Default for UstawieniaCzestosci {
+    fn default() -> Self {
+        UstawieniaCzestosci::nowe(true, Vec::new(), 20)
+    }
+}
+
+// Plik stop-slow: slowa rozdzielone bialymi znakami, linie od '#' to komentarze
+pub fn wczytaj_stop_slowa(sciezka: &str) -> Wynik<Vec<String>> {
+    let tekst = std::fs::read_to_string(sciezka).map_err(|e| Blad::io(format!("odczyt stop-slow {}", sciezka), e))?;
+    Ok(tekst
+        .lines()
+        .filter(|linia| !linia.trim_start().starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CzestoscSlowa {
+    pub slowo: String,
+    pub liczba: u64,
+}
+
+// Podsumowanie histogramu; pelna tabela czestosci zostaje w pamieci, do raportu trafia top-N
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Histogram {
+    // Slowa policzone w histogramie (bez stop-slow)
+    pub tokeny: u64,
+    // Rozmiar slownika: liczba roznych slow
+    pub slownik: usize,
+    // Slowa wystepujace dokladnie raz
+    pub hapaksy: usize,
+    // Wystapienia stop-slow
+    pub pominiete: u64,
+    pub top: Vec<CzestoscSlowa>,
+}
+
+// Wynik czesciowy jednego watku / fragmentu
+#[derive(Debug, Default)]
+struct Czestosci {
+    slowa: HashMap<String, u64>,
+    pominiete: u64,
+}
+
+impl Czestosci {
+    fn dodaj_fragment(&mut self, fragment: &str, ustawienia: &UstawieniaCzestosci) {
+        for slowo in fragment.unicode_words() {
+            // Bez kopiowania, gdy slowo nie ma wielkich liter albo zamiana jest wylaczona
+            let slowo: Cow<str> = if ustawienia.male_litery && slowo.chars().any(char::is_uppercase) {
+                Cow::Owned(slowo.to_lowercase())
+            } else {
+                Cow::Borrowed(slowo)
+            };
+            if ustawienia.stop_slowa.contains(slowo.as_ref()) {
+                self.pominiete += 1;
+            } else if let Some(liczba) = self.slowa.get_mut(slowo.as_ref()) {
+                *liczba += 1;
+            } else {
+                self.slowa.insert(slowo.into_owned(), 1);
+            }
+        }
+    }
+
+    // Mniejsza mapa wlewana do wiekszej
+    fn scal(mut self, mut inne: Czestosci) -> Czestosci {
+        if self.slowa.len() < inne.slowa.len() {
+            std::mem::swap(&mut self, &mut inne);
+        }
+        for (slowo, liczba) in inne.slowa {
+            *self.slowa.entry(slowo).or_insert(0) += liczba;
+        }
+        self.pominiete += inne.pominiete;
+        self
+    }
+
+    fn podsumuj(self, top: usize) -> Histogram {
+        let tokeny = self.slowa.values().sum();
+        let hapaksy = self.slowa.values().filter(|&&n| n == 1).count();
+        let slownik = self.slowa.len();
+        let mut slowa: Vec<(String, u64)> = self.slowa.into_iter().collect();
+        // Rowne czestosci alfabetycznie - ta sama lista niezaleznie od kolejnosci scalania
+        slowa.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        slowa.truncate(top);
+        Histogram {
+            tokeny,
+            slownik,
+            hapaksy,
+            pominiete: self.pominiete,
+            top: slowa.into_iter().map(|(slowo, liczba)| CzestoscSlowa { slowo, liczba }).collect(),
+        }
+    }
+}
+
+// Tekst pliku (rozpakowanego, gdy skompresowany) i czas dekompresji; bajty spoza UTF-8 zastapione
+// znakiem U+FFFD (nie sa czescia zadnego slowa)
+fn tekst_pliku(sciezka: &str) -> Wynik<(String, Option<f64>)> {
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka)?;
+    let tekst = String::from_utf8(buffer).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
+    Ok((tekst, dekompresja))
+}
+
+// Fragmenty bez zakladki: koniec przesuniety do najblizszego bialego znaku, wiec zadne slowo
+// nie jest rozciete, a kazde trafia do dokladnie jednego fragmentu
+pub fn podziel_tekst(tekst: &str, rozmiar: usize) -> Vec<(usize, usize)> {
+    let mut fragmenty = Vec::new();
+    let mut poz = 0;
+    while poz < tekst.len() {
+        let mut koniec = (poz + rozmiar.max(1)).min(tekst.len());
+        while !tekst.is_char_boundary(koniec) {
+            koniec += 1;
+        }
+        koniec = match tekst[koniec..].find(char::is_whitespace) {
+            Some(p) => koniec + p,
+            None => tekst.len(),
+        };
+        fragmenty.push((poz, koniec));
+        poz = koniec;
+    }
+    fragmenty
+}
+
+fn zakoncz(pomiar: Pomiar, czestosci: Czestosci, ustawienia: &UstawieniaCzestosci, dekompresja: Option<f64>) -> Metrics {
+    let histogram = czestosci.podsumuj(ustawienia.top);
+    let mut metrics = pomiar.zakoncz(i32::try_from(histogram.tokeny).unwrap_or(i32::MAX));
+    metrics.histogram = Some(histogram);
+    metrics.czas_dekompresji = dekompresja;
+    metrics
+}
+
+pub fn czestosc_sekwencyjnie(sciezka_pliku: &str, ustawienia: &UstawieniaCzestosci) -> Wynik<Metrics> {
+    let pomiar = Pomiar::start();
+
+    let (tekst, dekompresja) = tekst_pliku(sciezka_pliku)?;
+    let mut czestosci = Czestosci::default();
+    for (start, end) in podziel_tekst(&tekst, ROZMIAR_FRAGMENTU) {
+        czestosci.dodaj_fragment(&tekst[start..end], ustawienia);
+    }
+
+    Ok(zakoncz(pomiar, czestosci, ustawienia, dekompresja))
+}
+
+// Kazdy watek liczy ciagly zakres fragmentow do wlasnej mapy; scalanie w watku glownym
+pub fn czestosc_arc_threads(
+    sciezka_pliku: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaCzestosci,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let (tekst, dekompresja) = tekst_pliku(sciezka_pliku)?;
+    let tekst = Arc::new(tekst);
+    let fragmenty = podziel_tekst(&tekst, ROZMIAR_FRAGMENTU);
+    let chunk_size = fragmenty.len().div_ceil(liczba_watkow.max(1)).max(1);
+    let wspolne = Arc::new(ustawienia.clone());
+
+    let watki: Vec<_> = fragmenty
+        .chunks(chunk_size)
+        .map(|zakres| {
+            let tekst = Arc::clone(&tekst);
+            let ustawienia = Arc::clone(&wspolne);
+            let zakres = zakres.to_vec();
+            thread::spawn(move || {
+                let mut czestosci = Czestosci::default();
+                for (start, end) in zakres {
+                    czestosci.dodaj_fragment(&tekst[start..end], &ustawienia);
+                }
+                czestosci
+            })
+        })
+        .collect();
+    let czestosci = watki
+        .into_iter()
+        .map(|watek| watek.join().expect("watek histogramu zakonczyl sie panika"))
+        .fold(Czestosci::default(), Czestosci::scal);
+
+    Ok(zakoncz(pomiar, czestosci, ustawienia, dekompresja))
+}
+
+// fold tworzy mape na kazdy kawalek pracy rayon, reduce scala je parami
+pub fn czestosc_rayon(sciezka_pliku: &str, liczba_watkow: usize, ustawienia: &UstawieniaCzestosci) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(liczba_watkow)
+        .build()
+        .map_err(|e| Blad::parametr("liczba watkow rayon", liczba_watkow, e.to_string()))?;
+    let (tekst, dekompresja) = tekst_pliku(sciezka_pliku)?;
+    let fragmenty = podziel_tekst(&tekst, ROZMIAR_FRAGMENTU);
+    let czestosci = pool.install(|| {
+        fragmenty
+            .par_iter()
+            .fold(Czestosci::default, |mut czestosci, &(start, end)| {
+                czestosci.dodaj_fragment(&tekst[start..end], ustawienia);
+                czestosci
+            })
+            .reduce(Czestosci::default, Czestosci::scal)
+    });
+
+    Ok(zakoncz(pomiar, czestosci, ustawienia, dekompresja))
+}
+
+pub fn zmierz(
+    implementacja: Implementacja,
+    sciezka_pliku: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaCzestosci,
+) -> Wynik<Metrics> {
+    match implementacja {
+        Implementacja::Sekwencyjny => czestosc_sekwencyjnie(sciezka_pliku, ustawienia),
+        Implementacja::ArcThreads => czestosc_arc_threads(sciezka_pliku, liczba_watkow, ustawienia),
+        Implementacja::Rayon => czestosc_rayon(sciezka_pliku, liczba_watkow, ustawienia),
+        inna => Err(Blad::parametr("implementacja histogramu", inna.nazwa(), "sekwencyjny, arc_threads lub rayon")),
+    }
+}
+
+// Parametry rekordu histogramu (wspolne dla scenariusza i konsoli)
+pub fn parametry(sciezka_pliku: &str, liczba_watkow: usize, ustawienia: &UstawieniaCzestosci) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("plik".to_string(), sciezka_pliku.to_string()),
+        ("watki".to_string(), liczba_watkow.to_string()),
+        ("male_litery".to_string(), if ustawienia.male_litery { "tak" } else { "nie" }.to_string()),
+        ("stop_slowa".to_string(), ustawienia.stop_slowa.len().to_string()),
+    ])
+}
+
+// Tryb konsolowy: plik z pulpitu, wszystkie trzy silniki i lista najczestszych slow
+pub fn czestosc_slow() -> Wynik<Raport> {
+    // Ustawienie kodowania UTF-8 dla konsoli
+    unsafe {
+        winapi::um::wincon::SetConsoleOutputCP(65001);
+    }
+
+    let nazwa_uzytkownika = wczytaj_linie("Podaj nazwe uzytkownika: ")?;
+    let liczba_watkow: usize = zapytaj("Podaj ilosc watkow: ", "ilosc watkow", co_najmniej(1))?;
+    let top: usize = zapytaj("Ile najczestszych slow pokazac: ", "dlugosc listy", co_najmniej(1))?;
+    let male_litery: String = zapytaj("Zamienic na male litery? (t/n): ", "male litery", jedna_z(&["t", "n"]))?;
+    let stop = wczytaj_linie("Podaj sciezke pliku stop-slow (pusta linia - bez stop-slow): ")?;
+    let stop_slowa = if stop.is_empty() { Vec::new() } else { wczytaj_stop_slowa(&stop)? };
+
+    let pelna_sciezka = |plik: &str| format!("C:\\Users\\{}\\Desktop\\{}", nazwa_uzytkownika, plik);
+    let plik: String = zapytaj("Podaj sciezke do pliku: ", "sciezka do pliku", |plik: &String| {
+        if Path::new(&pelna_sciezka(plik)).is_file() {
+            Ok(())
+        } else {
+            Err(format!("istniejacy plik ({} nie istnieje)", pelna_sciezka(plik)))
+        }
+    })?;
+    let sciezka_pliku = pelna_sciezka(&plik);
+    let ustawienia = UstawieniaCzestosci::nowe(male_litery == "t", stop_slowa, top);
+
+    let mut rekordy = Vec::new();
+    for implementacja in IMPLEMENTACJE {
+        let watki = if implementacja.rownolegla() { liczba_watkow } else { 1 };
+        let metrics = zmierz(implementacja, &sciezka_pliku, watki, &ustawienia)?;
+        println!(
+            "{}: {} slow (czas: {:.2}s, CPU: {:.1}%, RAM: {:+} B, szczyt {} B)",
+            implementacja.nazwa(),
+            metrics.count,
+            metrics.czas,
+            metrics.cpu_usage,
+            metrics.ram_usage,
+            metrics.ram_szczyt
+        );
+        rekordy.push(RekordWyniku {
+            benchmark: "czestosc_slow".to_string(),
+            implementacja: implementacja.nazwa().to_string(),
+            parametry: parametry(&sciezka_pliku, watki, &ustawienia),
+            powtorzenie: 1,
+            metrics,
+        });
+    }
+
+    if let Some(h) = rekordy.first().and_then(|r| r.metrics.histogram.as_ref()) {
+        println!(
+            "\nSlowa: {}, slownik: {}, hapaksy: {}, pominiete stop-slowa: {}",
+            h.tokeny, h.slownik, h.hapaksy, h.pominiete
+        );
+        for (i, s) in h.top.iter().enumerate() {
+            println!("{:>4}. {:<24} {:>10}", i + 1, s.slowo, s.liczba);
+        }
+    }
+    Ok(Raport {
+        nazwa: "czestosc_slow (konsola)".to_string(),
+        seed: None,
+        srodowisko: Some(Srodowisko::wykryj()),
+        rekordy,
+    })
+}
@@ -0,0 +1,574 @@
+// src/historia.rs
+// Historia wynikow w lokalnej bazie SQLite: zapis kazdego przebiegu i polecenia do przegladania
+use rusqlite::{params, params_from_iter, Connection};
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use crate::blad::{Blad, Wynik};
+use crate::srodowisko::Srodowisko;
+use crate::wyniki::{FormatRaportu, Raport, RekordWyniku};
+use crate::alokacje::StatystykiAlokacji;
+use crate::zasoby::{Metrics, RozkladPracy};
+
+pub const BAZA_DOMYSLNA: &str = "historia.sqlite";
+
+const SCHEMAT: &str = "
+CREATE TABLE IF NOT EXISTS przebiegi (
+    id          INTEGER PRIMARY KEY,
+    nazwa       TEXT NOT NULL,
+    znacznik    INTEGER NOT NULL,
+    seed        INTEGER,
+    rewizja     TEXT NOT NULL,
+    host        TEXT NOT NULL,
+    srodowisko  TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS rekordy (
+    id            INTEGER PRIMARY KEY,
+    przebieg      INTEGER NOT NULL REFERENCES przebiegi(id) ON DELETE CASCADE,
+    benchmark     TEXT NOT NULL,
+    implementacja TEXT NOT NULL,
+    parametry     TEXT NOT NULL,
+    seria         TEXT NOT NULL,
+    powtorzenie   INTEGER NOT NULL,
+    count         INTEGER NOT NULL,
+    czas          REAL,
+    cpu_usage     REAL,
+    ram_usage     INTEGER NOT NULL,
+    cpu_user      REAL,
+    cpu_system    REAL,
+    cpu_usage_abs REAL,
+    ram_szczyt    INTEGER,
+    alokacje_liczba INTEGER,
+    alokacje_bajty INTEGER,
+    alokacje_zwolnione INTEGER,
+    alokacje_szczyt INTEGER,
+    alokacje_watki TEXT,
+    nierownowaga REAL,
+    rozklad_watkow TEXT,
+    histogram TEXT,
+    trafienia TEXT,
+    odleglosci TEXT,
+    czas_dekompresji REAL,
+    weryfikacja   TEXT,
+    wyciek        TEXT
+);
+CREATE INDEX IF NOT EXISTS rekordy_benchmark ON rekordy(benchmark, implementacja);
+CREATE INDEX IF NOT EXISTS rekordy_przebieg ON rekordy(przebieg);
+";
+
+// Kolumny dodane do tabeli rekordy po pierwszej wersji schematu
+const NOWE_KOLUMNY: [(&str, &str); 17] = [
+    ("cpu_user", "REAL"),
+    ("cpu_system", "REAL"),
+    ("cpu_usage_abs", "REAL"),
+    ("ram_szczyt", "INTEGER"),
+    ("alokacje_liczba", "INTEGER"),
+    ("alokacje_bajty", "INTEGER"),
+    ("alokacje_zwolnione", "INTEGER"),
+    ("alokacje_szczyt", "INTEGER"),
+    ("alokacje_watki", "TEXT"),
+    ("nierownowaga", "REAL"),
+    ("rozklad_watkow", "TEXT"),
+    ("histogram", "TEXT"),
+    ("trafienia", "TEXT"),
+    ("odleglosci", "TEXT"),
+    ("czas_dekompresji", "REAL"),
+    ("weryfikacja", "TEXT"),
+    ("wyciek", "TEXT"),
+];
+
+// Kolumny rekordy, ktorych trend mozna pokazac
+const METRYKI: [&str; 13] = [
+    "czas",
+    "czas_dekompresji",
+    "cpu_usage",
+    "cpu_usage_abs",
+    "cpu_user",
+    "cpu_system",
+    "ram_usage",
+    "ram_szczyt",
+    "alokacje_liczba",
+    "alokacje_bajty",
+    "alokacje_szczyt",
+    "nierownowaga",
+    "count",
+];
+
+// Zawezenie zapytan; puste pola nie filtruja. Daty w formacie RRRR-MM-DD (UTC, wlacznie)
+#[derive(Debug, Default, Clone)]
+pub struct Filtr {
+    pub benchmark: Option<String>,
+    pub implementacja: Option<String>,
+    pub od: Option<String>,
+    pub do_dnia: Option<String>,
+}
+
+impl Filtr {
+    // Warunek SQL na tabelach p (przebiegi) i r (rekordy) oraz jego parametry
+    fn warunek(&self) -> (String, Vec<String>) {
+        let mut warunki = vec!["1 = 1".to_string()];
+        let mut wartosci = Vec::new();
+        let pola = [
+            ("r.benchmark = ?", &self.benchmark),
+            ("r.implementacja = ?", &self.implementacja),
+            ("date(p.znacznik, 'unixepoch') >= ?", &self.od),
+            ("date(p.znacznik, 'unixepoch') <= ?", &self.do_dnia),
+        ];
+        for (warunek, wartosc) in pola {
+            if let Some(w) = wartosc {
+                warunki.push(warunek.to_string());
+                wartosci.push(w.clone());
+            }
+        }
+        (warunki.join(" AND "), wartosci)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Przebieg {
+    pub id: i64,
+    pub nazwa: String,
+    pub data: String,
+    pub seed: Option<u64>,
+    pub rewizja: String,
+    pub host: String,
+    pub liczba_rekordow: usize,
+}
+
+// Srednia metryki jednej serii w jednym przebiegu
+#[derive(Debug, Clone)]
+pub struct PunktTrendu {
+    pub seria: String,
+    pub przebieg: i64,
+    pub data: String,
+    pub rewizja: String,
+    pub srednia: f64,
+    pub probki: usize,
+}
+
+pub struct Historia {
+    polaczenie: Connection,
+}
+
+impl Historia {
+    pub fn otworz(sciezka: &str) -> Wynik<Self> {
+        let polaczenie = Connection::open(sciezka)?;
+        polaczenie.execute_batch("PRAGMA foreign_keys = ON;")?;
+        polaczenie.execute_batch(SCHEMAT)?;
+        // Bazy zalozone starsza wersja programu dostaja brakujace kolumny (NULL w starych wierszach)
+        let istniejace: Vec<String> = polaczenie
+            .prepare("SELECT name FROM pragma_table_info('rekordy')")?
+            .query_map([], |w| w.get(0))?
+            .collect::<Result<_, _>>()?;
+        for (kolumna, typ) in NOWE_KOLUMNY {
+            if !istniejace.iter().any(|k| k == kolumna) {
+                polaczenie.execute_batch(&format!("ALTER TABLE rekordy ADD COLUMN {} {};", kolumna, typ))?;
+            }
+        }
+        Ok(Historia { polaczenie })
+    }
+
+    // Zapisuje caly raport w jednej transakcji; zwraca identyfikator przebiegu.
+    // Raport bez odcisku srodowiska dostaje odcisk biezacego hosta
+    pub fn zapisz(&mut self, raport: &Raport) -> Wynik<i64> {
+        let srodowisko = raport.srodowisko.clone().unwrap_or_else(Srodowisko::wykryj);
+        let znacznik = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let srodowisko_json =
+            serde_json::to_string(&srodowisko).map_err(|e| Blad::io("serializacja srodowiska", e.into()))?;
+
+        let tx = self.polaczenie.transaction()?;
+        tx.execute(
+            "INSERT INTO przebiegi (nazwa, znacznik, seed, rewizja, host, srodowisko) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                raport.nazwa,
+                znacznik,
+                // SQLite przechowuje liczby ze znakiem - ziarno zapisywane bit w bit
+                raport.seed.map(|s| s as i64),
+                srodowisko.rewizja,
+                srodowisko.host,
+                srodowisko_json
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        {
+            let mut wstaw = tx.prepare(
+                "INSERT INTO rekordy (przebieg, benchmark, implementacja, parametry, seria, powtorzenie, count, czas, cpu_usage, ram_usage,
+                                      cpu_user, cpu_system, cpu_usage_abs, ram_szczyt,
+                                      alokacje_liczba, alokacje_bajty, alokacje_zwolnione, alokacje_szczyt, alokacje_watki,
+                                      nierownowaga, rozklad_watkow, histogram, trafienia, odleglosci, czas_dekompresji, weryfikacja, wyciek)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27)",
+            )?;
+            for r in &raport.rekordy {
+                let parametry =
+                    serde_json::to_string(&r.parametry).map_err(|e| Blad::io("serializacja parametrow", e.into()))?;
+                let alokacje = r.metrics.alokacje.as_ref();
+                let watki = alokacje
+                    .map(|a| serde_json::to_string(&a.watki))
+                    .transpose()
+                    .map_err(|e| Blad::io("serializacja alokacji", e.into()))?;
+                let rozklad = r.metrics.rozklad.as_ref();
+                let rozklad_watkow = rozklad
+                    .map(|rozklad| serde_json::to_string(&rozklad.watki))
+                    .transpose()
+                    .map_err(|e| Blad::io("serializacja rozkladu pracy", e.into()))?;
+                let histogram = r
+                    .metrics
+                    .histogram
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .map_err(|e| Blad::io("serializacja histogramu", e.into()))?;
+                let trafienia = r
+                    .metrics
+                    .trafienia
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .map_err(|e| Blad::io("serializacja pozycji trafien", e.into()))?;
+                let odleglosci = r
+                    .metrics
+                    .odleglosci
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .map_err(|e| Blad::io("serializacja dopasowan rozmytych", e.into()))?;
+                let weryfikacja = r
+                    .metrics
+                    .weryfikacja
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .map_err(|e| Blad::io("serializacja weryfikacji", e.into()))?;
+                let wyciek = r
+                    .metrics
+                    .wyciek
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .map_err(|e| Blad::io("serializacja analizy wycieku", e.into()))?;
+                wstaw.execute(params![
+                    id,
+                    r.benchmark,
+                    r.implementacja,
+                    parametry,
+                    r.klucz(),
+                    r.powtorzenie as i64,
+                    r.metrics.count,
+                    r.metrics.czas,
+                    r.metrics.cpu_usage,
+                    r.metrics.ram_usage,
+                    r.metrics.cpu_user,
+                    r.metrics.cpu_system,
+                    r.metrics.cpu_usage_abs,
+                    r.metrics.ram_szczyt as i64,
+                    alokacje.map(|a| a.liczba as i64),
+                    alokacje.map(|a| a.zaalokowane as i64),
+                    alokacje.map(|a| a.zwolnione as i64),
+                    alokacje.map(|a| a.szczyt as i64),
+                    watki,
+                    rozklad.map(|rozklad| rozklad.nierownowaga),
+                    rozklad_watkow,
+                    histogram,
+                    trafienia,
+                    odleglosci,
+                    r.metrics.czas_dekompresji,
+                    weryfikacja,
+                    wyciek
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(id)
+    }
+
+    // Przebiegi zawierajace co najmniej jeden rekord pasujacy do filtra, od najnowszego
+    pub fn przebiegi(&self, filtr: &Filtr) -> Wynik<Vec<Przebieg>> {
+        let (warunek, wartosci) = filtr.warunek();
+        let mut zapytanie = self.polaczenie.prepare(&format!(
+            "SELECT p.id, p.nazwa, datetime(p.znacznik, 'unixepoch'), p.seed, p.rewizja, p.host, COUNT(r.id)
+             FROM przebiegi p JOIN rekordy r ON r.przebieg = p.id
+             WHERE {}
+             GROUP BY p.id ORDER BY p.znacznik DESC, p.id DESC",
+            warunek
+        ))?;
+        let wiersze = zapytanie.query_map(params_from_iter(wartosci), |w| {
+            Ok(Przebieg {
+                id: w.get(0)?,
+                nazwa: w.get(1)?,
+                data: w.get(2)?,
+                seed: w.get::<_, Option<i64>>(3)?.map(|s| s as u64),
+                rewizja: w.get(4)?,
+                host: w.get(5)?,
+                liczba_rekordow: w.get::<_, i64>(6)? as usize,
+            })
+        })?;
+        Ok(wiersze.collect::<Result<_, _>>()?)
+    }
+
+    // Odtwarza raport zapisanego przebiegu
+    pub fn raport(&self, id: i64) -> Wynik<Raport> {
+        let (nazwa, seed, srodowisko) = self
+            .polaczenie
+            .query_row("SELECT nazwa, seed, srodowisko FROM przebiegi WHERE id = ?1", [id], |w| {
+                Ok((w.get::<_, String>(0)?, w.get::<_, Option<i64>>(1)?, w.get::<_, String>(2)?))
+            })
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Blad::parametr("przebieg", id, "identyfikator z 'historia lista'"),
+                e => e.into(),
+            })?;
+        let mut zapytanie = self.polaczenie.prepare(
+            "SELECT benchmark, implementacja, parametry, powtorzenie, count, czas, cpu_usage, ram_usage,
+                    cpu_user, cpu_system, cpu_usage_abs, ram_szczyt,
+                    alokacje_liczba, alokacje_bajty, alokacje_zwolnione, alokacje_szczyt, alokacje_watki,
+                    nierownowaga, rozklad_watkow, histogram, trafienia, odleglosci, czas_dekompresji, weryfikacja, wyciek
+             FROM rekordy WHERE przebieg = ?1 ORDER BY id",
+        )?;
+        let wiersze = zapytanie.query_map([id], |w| {
+            Ok((
+                w.get::<_, String>(0)?,
+                w.get::<_, String>(1)?,
+                w.get::<_, String>(2)?,
+                w.get::<_, i64>(3)?,
+                Metrics {
+                    count: w.get(4)?,
+                    czas: w.get::<_, Option<f64>>(5)?.unwrap_or(f64::NAN),
+                    cpu_usage: w.get::<_, Option<f64>>(6)?.unwrap_or(f64::NAN),
+                    ram_usage: w.get(7)?,
+                    cpu_user: w.get::<_, Option<f64>>(8)?.unwrap_or_default(),
+                    cpu_system: w.get::<_, Option<f64>>(9)?.unwrap_or_default(),
+                    cpu_usage_abs: w.get::<_, Option<f64>>(10)?.unwrap_or_default(),
+                    ram_szczyt: w.get::<_, Option<i64>>(11)?.unwrap_or_default() as u64,
+                    alokacje: match w.get::<_, Option<i64>>(12)? {
+                        Some(liczba) => Some(StatystykiAlokacji {
+                            liczba: liczba as u64,
+                            zaalokowane: w.get::<_, i64>(13)? as u64,
+                            zwolnione: w.get::<_, i64>(14)? as u64,
+                            szczyt: w.get::<_, i64>(15)? as u64,
+                            // Nieczytelna lista watkow nie przekresla sum - zostaje pusta
+                            watki: w
+                                .get::<_, Option<String>>(16)?
+                                .and_then(|j| serde_json::from_str(&j).ok())
+                                .unwrap_or_default(),
+                        }),
+                        None => None,
+                    },
+                    rozklad: match w.get::<_, Option<f64>>(17)? {
+                        Some(nierownowaga) => Some(RozkladPracy {
+                            watki: w
+                                .get::<_, Option<String>>(18)?
+                                .and_then(|j| serde_json::from_str(&j).ok())
+                                .unwrap_or_default(),
+                            nierownowaga,
+                        }),
+                        None => None,
+                    },
+                    histogram: w
+                        .get::<_, Option<String>>(19)?
+                        .and_then(|j| serde_json::from_str(&j).ok()),
+                    trafienia: w
+                        .get::<_, Option<String>>(20)?
+                        .and_then(|j| serde_json::from_str(&j).ok()),
+                    odleglosci: w
+                        .get::<_, Option<String>>(21)?
+                        .and_then(|j| serde_json::from_str(&j).ok()),
+                    czas_dekompresji: w.get(22)?,
+                    weryfikacja: w
+                        .get::<_, Option<String>>(23)?
+                        .and_then(|j| serde_json::from_str(&j).ok()),
+                    wyciek: w
+                        .get::<_, Option<String>>(24)?
+                        .and_then(|j| serde_json::from_str(&j).ok()),
+                },
+            ))
+        })?;
+        let mut rekordy = Vec::new();
+        for wiersz in wiersze {
+            let (benchmark, implementacja, parametry, powtorzenie, metrics) = wiersz?;
+            let parametry: BTreeMap<String, String> = serde_json::from_str(&parametry).map_err(|e| Blad::Parsowanie {
+                pole: format!("parametry przebiegu {}", id),
+                wartosc: parametry.clone(),
+                zrodlo: e.to_string(),
+            })?;
+            rekordy.push(RekordWyniku {
+                benchmark,
+                implementacja,
+                parametry,
+                powtorzenie: powtorzenie as usize,
+                metrics,
+            });
+        }
+        Ok(Raport {
+            nazwa,
+            seed: seed.map(|s| s as u64),
+            srodowisko: serde_json::from_str(&srodowisko).ok(),
+            rekordy,
+        })
+    }
+
+    // Srednia metryki dla kazdej serii w kolejnych przebiegach (chronologicznie)
+    pub fn trend(&self, metryka: &str, filtr: &Filtr) -> Wynik<Vec<PunktTrendu>> {
+        if !METRYKI.contains(&metryka) {
+            return Err(Blad::parametr("metryka", metryka, METRYKI.join(", ")));
+        }
+        let (warunek, wartosci) = filtr.warunek();
+        let mut zapytanie = self.polaczenie.prepare(&format!(
+            "SELECT r.seria, p.id, datetime(p.znacznik, 'unixepoch'), p.rewizja, AVG(r.{m}), COUNT(r.{m})
+             FROM przebiegi p JOIN rekordy r ON r.przebieg = p.id
+             WHERE {w}
+             GROUP BY r.seria, p.id HAVING COUNT(r.{m}) > 0 ORDER BY r.seria, p.znacznik, p.id",
+            m = metryka,
+            w = warunek
+        ))?;
+        let wiersze = zapytanie.query_map(params_from_iter(wartosci), |w| {
+            Ok(PunktTrendu {
+                seria: w.get(0)?,
+                przebieg: w.get(1)?,
+                data: w.get(2)?,
+                rewizja: w.get(3)?,
+                srednia: w.get::<_, Option<f64>>(4)?.unwrap_or(f64::NAN),
+                probki: w.get::<_, i64>(5)? as usize,
+            })
+        })?;
+        Ok(wiersze.collect::<Result<_, _>>()?)
+    }
+}
+
+fn data_poprawna(data: &str) -> bool {
+    let b = data.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b.iter().enumerate().all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit())
+}
+
+fn wypisz_przebiegi(przebiegi: &[Przebieg]) {
+    println!(
+        "{:>5}  {:<19}  {:<24} {:<20} {:<16} {:>20} {:>8}",
+        "id", "data (UTC)", "nazwa", "rewizja", "host", "seed", "rekordy"
+    );
+    for p in przebiegi {
+        let seed = p.seed.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:>5}  {:<19}  {:<24} {:<20} {:<16} {:>20} {:>8}",
+            p.id, p.data, p.nazwa, p.rewizja, p.host, seed, p.liczba_rekordow
+        );
+    }
+    if przebiegi.is_empty() {
+        println!("(brak przebiegow pasujacych do filtra)");
+    }
+}
+
+fn wypisz_trend(metryka: &str, punkty: &[PunktTrendu]) {
+    const SZEROKOSC_PASKA: f64 = 40.0;
+    let mut seria_poprzednia: Option<&str> = None;
+    for (i, p) in punkty.iter().enumerate() {
+        if seria_poprzednia != Some(p.seria.as_str()) {
+            seria_poprzednia = Some(&p.seria);
+            // Pasek skalowany do maksimum w obrebie serii
+            let maks = punkty[i..]
+                .iter()
+                .take_while(|q| q.seria == p.seria)
+                .map(|q| q.srednia)
+                .filter(|v| v.is_finite())
+                .fold(0.0, f64::max);
+            println!("\n{} ({})", p.seria, metryka);
+            println!("{:>5}  {:<19}  {:<20} {:>14} {:>5}", "id", "data (UTC)", "rewizja", "srednia", "n");
+            for q in punkty[i..].iter().take_while(|q| q.seria == p.seria) {
+                let pasek = if maks > 0.0 && q.srednia.is_finite() {
+                    "#".repeat((q.srednia / maks * SZEROKOSC_PASKA).round() as usize)
+                } else {
+                    String::new()
+                };
+                println!(
+                    "{:>5}  {:<19}  {:<20} {:>14.6} {:>5}  {}",
+                    q.przebieg, q.data, q.rewizja, q.srednia, q.probki, pasek
+                );
+            }
+        }
+    }
+    if punkty.is_empty() {
+        println!("(brak pomiarow pasujacych do filtra)");
+    }
+}
+
+const UZYCIE: &str = "historia lista [filtry] | historia pokaz <id> [--format tekst|csv|json|html] | \
+                      historia trend <metryka> [filtry]; metryki: czas, czas_dekompresji, cpu_usage, \
+                      cpu_usage_abs, cpu_user, cpu_system, ram_usage, ram_szczyt, alokacje_liczba, \
+                      alokacje_bajty, alokacje_szczyt, nierownowaga, count; \
+                      filtry: --benchmark <nazwa> --implementacja <nazwa> --od RRRR-MM-DD --do RRRR-MM-DD";
+
+// Polecenie `historia ...` z linii polecen; `argumenty` to wszystko po slowie historia
+pub fn polecenie(baza: &str, argumenty: &[String]) -> Wynik<()> {
+    let Some((komenda, reszta)) = argumenty.split_first() else {
+        return Err(Blad::parametr("historia", "", UZYCIE));
+    };
+
+    let mut filtr = Filtr::default();
+    let mut format = FormatRaportu::Tekst;
+    let mut pozycyjne = Vec::new();
+    let mut it = reszta.iter();
+    while let Some(arg) = it.next() {
+        let mut wartosc = || it.next().cloned().ok_or_else(|| Blad::parametr(arg, "", "wartosc opcji"));
+        match arg.as_str() {
+            "--benchmark" => filtr.benchmark = Some(wartosc()?),
+            "--implementacja" => filtr.implementacja = Some(wartosc()?),
+            "--od" | "--do" => {
+                let data = wartosc()?;
+                if !data_poprawna(&data) {
+                    return Err(Blad::parametr(arg, data, "data RRRR-MM-DD"));
+                }
+                if arg == "--od" {
+                    filtr.od = Some(data);
+                } else {
+                    filtr.do_dnia = Some(data);
+                }
+            }
+            "--format" => {
+                format = match wartosc()?.as_str() {
+                    "tekst" => FormatRaportu::Tekst,
+                    "csv" => FormatRaportu::Csv,
+                    "json" => FormatRaportu::Json,
+                    "html" => FormatRaportu::Html,
+                    inny => return Err(Blad::parametr("--format", inny, "tekst, csv, json lub html")),
+                }
+            }
+            _ if arg.starts_with("--") => return Err(Blad::parametr("historia", arg, UZYCIE)),
+            _ => pozycyjne.push(arg.as_str()),
+        }
+    }
+
+    let historia = Historia::otworz(baza)?;
+    match (komenda.as_str(), pozycyjne.as_slice()) {
+        ("lista", []) => wypisz_przebiegi(&historia.przebiegi(&filtr)?),
+        ("pokaz", [id]) => {
+            let id: i64 = id.parse().map_err(|e: std::num::ParseIntError| Blad::Parsowanie {
+                pole: "przebieg".to_string(),
+                wartosc: id.to_string(),
+                zrodlo: e.to_string(),
+            })?;
+            print!("{}", historia.raport(id)?.formatuj(format)?);
+        }
+        ("trend", [metryka]) => wypisz_trend(metryka, &historia.trend(metryka, &filtr)?),
+        _ => return Err(Blad::parametr("historia", argumenty.join(" "), UZYCIE)),
+    }
+    Ok(())
+}
+
+pub fn zapisz_przebieg(baza: &str, raport: &Raport) -> Wynik<i64> {
+    Historia::otworz(baza)?.zapisz(raport)
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+
+    #[test]
+    fn uzycie_wymienia_wszystkie_metryki_trendu() {
+        for metryka in METRYKI {
+            assert!(UZYCIE.contains(&format!(" {},", metryka)) || UZYCIE.contains(&format!(" {};", metryka)), "{}", metryka);
+        }
+    }
+}
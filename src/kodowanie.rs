@@ -0,0 +1,267 @@
+// src/kodowanie.rs
+// Kodowanie plikow wejsciowych: wykrywanie (BOM, heurystyka) i transkodowanie.
+// Silniki porownuja bajty, wiec plik i slowo musza byc w tym samym kodowaniu: albo plik jest
+// przekodowany do UTF-8 przed pomiarem (plik roboczy w katalogu tymczasowym), albo slowo jest
+// zakodowane w kodowaniu pliku (tylko kodowania jednobajtowe i tryb dokladny). Plik skompresowany
+// (kompresja.rs) jest rozpakowywany przy wykrywaniu i przekodowaniu
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+
+use crate::blad::{Blad, Wynik};
+use crate::kompresja::{self, Kompresja};
+
+// Poczatek pliku, na ktorym dziala heurystyka
+const PROBKA: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Kodowanie {
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    #[serde(rename = "windows-1250")]
+    Windows1250,
+    #[serde(rename = "iso-8859-2")]
+    Iso8859_2,
+}
+
+impl Kodowanie {
+    pub const WSZYSTKIE: [Kodowanie; 5] = [
+        Kodowanie::Utf8,
+        Kodowanie::Utf16Le,
+        Kodowanie::Utf16Be,
+        Kodowanie::Windows1250,
+        Kodowanie::Iso8859_2,
+    ];
+
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            Kodowanie::Utf8 => "utf-8",
+            Kodowanie::Utf16Le => "utf-16le",
+            Kodowanie::Utf16Be => "utf-16be",
+            Kodowanie::Windows1250 => "windows-1250",
+            Kodowanie::Iso8859_2 => "iso-8859-2",
+        }
+    }
+
+    // Nazwa z linii polecen, bez rozrozniania wielkosci liter
+    pub fn z_nazwy(nazwa: &str) -> Wynik<Self> {
+        Kodowanie::WSZYSTKIE
+            .into_iter()
+            .find(|k| k.nazwa().eq_ignore_ascii_case(nazwa))
+            .ok_or_else(|| Blad::parametr("kodowanie", nazwa, "utf-8, utf-16le, utf-16be, windows-1250 lub iso-8859-2"))
+    }
+
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            Kodowanie::Utf8 => encoding_rs::UTF_8,
+            Kodowanie::Utf16Le => encoding_rs::UTF_16LE,
+            Kodowanie::Utf16Be => encoding_rs::UTF_16BE,
+            Kodowanie::Windows1250 => encoding_rs::WINDOWS_1250,
+            Kodowanie::Iso8859_2 => encoding_rs::ISO_8859_2,
+        }
+    }
+
+    // Jeden bajt na znak - zakodowane slowo trafia tylko na granice znakow pliku
+    pub fn jednobajtowe(self) -> bool {
+        matches!(self, Kodowanie::Windows1250 | Kodowanie::Iso8859_2)
+    }
+}
+
+// Co jest przekodowywane, gdy plik nie jest w UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transkodowanie {
+    // Plik do UTF-8 przed pomiarem; dziala ze wszystkimi trybami
+    #[default]
+    Wejscie,
+    // Slowo do kodowania pliku; pomiar na oryginalnych bajtach
+    Wzorzec,
+}
+
+impl Transkodowanie {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            Transkodowanie::Wejscie => "wejscie",
+            Transkodowanie::Wzorzec => "wzorzec",
+        }
+    }
+}
+
+// Kodowanie z BOM, a bez niego z heurystyki; drugi element mowi, skad wynik
+pub fn wykryj(probka: &[u8]) -> (Kodowanie, &'static str) {
+    for (bom, kodowanie) in [
+        (&[0xEF, 0xBB, 0xBF][..], Kodowanie::Utf8),
+        (&[0xFF, 0xFE][..], Kodowanie::Utf16Le),
+        (&[0xFE, 0xFF][..], Kodowanie::Utf16Be),
+    ] {
+        if probka.starts_with(bom) {
+            return (kodowanie, "BOM");
+        }
+    }
+
+    // UTF-16 bez BOM: znaki ASCII maja zerowy bajt starszy, wiec zera skupiaja sie na jednej parzystosci
+    let pary = probka.len() / 2;
+    let zera = |przesuniecie: usize| probka.iter().skip(przesuniecie).step_by(2).filter(|&&b| b == 0).count();
+    let (zera_parzyste, zera_nieparzyste) = (zera(0), zera(1));
+    if pary > 0 && zera_nieparzyste * 4 > pary && zera_parzyste * 10 < zera_nieparzyste {
+        return (Kodowanie::Utf16Le, "heurystyka: zera w bajtach nieparzystych");
+    }
+    if pary > 0 && zera_parzyste * 4 > pary && zera_nieparzyste * 10 < zera_parzyste {
+        return (Kodowanie::Utf16Be, "heurystyka: zera w bajtach parzystych");
+    }
+
+    // Probka moze konczyc sie w srodku znaku UTF-8 - niepelna sekwencja na koncu nie przeczy UTF-8
+    match std::str::from_utf8(probka) {
+        Ok(_) => return (Kodowanie::Utf8, "heurystyka: poprawny UTF-8"),
+        Err(e) if e.error_len().is_none() => return (Kodowanie::Utf8, "heurystyka: poprawny UTF-8"),
+        Err(_) => {}
+    }
+
+    // 0x80-0x9F to w ISO-8859-2 znaki sterujace, a w Windows-1250 m.in. s/z z kreska (9C, 9F, 8C, 8F)
+    if probka.iter().any(|b| (0x80..=0x9F).contains(b)) {
+        return (Kodowanie::Windows1250, "heurystyka: bajty 0x80-0x9F");
+    }
+    // Pozostale roznice polskich liter: a z ogonkiem (B9/A5 w Windows-1250, B1/A1 w ISO-8859-2),
+    // s i z z kreska w ISO-8859-2 (B6/A6, BC/AC)
+    let ile = |bajty: &[u8]| probka.iter().filter(|b| bajty.contains(b)).count();
+    if ile(&[0xB1, 0xA1, 0xB6, 0xA6, 0xBC, 0xAC]) > ile(&[0xB9, 0xA5]) {
+        (Kodowanie::Iso8859_2, "heurystyka: polskie litery ISO-8859-2")
+    } else {
+        (Kodowanie::Windows1250, "heurystyka: polskie litery Windows-1250")
+    }
+}
+
+// Probka z poczatku danych po rozpakowaniu, gdy plik jest skompresowany
+pub fn wykryj_w_pliku(sciezka: &str) -> Wynik<(Kodowanie, &'static str)> {
+    let mut probka = Vec::new();
+    kompresja::czytnik(sciezka)?
+        .0
+        .take(PROBKA)
+        .read_to_end(&mut probka)
+        .map_err(|e| Blad::io(format!("odczyt pliku {}", sciezka), e))?;
+    Ok(wykryj(&probka))
+}
+
+// Bajty slowa w kodowaniu pliku; znak spoza kodowania to blad, a nie cicho zerowy wynik
+pub fn zakoduj_wzorzec(slowo: &str, kodowanie: Kodowanie) -> Wynik<Vec<u8>> {
+    if kodowanie == Kodowanie::Utf8 {
+        return Ok(slowo.as_bytes().to_vec());
+    }
+    if !kodowanie.jednobajtowe() {
+        return Err(Blad::parametr("transkodowanie", "wzorzec", format!("wejscie dla kodowania {}", kodowanie.nazwa())));
+    }
+    let (bajty, _, niemapowalne) = kodowanie.encoding().encode(slowo);
+    if niemapowalne {
+        return Err(Blad::parametr("slowo", slowo, format!("znaki dostepne w kodowaniu {}", kodowanie.nazwa())));
+    }
+    Ok(bajty.into_owned())
+}
+
+// Plik przekazywany silnikom: oryginal albo jego kopia w UTF-8, usuwana razem z ta wartoscia
+#[derive(Debug)]
+pub struct PlikWejsciowy {
+    pub sciezka: String,
+    pub kodowanie: Kodowanie,
+    pub transkodowanie: Transkodowanie,
+    // Kompresja oryginalu; kopia w UTF-8 jest juz rozpakowana
+    pub kompresja: Kompresja,
+    // Bajty widziane przez silniki (po rozpakowaniu i przekodowaniu) - podstawa doboru fragmentu
+    pub rozmiar: u64,
+    roboczy: bool,
+}
+
+impl PlikWejsciowy {
+    // Slowa sa kodowane tylko przy transkodowaniu wzorca
+    pub fn kodowanie_wzorca(&self) -> Kodowanie {
+        match self.transkodowanie {
+            Transkodowanie::Wejscie => Kodowanie::Utf8,
+            Transkodowanie::Wzorzec => self.kodowanie,
+        }
+    }
+
+    // UTF-8 bez kompresji nie zmienia klucza serii - zgodnosc z dawnymi baseline
+    pub fn dopisz_parametry(&self, parametry: &mut BTreeMap<String, String>) {
+        if self.kompresja != Kompresja::Brak {
+            parametry.insert("kompresja".to_string(), self.kompresja.nazwa().to_string());
+        }
+        if self.kodowanie != Kodowanie::Utf8 {
+            parametry.insert("kodowanie".to_string(), self.kodowanie.nazwa().to_string());
+            parametry.insert("transkodowanie".to_string(), self.transkodowanie.nazwa().to_string());
+        }
+    }
+}
+
+impl Drop for PlikWejsciowy {
+    fn drop(&mut self) {
+        if self.roboczy {
+            let _ = std::fs::remove_file(&self.sciezka);
+        }
+    }
+}
+
+static LICZNIK_ROBOCZYCH: AtomicUsize = AtomicUsize::new(0);
+
+// Wywolywane przed Pomiar::start: rozpoznanie kompresji, wykrycie kodowania (gdy nie podane) i ewentualne
+// przekodowanie pliku. Plik skompresowany w UTF-8 (albo z transkodowaniem wzorca) rozpakowuja silniki w trakcie
+// pomiaru; przekodowywany jest rozpakowany tutaj, wiec jego pomiar nie obejmuje dekompresji.
+// Transkodowanie wzorca dla UTF-16 przechodzi na przekodowanie pliku - dwubajtowe slowo mogloby
+// pasowac z przesunieciem o jeden bajt
+pub fn przygotuj(sciezka: &str, kodowanie: Option<Kodowanie>, transkodowanie: Transkodowanie) -> Wynik<PlikWejsciowy> {
+    let (rozmiar, kompresja) = kompresja::rozmiar_danych(sciezka)?;
+    if kompresja != Kompresja::Brak {
+        eprintln!("plik {}: kompresja {}, {} B po rozpakowaniu", sciezka, kompresja.nazwa(), rozmiar);
+    }
+    let (kodowanie, zrodlo) = match kodowanie {
+        Some(k) => (k, "podane"),
+        None => wykryj_w_pliku(sciezka)?,
+    };
+    let mut wejscie =
+        PlikWejsciowy { sciezka: sciezka.to_string(), kodowanie, transkodowanie, kompresja, rozmiar, roboczy: false };
+    if kodowanie == Kodowanie::Utf8 {
+        return Ok(wejscie);
+    }
+    if transkodowanie == Transkodowanie::Wzorzec && !kodowanie.jednobajtowe() {
+        eprintln!("plik {}: transkodowanie wzorca niedostepne dla {}, plik przekodowany do UTF-8", sciezka, kodowanie.nazwa());
+        wejscie.transkodowanie = Transkodowanie::Wejscie;
+    }
+    if wejscie.transkodowanie == Transkodowanie::Wzorzec {
+        eprintln!("plik {}: kodowanie {} ({}), slowa kodowane w tym samym kodowaniu", sciezka, kodowanie.nazwa(), zrodlo);
+        return Ok(wejscie);
+    }
+
+    let (bajty, _) = kompresja::odczytaj(sciezka)?;
+    let (tekst, bledy) = kodowanie.encoding().decode_with_bom_removal(&bajty);
+    if bledy {
+        eprintln!(
+            "Ostrzezenie: plik {} zawiera bajty niepoprawne w {} - zastapione znakiem U+FFFD",
+            sciezka,
+            kodowanie.nazwa()
+        );
+    }
+    let roboczy = std::env::temp_dir().join(format!(
+        "rsa_liczenie_slow_{}_{}.utf8.txt",
+        std::process::id(),
+        LICZNIK_ROBOCZYCH.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&roboczy, tekst.as_bytes())
+        .map_err(|e| Blad::io(format!("zapis pliku roboczego {}", roboczy.display()), e))?;
+    eprintln!(
+        "plik {}: kodowanie {} ({}), przekodowany do UTF-8 ({} B)",
+        sciezka,
+        kodowanie.nazwa(),
+        zrodlo,
+        tekst.len()
+    );
+    wejscie.sciezka = roboczy.to_string_lossy().into_owned();
+    wejscie.rozmiar = tekst.len() as u64;
+    wejscie.roboczy = true;
+    Ok(wejscie)
+}
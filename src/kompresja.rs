@@ -0,0 +1,131 @@
+// src/kompresja.rs
+// Przezroczysta dekompresja plikow wejsciowych: gzip, zstd i xz rozpoznawane po naglowku (magic bytes),
+// a bez niego po rozszerzeniu. Dekoder czyta plik strumieniowo i oddaje rozpakowane bajty prosto silnikom,
+// bez kopii na dysku; czas spedzony w dekoderze (razem z odczytem skompresowanych bajtow) mierzony osobno
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::blad::{Blad, Wynik};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kompresja {
+    Brak,
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Kompresja {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            Kompresja::Brak => "brak",
+            Kompresja::Gzip => "gzip",
+            Kompresja::Zstd => "zstd",
+            Kompresja::Xz => "xz",
+        }
+    }
+
+    fn z_naglowka(naglowek: &[u8]) -> Option<Self> {
+        [
+            (&[0x1F, 0x8B][..], Kompresja::Gzip),
+            (&[0x28, 0xB5, 0x2F, 0xFD][..], Kompresja::Zstd),
+            (&[0xFD, b'7', b'z', b'X', b'Z', 0x00][..], Kompresja::Xz),
+        ]
+        .into_iter()
+        .find(|(magia, _)| naglowek.starts_with(magia))
+        .map(|(_, kompresja)| kompresja)
+    }
+
+    fn z_rozszerzenia(sciezka: &str) -> Option<Self> {
+        let rozszerzenie = Path::new(sciezka).extension()?.to_str()?.to_ascii_lowercase();
+        match rozszerzenie.as_str() {
+            "gz" | "gzip" => Some(Kompresja::Gzip),
+            "zst" | "zstd" => Some(Kompresja::Zstd),
+            "xz" => Some(Kompresja::Xz),
+            _ => None,
+        }
+    }
+}
+
+fn blad_odczytu(sciezka: &str) -> impl Fn(io::Error) -> Blad + '_ {
+    move |e| Blad::io(format!("odczyt pliku {}", sciezka), e)
+}
+
+// Naglowek ma pierwszenstwo; rozszerzenie rozstrzyga tylko, gdy naglowek nic nie mowi
+// (wtedy uszkodzony plik .gz konczy sie bledem dekodera zamiast cicho zerowym wynikiem)
+pub fn wykryj(sciezka: &str) -> Wynik<Kompresja> {
+    let mut naglowek = Vec::with_capacity(6);
+    File::open(sciezka)
+        .and_then(|plik| plik.take(6).read_to_end(&mut naglowek))
+        .map_err(blad_odczytu(sciezka))?;
+    Ok(Kompresja::z_naglowka(&naglowek)
+        .or_else(|| Kompresja::z_rozszerzenia(sciezka))
+        .unwrap_or(Kompresja::Brak))
+}
+
+// Strumien rozpakowanych bajtow pliku (dla pliku nieskompresowanego - sam plik)
+pub fn czytnik(sciezka: &str) -> Wynik<(Box<dyn Read + Send>, Kompresja)> {
+    let kompresja = wykryj(sciezka)?;
+    let plik = BufReader::new(File::open(sciezka).map_err(blad_odczytu(sciezka))?);
+    let czytnik: Box<dyn Read + Send> = match kompresja {
+        Kompresja::Brak => Box::new(plik),
+        // Wiele czlonow gzip/xz (np. pliki sklejone przez cat) czytane do konca
+        Kompresja::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(plik)),
+        Kompresja::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(plik).map_err(blad_odczytu(sciezka))?),
+        Kompresja::Xz => Box::new(xz2::bufread::XzDecoder::new_multi_decoder(plik)),
+    };
+    Ok((czytnik, kompresja))
+}
+
+// Czytnik sumujacy czas spedzony w read() - przy dekoderze to czas dekompresji
+pub struct Mierzony<R> {
+    wewnetrzny: R,
+    czas: Duration,
+}
+
+impl<R: Read> Mierzony<R> {
+    pub fn new(wewnetrzny: R) -> Self {
+        Mierzony { wewnetrzny, czas: Duration::ZERO }
+    }
+
+    pub fn sekundy(&self) -> f64 {
+        self.czas.as_secs_f64()
+    }
+}
+
+impl<R: Read> Read for Mierzony<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let poczatek = Instant::now();
+        let wynik = self.wewnetrzny.read(buf);
+        self.czas += poczatek.elapsed();
+        wynik
+    }
+}
+
+// Caly plik po rozpakowaniu i czas dekompresji (None dla pliku nieskompresowanego)
+pub fn odczytaj(sciezka: &str) -> Wynik<(Vec<u8>, Option<f64>)> {
+    let (czytnik, kompresja) = czytnik(sciezka)?;
+    if kompresja == Kompresja::Brak {
+        drop(czytnik);
+        return std::fs::read(sciezka).map(|dane| (dane, None)).map_err(blad_odczytu(sciezka));
+    }
+    let mut czytnik = Mierzony::new(czytnik);
+    let mut dane = Vec::new();
+    czytnik
+        .read_to_end(&mut dane)
+        .map_err(|e| Blad::io(format!("dekompresja pliku {} ({})", sciezka, kompresja.nazwa()), e))?;
+    Ok((dane, Some(czytnik.sekundy())))
+}
+
+// Rozmiar danych po rozpakowaniu; wymaga przejscia przez caly strumien, wiec wolane raz przed pomiarami
+pub fn rozmiar_danych(sciezka: &str) -> Wynik<(u64, Kompresja)> {
+    let (mut czytnik, kompresja) = czytnik(sciezka)?;
+    let rozmiar = match kompresja {
+        Kompresja::Brak => std::fs::metadata(sciezka).map_err(blad_odczytu(sciezka))?.len(),
+        _ => io::copy(&mut czytnik, &mut io::sink())
+            .map_err(|e| Blad::io(format!("dekompresja pliku {} ({})", sciezka, kompresja.nazwa()), e))?,
+    };
+    Ok((rozmiar, kompresja))
+}
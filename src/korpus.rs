@@ -0,0 +1,511 @@
+// src/korpus.rs
+// Generator syntetycznych korpusow o znanej zawartosci: plik zadanego rozmiaru ze slow o losowej dlugosci
+// (alfabet i rozklad dlugosci ze specyfikacji TOML), slownictwo o rozkladzie Zipfa i wstawione slowa w zadanej
+// liczbie - rowniez przecinajace granice fragmentow i lezace w calosci w zakladce miedzy fragmentami.
+// Slownictwo i wypelnienia nie zawieraja wstawianych slow, wiec oczekiwana liczba wystapien wynika z samych
+// wstawek; manifest JSON zapisany obok pliku sluzy do weryfikacji wynikow silnikow
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::SystemTime;
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::{index, SliceRandom};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::blad::{Blad, Wynik};
+use crate::liczenie_slow::{opis_rozmiaru, parsuj_bajty, RozmiarFragmentu, OVERLAP_SIZE, ROZMIAR_FRAGMENTU};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpecyfikacjaKorpusu {
+    pub plik: String,
+    // Manifest oczekiwanych wynikow; domyslnie <plik>.manifest.json
+    pub manifest: Option<String>,
+    pub seed: Option<u64>,
+    // Rozmiar pliku w bajtach (65536, "64K", "256M")
+    #[serde(deserialize_with = "rozmiar_w_bajtach")]
+    pub rozmiar: u64,
+    #[serde(default = "alfabet_domyslny")]
+    pub alfabet: String,
+    // Dlugosc slow w znakach: dlugosc_min..=dlugosc_max, wagi dla kolejnych dlugosci (domyslnie rowne)
+    #[serde(default = "dlugosc_min_domyslna")]
+    pub dlugosc_min: usize,
+    #[serde(default = "dlugosc_max_domyslna")]
+    pub dlugosc_max: usize,
+    #[serde(default)]
+    pub wagi_dlugosci: Vec<f64>,
+    // Liczba roznych slow i wykladnik s rozkladu Zipfa (czestosc slowa o randze r ~ 1 / r^s)
+    #[serde(default = "slownik_domyslny")]
+    pub slownik: usize,
+    #[serde(default = "zipf_domyslny")]
+    pub zipf: f64,
+    // Linia lamana po przekroczeniu tej liczby bajtow
+    #[serde(default = "dlugosc_linii_domyslna")]
+    pub dlugosc_linii: usize,
+    // Fragment, ktorego granice (wielokrotnosci rozmiaru) dostaja wstawki na_granicach i w_zakladce
+    #[serde(default = "fragment_domyslny")]
+    pub fragment: RozmiarFragmentu,
+    #[serde(default)]
+    pub wstawki: Vec<Wstawka>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wstawka {
+    pub slowo: String,
+    // Wszystkie wstawienia, lacznie z tymi na granicach i w zakladce
+    pub liczba: u64,
+    // Wstawienia przecinajace granice fragmentu (czesc slowa w kazdym z dwoch fragmentow)
+    #[serde(default)]
+    pub na_granicach: u64,
+    // Wstawienia w calosci w zakladce przed granica - widoczne w dwoch fragmentach
+    #[serde(default)]
+    pub w_zakladce: u64,
+}
+
+// Oczekiwane wyniki dla wygenerowanego pliku
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub plik: String,
+    pub rozmiar: u64,
+    pub seed: u64,
+    pub fragment: usize,
+    pub slownik: usize,
+    pub zipf: f64,
+    // Slowa (z wypelnieniami) zapisane w pliku
+    pub slowa: u64,
+    pub oczekiwane: Vec<Oczekiwane>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Oczekiwane {
+    pub slowo: String,
+    pub wstawione: u64,
+    // Wynik poprawnego silnika: wystapienia (rowniez nakladajace sie) lacznie z tymi wewnatrz
+    // innych wstawionych slow, np. "ma" w "mama"
+    pub wystapienia: u64,
+    // Offsety wstawien przecinajacych granice i lezacych w zakladce
+    pub na_granicach: Vec<u64>,
+    pub w_zakladce: Vec<u64>,
+}
+
+fn rozmiar_w_bajtach<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Zapis {
+        Liczba(u64),
+        Tekst(String),
+    }
+    match Zapis::deserialize(deserializer)? {
+        Zapis::Liczba(n) => Ok(n),
+        Zapis::Tekst(t) => parsuj_bajty(&t)
+            .map(|n| n as u64)
+            .ok_or_else(|| serde::de::Error::custom(format!("'{}': oczekiwano liczby bajtow (np. 65536, 64K, 256M)", t))),
+    }
+}
+
+fn alfabet_domyslny() -> String {
+    "aąbcćdeęfghijklłmnńoóprsśtuwyzźż".to_string()
+}
+
+fn dlugosc_min_domyslna() -> usize {
+    1
+}
+
+fn dlugosc_max_domyslna() -> usize {
+    10
+}
+
+fn slownik_domyslny() -> usize {
+    10_000
+}
+
+fn zipf_domyslny() -> f64 {
+    1.0
+}
+
+fn dlugosc_linii_domyslna() -> usize {
+    80
+}
+
+fn fragment_domyslny() -> RozmiarFragmentu {
+    RozmiarFragmentu::Bajty(ROZMIAR_FRAGMENTU)
+}
+
+pub fn sciezka_manifestu(plik: &str) -> String {
+    format!("{}.manifest.json", plik)
+}
+
+// Wystapienia slowa w tekscie, rowniez nakladajace sie - tak licza silniki
+fn wystapienia(tekst: &[u8], slowo: &[u8]) -> u64 {
+    tekst.windows(slowo.len()).filter(|okno| *okno == slowo).count() as u64
+}
+
+impl SpecyfikacjaKorpusu {
+    pub fn wczytaj(sciezka: &str) -> Wynik<Self> {
+        let tekst = std::fs::read_to_string(sciezka)
+            .map_err(|e| Blad::io(format!("odczyt specyfikacji korpusu {}", sciezka), e))?;
+        let specyfikacja: SpecyfikacjaKorpusu = toml::from_str(&tekst).map_err(|e| Blad::Parsowanie {
+            pole: format!("specyfikacja korpusu {}", sciezka),
+            wartosc: e.span().map(|z| tekst[z].to_string()).unwrap_or_default(),
+            zrodlo: e.message().to_string(),
+        })?;
+        specyfikacja.waliduj()?;
+        Ok(specyfikacja)
+    }
+
+    // Bledy niezalezne od losowania zglaszane przed utworzeniem pliku
+    pub fn waliduj(&self) -> Wynik<()> {
+        if self.rozmiar == 0 {
+            return Err(Blad::parametr("rozmiar", 0, "liczba bajtow > 0"));
+        }
+        if self.alfabet.is_empty() || self.alfabet.chars().any(char::is_whitespace) {
+            return Err(Blad::parametr("alfabet", &self.alfabet, "niepusty ciag znakow bez bialych znakow"));
+        }
+        if self.dlugosc_min == 0 || self.dlugosc_min > self.dlugosc_max {
+            return Err(Blad::parametr(
+                "dlugosc_min..dlugosc_max",
+                format!("{}..{}", self.dlugosc_min, self.dlugosc_max),
+                "1 <= dlugosc_min <= dlugosc_max",
+            ));
+        }
+        let dlugosci = self.dlugosc_max - self.dlugosc_min + 1;
+        if !self.wagi_dlugosci.is_empty() && self.wagi_dlugosci.len() != dlugosci {
+            return Err(Blad::parametr(
+                "wagi_dlugosci",
+                self.wagi_dlugosci.len(),
+                format!("{} wag (po jednej na dlugosc {}..={})", dlugosci, self.dlugosc_min, self.dlugosc_max),
+            ));
+        }
+        if self.slownik == 0 {
+            return Err(Blad::parametr("slownik", 0, "liczba slow >= 1"));
+        }
+        if !self.zipf.is_finite() || self.zipf < 0.0 {
+            return Err(Blad::parametr("zipf", self.zipf, "wykladnik >= 0"));
+        }
+        if self.dlugosc_linii == 0 {
+            return Err(Blad::parametr("dlugosc_linii", 0, "liczba bajtow >= 1"));
+        }
+        if self.fragment == RozmiarFragmentu::Auto {
+            return Err(Blad::parametr("fragment", "auto", "rozmiar w bajtach (granice musza byc znane przed pomiarem)"));
+        }
+
+        let mut slowa = HashSet::new();
+        for w in &self.wstawki {
+            if w.slowo.is_empty() || w.slowo.chars().any(char::is_whitespace) {
+                return Err(Blad::parametr("wstawki.slowo", &w.slowo, "niepuste slowo bez bialych znakow"));
+            }
+            if !slowa.insert(w.slowo.as_str()) {
+                return Err(Blad::parametr("wstawki.slowo", &w.slowo, "kazde slowo najwyzej raz"));
+            }
+            if w.na_granicach + w.w_zakladce > w.liczba {
+                return Err(Blad::parametr(
+                    &format!("wstawki {}", w.slowo),
+                    format!("na_granicach {} + w_zakladce {}", w.na_granicach, w.w_zakladce),
+                    format!("razem najwyzej liczba = {}", w.liczba),
+                ));
+            }
+            // Slowo dluzsze niz zakladka nie miesci sie w niej i nie jest widoczne w calosci we fragmencie za granica
+            if w.na_granicach + w.w_zakladce > 0 && !(2..=OVERLAP_SIZE).contains(&w.slowo.len()) {
+                return Err(Blad::parametr(
+                    &format!("wstawki {}", w.slowo),
+                    format!("{} B", w.slowo.len()),
+                    format!("slowo 2..={} B dla wstawek na granicach i w zakladce", OVERLAP_SIZE),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn plik_manifestu(&self) -> String {
+        self.manifest.clone().unwrap_or_else(|| sciezka_manifestu(&self.plik))
+    }
+}
+
+impl Manifest {
+    pub fn wczytaj(sciezka: &str) -> Wynik<Self> {
+        let tekst = std::fs::read_to_string(sciezka).map_err(|e| Blad::io(format!("odczyt manifestu {}", sciezka), e))?;
+        serde_json::from_str(&tekst).map_err(|e| Blad::Parsowanie {
+            pole: format!("manifest {}", sciezka),
+            wartosc: format!("linia {}, kolumna {}", e.line(), e.column()),
+            zrodlo: e.to_string(),
+        })
+    }
+
+    // Oczekiwana liczba wystapien slowa; None dla slowa, ktorego generator nie wstawial
+    pub fn wystapienia(&self, slowo: &str) -> Option<u64> {
+        self.oczekiwane.iter().find(|o| o.slowo == slowo).map(|o| o.wystapienia)
+    }
+}
+
+// Losowe slowa z alfabetu; `zakazane` - wstawiane slowa, ktorych slowo nie moze zawierac
+struct Slowotworca<'a> {
+    alfabet: Vec<char>,
+    dlugosci: WeightedIndex<f64>,
+    dlugosc_min: usize,
+    zakazane: &'a [String],
+}
+
+impl Slowotworca<'_> {
+    fn dozwolone(&self, slowo: &str) -> bool {
+        !self.zakazane.iter().any(|z| slowo.contains(z.as_str()))
+    }
+
+    fn slowo(&self, rng: &mut StdRng) -> String {
+        let dlugosc = self.dlugosc_min + self.dlugosci.sample(rng);
+        (0..dlugosc).map(|_| self.alfabet[rng.gen_range(0..self.alfabet.len())]).collect()
+    }
+
+    // Dokladnie `bajty` bajtow bez separatora: znaki alfabetu, ktore sie mieszcza, reszta spacjami
+    // (gdy zostal 1 bajt, a alfabet ma tylko znaki wielobajtowe)
+    fn wypelnienie(&self, bajty: usize, rng: &mut StdRng) -> String {
+        for _ in 0..10 {
+            let mut tekst = String::with_capacity(bajty);
+            while tekst.len() < bajty {
+                let zostalo = bajty - tekst.len();
+                let pasujace: Vec<char> = self.alfabet.iter().copied().filter(|z| z.len_utf8() <= zostalo).collect();
+                match pasujace.choose(rng) {
+                    Some(&znak) => tekst.push(znak),
+                    None => tekst.push(' '),
+                }
+            }
+            if self.dozwolone(&tekst) {
+                return tekst;
+            }
+        }
+        " ".repeat(bajty)
+    }
+}
+
+// Zapis slow z separatorami i sledzenie pozycji w pliku
+struct Pisarz {
+    wyjscie: BufWriter<File>,
+    pozycja: u64,
+    linia: usize,
+    dlugosc_linii: usize,
+    slowa: u64,
+}
+
+impl Pisarz {
+    // Slowo i separator: spacja, a po przekroczeniu dlugosci linii znak nowej linii
+    fn slowo(&mut self, slowo: &[u8]) -> io::Result<()> {
+        self.wyjscie.write_all(slowo)?;
+        self.linia += slowo.len();
+        let separator = if self.linia >= self.dlugosc_linii {
+            self.linia = 0;
+            b'\n'
+        } else {
+            self.linia += 1;
+            b' '
+        };
+        self.wyjscie.write_all(&[separator])?;
+        self.pozycja += slowo.len() as u64 + 1;
+        self.slowa += 1;
+        Ok(())
+    }
+}
+
+// Wstawka w stalym miejscu: (offset poczatku, indeks wstawki, w zakladce)
+type WstawkaStala = (u64, usize, bool);
+
+pub fn generuj(specyfikacja: &SpecyfikacjaKorpusu, seed: Option<u64>) -> Wynik<Manifest> {
+    specyfikacja.waliduj()?;
+    // Ziarno z linii polecen ma pierwszenstwo przed ziarnem ze specyfikacji
+    let seed = seed.or(specyfikacja.seed).unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    });
+    let mut rng = StdRng::seed_from_u64(seed);
+    let wstawki = &specyfikacja.wstawki;
+    let zakazane: Vec<String> = wstawki.iter().map(|w| w.slowo.clone()).collect();
+
+    let mut alfabet: Vec<char> = specyfikacja.alfabet.chars().collect();
+    alfabet.sort_unstable();
+    alfabet.dedup();
+    let wagi = if specyfikacja.wagi_dlugosci.is_empty() {
+        vec![1.0; specyfikacja.dlugosc_max - specyfikacja.dlugosc_min + 1]
+    } else {
+        specyfikacja.wagi_dlugosci.clone()
+    };
+    let dlugosci = WeightedIndex::new(&wagi)
+        .map_err(|e| Blad::parametr("wagi_dlugosci", format!("{:?}", wagi), format!("wagi >= 0, nie wszystkie zerowe ({})", e)))?;
+    let tworca = Slowotworca { alfabet, dlugosci, dlugosc_min: specyfikacja.dlugosc_min, zakazane: &zakazane };
+
+    // Slownictwo w kolejnosci rang; proby ograniczone, bo maly alfabet i krotkie slowa szybko sie wyczerpuja
+    let mut slownictwo = Vec::with_capacity(specyfikacja.slownik);
+    let mut znane = HashSet::with_capacity(specyfikacja.slownik);
+    let mut proby = 0;
+    while slownictwo.len() < specyfikacja.slownik {
+        proby += 1;
+        if proby > 20 * specyfikacja.slownik + 1000 {
+            return Err(Blad::parametr(
+                "slownik",
+                specyfikacja.slownik,
+                format!("najwyzej {} roznych slow dla tego alfabetu i dlugosci", slownictwo.len()),
+            ));
+        }
+        let slowo = tworca.slowo(&mut rng);
+        if tworca.dozwolone(&slowo) && znane.insert(slowo.clone()) {
+            slownictwo.push(slowo);
+        }
+    }
+    let rangi = WeightedIndex::new((1..=specyfikacja.slownik).map(|r| 1.0 / (r as f64).powf(specyfikacja.zipf)))
+        .map_err(|e| Blad::parametr("zipf", specyfikacja.zipf, e.to_string()))?;
+
+    // Rezerwa na koncu pliku: wszystkie wstawki i dwa najdluzsze slowa, zeby losowe wstawki zdazyly przed koncem
+    let najdluzsze = (specyfikacja.dlugosc_max * 4 + 1) as u64;
+    let rezerwa: u64 = wstawki.iter().map(|w| (w.slowo.len() as u64 + 1) * w.liczba).sum::<u64>() + 2 * najdluzsze;
+    if rezerwa.saturating_mul(2) > specyfikacja.rozmiar {
+        return Err(Blad::parametr(
+            "rozmiar",
+            specyfikacja.rozmiar,
+            format!("co najmniej {} B dla zadanych wstawek", rezerwa * 2),
+        ));
+    }
+    let koniec_wstawek = specyfikacja.rozmiar - rezerwa;
+
+    // Wstawki stale: losowo wybrane granice, kazda z jedna wstawka
+    let fragment = specyfikacja.fragment.rozstrzygnij(specyfikacja.rozmiar, 1);
+    let granice: Vec<u64> = (1..).map(|k| k * fragment as u64).take_while(|&g| g <= koniec_wstawek).collect();
+    let mut rodzaje: Vec<(usize, bool)> = wstawki
+        .iter()
+        .enumerate()
+        .flat_map(|(i, w)| {
+            std::iter::repeat_n((i, false), w.na_granicach as usize)
+                .chain(std::iter::repeat_n((i, true), w.w_zakladce as usize))
+        })
+        .collect();
+    if rodzaje.len() > granice.len() {
+        return Err(Blad::parametr(
+            "wstawki na_granicach + w_zakladce",
+            rodzaje.len(),
+            format!("najwyzej {} (granice fragmentu {} w pliku {} B)", granice.len(), opis_rozmiaru(fragment), specyfikacja.rozmiar),
+        ));
+    }
+    rodzaje.shuffle(&mut rng);
+    let mut wybrane = index::sample(&mut rng, granice.len(), rodzaje.len()).into_vec();
+    wybrane.sort_unstable();
+    // Na granicy: polowa slowa po kazdej stronie; w zakladce: slowo na srodku OVERLAP_SIZE bajtow przed granica
+    let stale: Vec<WstawkaStala> = wybrane
+        .into_iter()
+        .zip(rodzaje)
+        .map(|(g, (i, w_zakladce))| {
+            let m = wstawki[i].slowo.len() as u64;
+            let start = if w_zakladce { granice[g] - (OVERLAP_SIZE as u64 + m) / 2 } else { granice[g] - m / 2 };
+            (start, i, w_zakladce)
+        })
+        .collect();
+
+    // Pozostale wstawki w losowych miejscach przed rezerwa, w kolejnosci offsetow
+    let mut losowe: Vec<(u64, usize)> = wstawki
+        .iter()
+        .enumerate()
+        .flat_map(|(i, w)| std::iter::repeat_n(i, (w.liczba - w.na_granicach - w.w_zakladce) as usize))
+        .map(|i| (rng.gen_range(0..koniec_wstawek), i))
+        .collect();
+    losowe.sort_unstable();
+
+    let plik = File::create(&specyfikacja.plik)
+        .map_err(|e| Blad::io(format!("tworzenie korpusu {}", specyfikacja.plik), e))?;
+    let mut pisarz =
+        Pisarz { wyjscie: BufWriter::new(plik), pozycja: 0, linia: 0, dlugosc_linii: specyfikacja.dlugosc_linii, slowa: 0 };
+    let blad_zapisu = |e| Blad::io(format!("zapis korpusu {}", specyfikacja.plik), e);
+    let mut oczekiwane: Vec<Oczekiwane> = wstawki
+        .iter()
+        .map(|w| Oczekiwane {
+            slowo: w.slowo.clone(),
+            wstawione: w.liczba,
+            wystapienia: 0,
+            na_granicach: Vec::new(),
+            w_zakladce: Vec::new(),
+        })
+        .collect();
+
+    // Kazdy krok zapisuje slowo (wstawke, gdy nadszedl jej offset, inaczej slowo ze slownika), a gdy nie miesci
+    // sie przed nastepna wstawka stala lub koncem pliku - wypelnienie dokladnie do tego miejsca
+    let (mut nastepna_stala, mut nastepna_losowa) = (0, 0);
+    loop {
+        let cel = stale.get(nastepna_stala).map_or(specyfikacja.rozmiar, |&(start, _, _)| start);
+        let losowa = losowe.get(nastepna_losowa).filter(|&&(offset, _)| offset <= pisarz.pozycja);
+        let slowo = match losowa {
+            Some(&(_, i)) => wstawki[i].slowo.as_str(),
+            None => slownictwo[rangi.sample(&mut rng)].as_str(),
+        };
+        // Slowo i separator konczace sie najpozniej na celu
+        if pisarz.pozycja + (slowo.len() as u64) < cel {
+            pisarz.slowo(slowo.as_bytes()).map_err(blad_zapisu)?;
+            if losowa.is_some() {
+                nastepna_losowa += 1;
+            }
+            continue;
+        }
+        let luka = (cel - pisarz.pozycja) as usize;
+        if luka > 0 {
+            let wypelnienie = tworca.wypelnienie(luka - 1, &mut rng);
+            pisarz.slowo(wypelnienie.as_bytes()).map_err(blad_zapisu)?;
+        }
+        let Some(&(start, i, w_zakladce)) = stale.get(nastepna_stala) else {
+            break;
+        };
+        pisarz.slowo(wstawki[i].slowo.as_bytes()).map_err(blad_zapisu)?;
+        if w_zakladce {
+            oczekiwane[i].w_zakladce.push(start);
+        } else {
+            oczekiwane[i].na_granicach.push(start);
+        }
+        nastepna_stala += 1;
+    }
+    pisarz.wyjscie.flush().map_err(blad_zapisu)?;
+    if nastepna_losowa < losowe.len() {
+        return Err(Blad::parametr(
+            "rozmiar",
+            specyfikacja.rozmiar,
+            format!("wiekszy plik ({} wstawek nie zmiescilo sie)", losowe.len() - nastepna_losowa),
+        ));
+    }
+
+    // Slownictwo i wypelnienia nie zawieraja wstawek, separatory ich nie przecinaja - licza sie tylko wstawki
+    for o in &mut oczekiwane {
+        o.wystapienia = wstawki.iter().map(|w| w.liczba * wystapienia(w.slowo.as_bytes(), o.slowo.as_bytes())).sum();
+    }
+    let manifest = Manifest {
+        plik: specyfikacja.plik.clone(),
+        rozmiar: pisarz.pozycja,
+        seed,
+        fragment,
+        slownik: specyfikacja.slownik,
+        zipf: specyfikacja.zipf,
+        slowa: pisarz.slowa,
+        oczekiwane,
+    };
+    let plik_manifestu = specyfikacja.plik_manifestu();
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| Blad::io("serializacja manifestu", e.into()))?;
+    std::fs::write(&plik_manifestu, json).map_err(|e| Blad::io(format!("zapis manifestu {}", plik_manifestu), e))?;
+    Ok(manifest)
+}
+
+// Polecenie `korpus <specyfikacja.toml>`: plik korpusu i manifest, podsumowanie na stderr
+pub fn uruchom_korpus(sciezka: &str, seed: Option<u64>) -> Wynik<Manifest> {
+    let specyfikacja = SpecyfikacjaKorpusu::wczytaj(sciezka)?;
+    let manifest = generuj(&specyfikacja, seed)?;
+    eprintln!(
+        "Korpus {}: {} B, {} slow, slownik {} (zipf {}), seed {}",
+        manifest.plik, manifest.rozmiar, manifest.slowa, manifest.slownik, manifest.zipf, manifest.seed
+    );
+    for o in &manifest.oczekiwane {
+        eprintln!(
+            "  {:<20} wstawione {:>10}  na granicach {:>6}  w zakladce {:>6}  oczekiwane {:>10}",
+            o.slowo,
+            o.wstawione,
+            o.na_granicach.len(),
+            o.w_zakladce.len(),
+            o.wystapienia
+        );
+    }
+    eprintln!("Manifest zapisany do {}", specyfikacja.plik_manifestu());
+    Ok(manifest)
+}
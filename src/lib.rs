@@ -0,0 +1,40 @@
+//! Biblioteka pomiarowa: silniki liczenia slow (sekwencyjny, Arc/watki, rayon, watki scoped, kanaly, atomiki,
+//! pula watkow, tokio) z algorytmami KMP, SIMD i memmem, tryby wyrazen regularnych i dopasowania rozmytego,
+//! histogram czestosci slow, szyfry AES/RSA z pomiarem, sonda zasobow procesu i typy wynikow.
+//! Plik wykonywalny (`main.rs`) tylko wywoluje `cli` - argumenty, menu i zapis wynikow sa w bibliotece.
+//!
+//! ```no_run
+//! use rsa_liczenie_slow_rust::liczenie_slow::{liczba_slow_rayon, UstawieniaLiczenia};
+//!
+//! let m = liczba_slow_rayon("korpus.txt", "kota", 8, &UstawieniaLiczenia::default()).unwrap();
+//! println!("{} wystapien w {:.2}s, CPU {:.1}%", m.count, m.czas, m.cpu_usage);
+//! ```
+
+pub mod alokacje;
+pub mod blad;
+pub mod cli;
+pub mod czestosc;
+pub mod historia;
+pub mod kodowanie;
+pub mod kompresja;
+pub mod korpus;
+pub mod liczenie_slow;
+pub mod porownanie;
+pub mod pozycje;
+pub mod pula_watkow;
+pub mod raport_html;
+pub mod rozmyte;
+pub mod rsa_aes;
+pub mod scenariusz;
+pub mod selftest;
+pub mod simd;
+pub mod srodowisko;
+pub mod wejscie;
+pub mod weryfikacja;
+pub mod wyciek_czasowy;
+pub mod wyniki;
+pub mod wyrazenia;
+pub mod zasoby;
+
+pub use blad::{Blad, Wynik};
+pub use zasoby::Metrics;
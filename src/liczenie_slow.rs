@@ -1,121 +1,348 @@
 use std::{
-    io::{self},
-    time::Instant,
-    sync::{Arc, Mutex},
-    mem,
-};
-use winapi::{
-    shared::minwindef::FILETIME,
-    um::{
-        processthreadsapi::{GetCurrentProcess, GetProcessTimes},
-        psapi::GetProcessMemoryInfo,
-        sysinfoapi::GetSystemInfo,
+    collections::BTreeMap,
+    io::{self, Read},
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        mpsc, Arc, Mutex,
     },
+    path::Path,
+    thread::ThreadId,
+    time::{Duration, Instant},
 };
+use memchr::memmem;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::blad::{Blad, Wynik};
+use crate::kodowanie::{self, Kodowanie};
+use crate::kompresja;
+use crate::pula_watkow::PulaWatkow;
+use crate::srodowisko::Srodowisko;
+use crate::wejscie::{co_najmniej, wczytaj_linie, zapytaj};
+use crate::weryfikacja::{self, Referencje};
+use crate::wyniki::{Raport, RekordWyniku, POKAZANE_FRAGMENTY};
+use crate::rozmyte::{self, DOMYSLNA_ODLEGLOSC};
+use crate::wyrazenia::{Obszar, Wyrazenie, MAKS_DOPASOWANIE};
+use crate::zasoby::{Metrics, Pomiar, PracaWatku, RozkladPracy};
 
 // Stałe
-const ROZMIAR_FRAGMENTU: usize = 2 * 1024 * 1024; // 2MB
-const OVERLAP_SIZE: usize = 256;
+pub const ROZMIAR_FRAGMENTU: usize = 2 * 1024 * 1024; // 2MB
+pub const OVERLAP_SIZE: usize = 256;
+// Najmniejszy dozwolony fragment - wielokrotnie wiekszy od zakladki
+pub const MIN_ROZMIAR_FRAGMENTU: usize = 4 * 1024;
+// Najdluzsze slowo trybu dokladnego liczone poprawnie na granicy fragmentow (sprawdz_dlugosc_slowa)
+pub const MAKS_DLUGOSC_SLOWA: usize = OVERLAP_SIZE + 1;
+// Wersja regul liczenia zapisywana w srodowisku raportu; zwiekszana, gdy te same dane daja inny count
+// (1: zakladka liczona raz, regex uzgadniany z jednym przejsciem). Baseline z inna wersja nie porownuje count
+pub const WERSJA_LICZENIA: u32 = 1;
 
 // Struktury
 #[allow(non_snake_case)]
 #[derive(Debug)]
-struct KmpPreprocessed {
+pub struct KmpPreprocessed {
     lps: Vec<usize>,
-    pattern: String,
+    pattern: Vec<u8>,
+    algorytm: Algorytm,
+    finder: memmem::Finder<'static>,
+    // Tryb regex: wyrazenie zamiast dokladnego slowa (algorytm nie ma wtedy znaczenia)
+    wyrazenie: Option<Wyrazenie>,
 }
 
-#[derive(Default, Clone)]
-struct Metrics {
-    count: i32,
-    czas: f64,
-    cpu_usage: f64,
-    ram_usage: usize,
+// Silniki liczenia wybierane w scenariuszach i raportach
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Implementacja {
+    Sekwencyjny,
+    ArcThreads,
+    Rayon,
+    // std::thread::scope - watki pozyczaja bufor, bez Arc
+    ScopedThreads,
+    // Dynamiczne rozdawanie fragmentow kanalem crossbeam, wyniki kanalem mpsc
+    Kanaly,
+    // Jak arc_threads, ale wynik w liczniku atomowym zamiast Mutex<Vec>
+    Atomiki,
+    // Wlasna pula o stalej liczbie watkow (pula_watkow.rs)
+    PulaWatkow,
+    // Asynchroniczny odczyt porcjami (tokio), skanowanie w spawn_blocking
+    Tokio,
 }
 
-// Windows API wrappers
-fn get_cpu_time() -> f64 {
-    unsafe {
-        let mut create_time = FILETIME {
-            dwLowDateTime: 0,
-            dwHighDateTime: 0,
-        };
-        let mut exit_time = FILETIME {
-            dwLowDateTime: 0,
-            dwHighDateTime: 0,
-        };
-        let mut kernel_time = FILETIME {
-            dwLowDateTime: 0,
-            dwHighDateTime: 0,
-        };
-        let mut user_time = FILETIME {
-            dwLowDateTime: 0,
-            dwHighDateTime: 0,
+impl Implementacja {
+    pub const WSZYSTKIE: [Implementacja; 8] = [
+        Implementacja::Sekwencyjny,
+        Implementacja::ArcThreads,
+        Implementacja::Rayon,
+        Implementacja::ScopedThreads,
+        Implementacja::Kanaly,
+        Implementacja::Atomiki,
+        Implementacja::PulaWatkow,
+        Implementacja::Tokio,
+    ];
+
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            Implementacja::Sekwencyjny => "sekwencyjny",
+            Implementacja::ArcThreads => "arc_threads",
+            Implementacja::Rayon => "rayon",
+            Implementacja::ScopedThreads => "scoped_threads",
+            Implementacja::Kanaly => "kanaly",
+            Implementacja::Atomiki => "atomiki",
+            Implementacja::PulaWatkow => "pula_watkow",
+            Implementacja::Tokio => "tokio",
+        }
+    }
+
+    // Czy liczba watkow ma wplyw na wynik (sekwencyjny jej nie uzywa)
+    pub fn rownolegla(self) -> bool {
+        self != Implementacja::Sekwencyjny
+    }
+}
+
+// Sposob dopasowania slowa do tekstu
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrybDopasowania {
+    // Dokladne wystapienia bajtow slowa (KMP), rowniez wewnatrz dluzszych slow
+    #[default]
+    Dokladny,
+    // Slowo jest wyrazeniem regularnym (wyrazenia.rs); dopasowania bez nakladania, jak find_iter
+    Regex,
+    // Podciagi w odleglosci Levenshteina <= maks_odleglosc (rozmyte.rs), wyniki wg odleglosci
+    Rozmyty,
+}
+
+// Algorytm wyszukiwania wzorca we fragmencie; wszystkie licza te same (rowniez nakladajace sie) wystapienia
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Algorytm {
+    // Knuth-Morris-Pratt, bajt po bajcie
+    #[default]
+    Kmp,
+    // Filtr pierwszego i ostatniego bajtu AVX2/SSE2 (simd.rs), wybierany w czasie wykonania
+    Simd,
+    // memchr::memmem (wektorowy, przenosny)
+    Memmem,
+}
+
+impl Algorytm {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            Algorytm::Kmp => "kmp",
+            Algorytm::Simd => "simd",
+            Algorytm::Memmem => "memmem",
+        }
+    }
+}
+
+// Ustawienia wspolne dla wszystkich silnikow liczenia
+#[derive(Debug, Clone, Copy)]
+pub struct UstawieniaLiczenia {
+    pub algorytm: Algorytm,
+    // Rozmiar fragmentu w bajtach (bez zakladki); tokio czyta plik porcjami tej wielkosci
+    pub fragment: usize,
+    pub tryb: TrybDopasowania,
+    // Tryb regex: najdluzsze dopasowanie (bajty) liczone poprawnie na granicy fragmentow
+    pub maks_dopasowanie: usize,
+    // Tryb rozmyty: najwieksza liczona odleglosc edycyjna k
+    pub maks_odleglosc: usize,
+    // Kodowanie pliku, do ktorego przekodowywane jest slowo (tryb dokladny); UTF-8 = bez zmian
+    pub kodowanie_wzorca: Kodowanie,
+}
+
+impl Default for UstawieniaLiczenia {
+    fn default() -> Self {
+        UstawieniaLiczenia {
+            algorytm: Algorytm::default(),
+            fragment: ROZMIAR_FRAGMENTU,
+            tryb: TrybDopasowania::default(),
+            maks_dopasowanie: MAKS_DOPASOWANIE,
+            maks_odleglosc: DOMYSLNA_ODLEGLOSC,
+            kodowanie_wzorca: Kodowanie::default(),
+        }
+    }
+}
+
+// Rozmiar fragmentu w scenariuszu: liczba bajtow (65536, "64K", "2M") albo "auto"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RozmiarFragmentu {
+    Bajty(usize),
+    // Dobierany z rozmiaru pliku, liczby watkow i cache ostatniego poziomu
+    Auto,
+}
+
+impl RozmiarFragmentu {
+    pub fn nazwa(self) -> String {
+        match self {
+            RozmiarFragmentu::Bajty(n) => opis_rozmiaru(n),
+            RozmiarFragmentu::Auto => "auto".to_string(),
+        }
+    }
+
+    // Rozmiar w bajtach dla konkretnego pliku i liczby watkow
+    pub fn rozstrzygnij(self, rozmiar_pliku: u64, liczba_watkow: usize) -> usize {
+        match self {
+            RozmiarFragmentu::Bajty(n) => n,
+            RozmiarFragmentu::Auto => dobierz_rozmiar_fragmentu(rozmiar_pliku, liczba_watkow),
+        }
+    }
+}
+
+impl std::str::FromStr for RozmiarFragmentu {
+    type Err = String;
+
+    fn from_str(tekst: &str) -> Result<Self, String> {
+        let tekst = tekst.trim();
+        if tekst.eq_ignore_ascii_case("auto") {
+            return Ok(RozmiarFragmentu::Auto);
+        }
+        let bajty = parsuj_bajty(tekst)
+            .ok_or_else(|| format!("'{}': oczekiwano liczby bajtow (np. 65536, 64K, 2M) albo auto", tekst))?;
+        if bajty < MIN_ROZMIAR_FRAGMENTU {
+            return Err(format!("'{}': fragment musi miec co najmniej {}", tekst, opis_rozmiaru(MIN_ROZMIAR_FRAGMENTU)));
+        }
+        Ok(RozmiarFragmentu::Bajty(bajty))
+    }
+}
+
+impl<'de> Deserialize<'de> for RozmiarFragmentu {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Zapis {
+            Liczba(u64),
+            Tekst(String),
+        }
+        let tekst = match Zapis::deserialize(deserializer)? {
+            Zapis::Liczba(n) => n.to_string(),
+            Zapis::Tekst(t) => t,
         };
-        
-        let process = GetCurrentProcess();
-        GetProcessTimes(
-            process,
-            &mut create_time,
-            &mut exit_time,
-            &mut kernel_time,
-            &mut user_time,
-        );
+        tekst.parse().map_err(serde::de::Error::custom)
+    }
+}
 
-        let user = (user_time.dwHighDateTime as u64) << 32 | user_time.dwLowDateTime as u64;
-        let kernel = (kernel_time.dwHighDateTime as u64) << 32 | kernel_time.dwLowDateTime as u64;
-        
-        (user + kernel) as f64 * 1e-7
+// "65536", "64K", "2M", "1G" -> liczba bajtow (przyrostki binarne, bez rozrozniania wielkosci liter)
+pub fn parsuj_bajty(tekst: &str) -> Option<usize> {
+    let tekst = tekst.trim();
+    let (liczba, mnoznik) = match tekst.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&tekst[..i], 1 << 10),
+        Some((i, 'm' | 'M')) => (&tekst[..i], 1 << 20),
+        Some((i, 'g' | 'G')) => (&tekst[..i], 1 << 30),
+        _ => (tekst, 1),
+    };
+    liczba.trim().parse::<usize>().ok().and_then(|n| n.checked_mul(mnoznik))
+}
+
+// 65536 -> "64 KiB", 2097152 -> "2 MiB"; rozmiary niepodzielne zostaja w bajtach
+pub fn opis_rozmiaru(bajty: usize) -> String {
+    match bajty {
+        n if n >= 1 << 30 && n % (1 << 30) == 0 => format!("{} GiB", n >> 30),
+        n if n >= 1 << 20 && n % (1 << 20) == 0 => format!("{} MiB", n >> 20),
+        n if n >= 1 << 10 && n % (1 << 10) == 0 => format!("{} KiB", n >> 10),
+        n => format!("{} B", n),
     }
 }
 
-fn get_memory_usage() -> usize {
-    
-    #[allow(non_snake_case)]
-    #[repr(C)]
-    struct PROCESS_MEMORY_COUNTERS_EX {
-
-        cb: u32,
-        PageFaultCount: u32,
-        PeakWorkingSetSize: usize,
-        WorkingSetSize: usize,
-        QuotaPeakPagedPoolUsage: usize,
-        QuotaPagedPoolUsage: usize,
-        QuotaPeakNonPagedPoolUsage: usize,
-        QuotaNonPagedPoolUsage: usize,
-        PagefileUsage: usize,
-        PeakPagefileUsage: usize,
-        PrivateUsage: usize,
+// Tryb auto: co najmniej FRAGMENTY_NA_WATEK fragmentow na watek, zeby szybsze watki mogly
+// przejac prace wolniejszych, ale nie wiecej niz udzial watku w cache ostatniego poziomu;
+// wynik zaokraglony w dol do wielokrotnosci 4 KiB
+pub fn dobierz_rozmiar_fragmentu(rozmiar_pliku: u64, liczba_watkow: usize) -> usize {
+    const FRAGMENTY_NA_WATEK: u64 = 4;
+    let watki = liczba_watkow.max(1);
+    let na_watek = rozmiar_pliku / (watki as u64 * FRAGMENTY_NA_WATEK);
+    let udzial_cache = crate::srodowisko::cache_ostatniego_poziomu()
+        .map(|cache| cache / watki as u64)
+        .unwrap_or(ROZMIAR_FRAGMENTU as u64);
+    let rozmiar = na_watek.min(udzial_cache).max(MIN_ROZMIAR_FRAGMENTU as u64) as usize;
+    rozmiar / MIN_ROZMIAR_FRAGMENTU * MIN_ROZMIAR_FRAGMENTU
+}
+
+impl TrybDopasowania {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            TrybDopasowania::Dokladny => "dokladny",
+            TrybDopasowania::Regex => "regex",
+            TrybDopasowania::Rozmyty => "rozmyty",
+        }
     }
+}
+
+// Stan pamieci podrecznej systemu plikow przed pomiarem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StanCache {
+    // Plik wczytany tuz przed pomiarem
+    Cieply,
+    // Strony pliku usuniete z cache - pomiar obejmuje odczyt z dysku
+    Zimny,
+}
+
+impl StanCache {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            StanCache::Cieply => "cieply",
+            StanCache::Zimny => "zimny",
+        }
+    }
+}
+
+// Wywolywane przed Pomiar::start, wiec przygotowanie nie wlicza sie do wyniku;
+// cache dotyczy bajtow na dysku, wiec plik skompresowany czytany bez rozpakowania
+pub fn przygotuj_cache(sciezka: &str, stan: StanCache) -> Wynik<()> {
+    match stan {
+        StanCache::Cieply => std::fs::read(sciezka)
+            .map(drop)
+            .map_err(|e| Blad::io(format!("odczyt pliku {}", sciezka), e)),
+        StanCache::Zimny => oproznij_cache(sciezka),
+    }
+}
+
+// Otwarcie pliku z FILE_FLAG_NO_BUFFERING usuwa jego strony z cache systemu,
+// o ile zaden inny uchwyt nie trzyma pliku otwartego z buforowaniem
+fn oproznij_cache(sciezka: &str) -> Wynik<()> {
+    use winapi::um::{
+        fileapi::{CreateFileW, OPEN_EXISTING},
+        handleapi::{CloseHandle, INVALID_HANDLE_VALUE},
+        winbase::FILE_FLAG_NO_BUFFERING,
+        winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE, GENERIC_READ},
+    };
 
+    let nazwa: Vec<u16> = sciezka.encode_utf16().chain(std::iter::once(0)).collect();
     unsafe {
-        let mut pmc: PROCESS_MEMORY_COUNTERS_EX = mem::zeroed();
-        pmc.cb = mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32;
-        
-        let process = GetCurrentProcess();
-        GetProcessMemoryInfo(
-            process,
-            &mut pmc as *mut _ as *mut _,
-            mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+        let uchwyt = CreateFileW(
+            nazwa.as_ptr(),
+            GENERIC_READ,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            std::ptr::null_mut(),
+            OPEN_EXISTING,
+            FILE_FLAG_NO_BUFFERING,
+            std::ptr::null_mut(),
         );
-        
-        pmc.PrivateUsage
+        if uchwyt == INVALID_HANDLE_VALUE {
+            return Err(Blad::io(format!("oproznianie cache pliku {}", sciezka), io::Error::last_os_error()));
+        }
+        CloseHandle(uchwyt);
     }
+    Ok(())
 }
 
 // Funkcje pomocnicze
-fn odczytaj_caly_plik(sciezka: &str) -> io::Result<Vec<u8>> {
-    std::fs::read(sciezka)
+// Zawartosc pliku, skompresowany (gzip, zstd, xz) rozpakowany w locie - patrz kompresja::odczytaj
+pub fn odczytaj_caly_plik(sciezka: &str) -> Wynik<Vec<u8>> {
+    kompresja::odczytaj(sciezka).map(|(dane, _)| dane)
+}
+
+pub fn podziel_na_fragmenty(buffer: &[u8]) -> Vec<(usize, usize)> {
+    podziel_na_fragmenty_po(buffer, ROZMIAR_FRAGMENTU)
 }
 
-fn podziel_na_fragmenty(buffer: &[u8]) -> Vec<(usize, usize)> {
+pub fn podziel_na_fragmenty_po(buffer: &[u8], rozmiar: usize) -> Vec<(usize, usize)> {
+    let rozmiar = rozmiar.max(MIN_ROZMIAR_FRAGMENTU);
     let mut fragmenty = Vec::new();
     let mut poz = 0;
     
     while poz < buffer.len() {
         let start = poz.saturating_sub(OVERLAP_SIZE);
-        let end = (poz + ROZMIAR_FRAGMENTU).min(buffer.len());
+        let end = (poz + rozmiar).min(buffer.len());
         fragmenty.push((start, end));
         poz = end;
     }
@@ -123,10 +350,50 @@ fn podziel_na_fragmenty(buffer: &[u8]) -> Vec<(usize, usize)> {
     fragmenty
 }
 
+// Poczatek wlasnego obszaru fragmentu z podziel_na_fragmenty_po - za zakladka wspolna z poprzednim
+pub fn wlasny_poczatek(start: usize) -> usize {
+    if start == 0 {
+        0
+    } else {
+        start + OVERLAP_SIZE
+    }
+}
+
+impl KmpPreprocessed {
+    // Dlugosc wzorca w bajtach
+    pub fn dlugosc(&self) -> usize {
+        self.pattern.len()
+    }
+
+    // Bajt, ktorego wlasny obszar fragmentu rozstrzyga o przynaleznosci trafienia zaczetego w `poczatek`:
+    // ostatni bajt slowa (pierwszy fragment, ktory widzi je w calosci), a dla wyrazenia - poczatek dopasowania
+    pub fn bajt_przynaleznosci(&self, poczatek: usize) -> usize {
+        match self.wyrazenie {
+            Some(_) => poczatek,
+            None => poczatek + self.pattern.len().max(1) - 1,
+        }
+    }
+}
+
+// Poczatki trafien jednego przejscia po calym buforze, bez podzialu na fragmenty (referencja weryfikacji):
+// memmem wznawiany bajt za trafieniem albo find_iter wyrazenia
+pub fn pozycje_jednego_przejscia(buffer: &[u8], wzorzec: &KmpPreprocessed) -> Vec<usize> {
+    let mut pozycje = Vec::new();
+    match &wzorzec.wyrazenie {
+        Some(wyrazenie) => pozycje = wyrazenie.poczatki(buffer),
+        None => przegladaj_memmem(buffer, wzorzec, |poz| pozycje.push(poz)),
+    }
+    pozycje
+}
+
 // Implementacja KMP
-fn przygotuj_wzorzec(slowo: &str) -> KmpPreprocessed {
-    let pattern = slowo.as_bytes();
-    let mut lps = vec![0; slowo.len()];
+pub fn przygotuj_wzorzec(slowo: &str, algorytm: Algorytm) -> KmpPreprocessed {
+    przygotuj_wzorzec_bajtow(slowo.as_bytes(), algorytm)
+}
+
+// Wzorzec z gotowych bajtow, np. slowa zakodowanego w kodowaniu pliku
+pub fn przygotuj_wzorzec_bajtow(pattern: &[u8], algorytm: Algorytm) -> KmpPreprocessed {
+    let mut lps = vec![0; pattern.len()];
     let mut len = 0;
 
     for i in 1..pattern.len() {
@@ -142,13 +409,151 @@ fn przygotuj_wzorzec(slowo: &str) -> KmpPreprocessed {
 
     KmpPreprocessed {
         lps,
-        pattern: slowo.to_string(),
+        pattern: pattern.to_vec(),
+        algorytm,
+        finder: memmem::Finder::new(pattern).into_owned(),
+        wyrazenie: None,
+    }
+}
+
+// Slowo z API biblioteki; skanery zakladaja wzorzec co najmniej jednobajtowy
+pub fn sprawdz_slowo(slowo: &str) -> Wynik<()> {
+    if slowo.is_empty() {
+        return Err(Blad::parametr("slowo", "", "niepuste slowo"));
+    }
+    Ok(())
+}
+
+// Slowo trybu dokladnego po przekodowaniu (`bajty`): wystapienie przecinajace granice musi zmiescic sie
+// w calosci we fragmencie za nia, ktory zaczyna sie OVERLAP_SIZE bajtow przed granica
+pub fn sprawdz_dlugosc_slowa(slowo: &str, bajty: usize) -> Wynik<()> {
+    if bajty > MAKS_DLUGOSC_SLOWA {
+        return Err(Blad::parametr(
+            "slowo",
+            format!("{} ({} B)", slowo, bajty),
+            format!("najwyzej {} B (zakladka fragmentow + 1)", MAKS_DLUGOSC_SLOWA),
+        ));
+    }
+    Ok(())
+}
+
+// Liczba watkow silnika rownoleglego z API biblioteki; 0 dzielilaby fragmenty przez zero,
+// a w rayon oznacza wszystkie rdzenie zamiast podanej liczby
+pub fn sprawdz_watki(liczba_watkow: usize) -> Wynik<()> {
+    if liczba_watkow == 0 {
+        return Err(Blad::parametr("liczba watkow", 0, "co najmniej 1"));
+    }
+    Ok(())
+}
+
+// Wzorzec dla trybu z ustawien; w trybie regex blad skladni wyrazenia zwracany przed liczeniem
+pub fn przygotuj_wyszukiwanie(slowo: &str, ustawienia: &UstawieniaLiczenia) -> Wynik<KmpPreprocessed> {
+    sprawdz_slowo(slowo)?;
+    if ustawienia.kodowanie_wzorca != Kodowanie::Utf8 && ustawienia.tryb != TrybDopasowania::Dokladny {
+        return Err(Blad::parametr("transkodowanie", "wzorzec", "tryb dokladny (regex i rozmyty wymagaja wejscia UTF-8)"));
+    }
+    let bajty = kodowanie::zakoduj_wzorzec(slowo, ustawienia.kodowanie_wzorca)?;
+    let mut wzorzec = przygotuj_wzorzec_bajtow(&bajty, ustawienia.algorytm);
+    match ustawienia.tryb {
+        TrybDopasowania::Dokladny => sprawdz_dlugosc_slowa(slowo, bajty.len())?,
+        TrybDopasowania::Regex => wzorzec.wyrazenie = Some(Wyrazenie::nowe(slowo, ustawienia.maks_dopasowanie)?),
+        // Wyniki wg odleglosci licza tylko silniki z rozmyte.rs
+        TrybDopasowania::Rozmyty => {
+            return Err(Blad::parametr("tryb", "rozmyty", "silnik z rozmyte::IMPLEMENTACJE (rozmyte::zmierz)"))
+        }
+    }
+    Ok(wzorzec)
+}
+
+// Fragment (start, end) z podziel_na_fragmenty_po; wyrazenie przeszukuje tez sasiednie bajty bufora,
+// a jego Obszar trafia do korekta_obszarow po policzeniu wszystkich fragmentow
+pub fn policz_fragment(buffer: &[u8], (start, end): (usize, usize), wzorzec: &KmpPreprocessed) -> (i32, Option<Obszar>) {
+    match &wzorzec.wyrazenie {
+        Some(wyrazenie) => {
+            let obszar = wyrazenie.dopasowania_w_obszarze(buffer, (wlasny_poczatek(start), end));
+            (obszar.liczba, Some(obszar))
+        }
+        None => (liczba_wlasnych(&buffer[start..end], wlasny_poczatek(start) - start, wzorzec), None),
+    }
+}
+
+// Tryb regex: poprawka sumy fragmentow do jednego przejscia po calym pliku (Wyrazenie::korekta); dokladny - 0
+fn korekta_obszarow(buffer: &[u8], wzorzec: &KmpPreprocessed, obszary: Vec<Obszar>) -> i32 {
+    wzorzec.wyrazenie.as_ref().map_or(0, |wyrazenie| wyrazenie.korekta(buffer, obszary))
+}
+
+// Liczby kolejnych fragmentow; w trybie regex kazda uzgodniona z jednym przejsciem po calym pliku
+pub fn liczby_fragmentow(buffer: &[u8], fragmenty: &[(usize, usize)], wzorzec: &KmpPreprocessed) -> Vec<i32> {
+    let mut koniec = 0;
+    fragmenty
+        .iter()
+        .map(|&fragment| match (&wzorzec.wyrazenie, policz_fragment(buffer, fragment, wzorzec)) {
+            (Some(wyrazenie), (_, Some(obszar))) => {
+                let (liczba, koniec_obszaru) = wyrazenie.uzgodnij(buffer, &obszar, koniec);
+                koniec = koniec_obszaru;
+                liczba
+            }
+            (_, (count, _)) => count,
+        })
+        .collect()
+}
+
+// Wystapienia we fragmencie bez lezacych w calosci w pierwszych `zakladka` bajtach - te zglosil juz
+// poprzedni fragment (ta sama regula co offset + m <= wlasny_poczatek w pozycje.rs)
+pub fn liczba_wlasnych(fragment: &[u8], zakladka: usize, wzorzec: &KmpPreprocessed) -> i32 {
+    let zakladka = zakladka.min(fragment.len());
+    liczba_slow_we_fragmencie(fragment, wzorzec) - liczba_slow_we_fragmencie(&fragment[..zakladka], wzorzec)
+}
+
+pub fn liczba_slow_we_fragmencie(fragment: &[u8], wzorzec: &KmpPreprocessed) -> i32 {
+    match wzorzec.algorytm {
+        Algorytm::Kmp => liczba_slow_kmp(fragment, wzorzec),
+        Algorytm::Simd => crate::simd::wystapienia(fragment, &wzorzec.pattern),
+        Algorytm::Memmem => liczba_slow_memmem(fragment, wzorzec),
+    }
+}
+
+// Pozycje poczatkow wszystkich (rowniez nakladajacych sie) wystapien we fragmencie, rosnaco
+pub fn pozycje_we_fragmencie(fragment: &[u8], wzorzec: &KmpPreprocessed) -> Vec<usize> {
+    let mut pozycje = Vec::new();
+    match wzorzec.algorytm {
+        Algorytm::Kmp => przegladaj_kmp(fragment, wzorzec, |poz| pozycje.push(poz)),
+        Algorytm::Simd => pozycje = crate::simd::pozycje(fragment, &wzorzec.pattern),
+        Algorytm::Memmem => przegladaj_memmem(fragment, wzorzec, |poz| pozycje.push(poz)),
+    }
+    pozycje
+}
+
+fn liczba_slow_memmem(fragment: &[u8], wzorzec: &KmpPreprocessed) -> i32 {
+    let mut count = 0;
+    przegladaj_memmem(fragment, wzorzec, |_| count += 1);
+    count
+}
+
+// find_iter pomija wystapienia nakladajace sie - szukanie wznawiane bajt za poczatkiem trafienia
+fn przegladaj_memmem(fragment: &[u8], wzorzec: &KmpPreprocessed, mut trafienie: impl FnMut(usize)) {
+    if wzorzec.pattern.is_empty() {
+        return;
+    }
+    let mut poz = 0;
+    while let Some(p) = wzorzec.finder.find(&fragment[poz..]) {
+        trafienie(poz + p);
+        poz += p + 1;
     }
 }
 
-fn liczba_slow_we_fragmencie(fragment: &[u8], wzorzec: &KmpPreprocessed) -> i32 {
-    let pattern = wzorzec.pattern.as_bytes();
+fn liczba_slow_kmp(fragment: &[u8], wzorzec: &KmpPreprocessed) -> i32 {
     let mut count = 0;
+    przegladaj_kmp(fragment, wzorzec, |_| count += 1);
+    count
+}
+
+// `trafienie` dostaje pozycje poczatku kazdego wystapienia
+fn przegladaj_kmp(fragment: &[u8], wzorzec: &KmpPreprocessed, mut trafienie: impl FnMut(usize)) {
+    let pattern = &wzorzec.pattern;
+    if pattern.is_empty() {
+        return;
+    }
     let (mut i, mut j) = (0, 0);
 
     while i < fragment.len() {
@@ -158,7 +563,7 @@ fn liczba_slow_we_fragmencie(fragment: &[u8], wzorzec: &KmpPreprocessed) -> i32
         }
 
         if j == pattern.len() {
-            count += 1;
+            trafienie(i - j);
             j = wzorzec.lps[j - 1];
         } else if i < fragment.len() && fragment[i] != pattern[j] {
             if j != 0 {
@@ -168,56 +573,107 @@ fn liczba_slow_we_fragmencie(fragment: &[u8], wzorzec: &KmpPreprocessed) -> i32
             }
         }
     }
+}
 
-    count
+// Praca jednego watku w trakcie liczenia; koniec = chwila zakonczenia ostatniego fragmentu
+#[derive(Debug, Default, Clone, Copy)]
+struct LicznikPracy {
+    fragmenty: usize,
+    bajty: u64,
+    praca: Duration,
+    koniec: Option<Instant>,
 }
 
-// Implementacje zliczania
-fn liczba_slow_sekwencyjny(sciezka_pliku: &str, slowo: &str) -> Metrics {
-    let start_cpu = get_cpu_time();
-    let start_time = Instant::now();
-    let start_mem = get_memory_usage();
-
-    let buffer = odczytaj_caly_plik(sciezka_pliku).unwrap();
-    let fragmenty = podziel_na_fragmenty(&buffer);
-    let wzorzec = przygotuj_wzorzec(slowo);
-
-    let total = fragmenty.iter()
-        .map(|&(start, end)| {
-            let fragment = &buffer[start..end];
-            liczba_slow_we_fragmencie(fragment, &wzorzec)
-        })
-        .sum();
+impl LicznikPracy {
+    fn dodaj(&mut self, bajty: usize, poczatek: Instant) {
+        let teraz = Instant::now();
+        self.fragmenty += 1;
+        self.bajty += bajty as u64;
+        self.praca += teraz - poczatek;
+        self.koniec = Some(teraz);
+    }
 
-    let czas = start_time.elapsed().as_secs_f64();
-    let end_cpu = get_cpu_time();
-    let end_mem = get_memory_usage();
+    fn scal(&mut self, inny: LicznikPracy) {
+        self.fragmenty += inny.fragmenty;
+        self.bajty += inny.bajty;
+        self.praca += inny.praca;
+        self.koniec = self.koniec.max(inny.koniec);
+    }
+}
 
-    let num_cpus = unsafe {
-        let mut sys_info = mem::zeroed();
-        GetSystemInfo(&mut sys_info);
-        sys_info.dwNumberOfProcessors as f64
-    };
+// Liczy kolejne fragmenty w jednym watku, mierzac jego prace
+fn policz_fragmenty(
+    buffer: &[u8],
+    fragmenty: impl IntoIterator<Item = (usize, usize)>,
+    wzorzec: &KmpPreprocessed,
+) -> (i32, LicznikPracy, Vec<Obszar>) {
+    let mut count = 0;
+    let mut praca = LicznikPracy::default();
+    let mut obszary = Vec::new();
+    for (start, end) in fragmenty {
+        let poczatek = Instant::now();
+        let (liczba, obszar) = policz_fragment(buffer, (start, end), wzorzec);
+        count += liczba;
+        obszary.extend(obszar);
+        praca.dodaj(end - start, poczatek);
+    }
+    (count, praca, obszary)
+}
 
-    Metrics {
-        count: total,
-        czas,
-        cpu_usage: (end_cpu - start_cpu) / (czas * num_cpus) * 100.0,
-        ram_usage: end_mem - start_mem,
+// Watki bez zadnego fragmentu czekaja od startu do konca pracy ostatniego watku
+fn rozklad_pracy(start: Instant, liczniki: &[LicznikPracy]) -> RozkladPracy {
+    let koniec = liczniki.iter().filter_map(|l| l.koniec).max().unwrap_or(start);
+    let watki: Vec<PracaWatku> = liczniki
+        .iter()
+        .enumerate()
+        .map(|(watek, l)| PracaWatku {
+            watek,
+            fragmenty: l.fragmenty,
+            bajty: l.bajty,
+            praca: l.praca.as_secs_f64(),
+            bezczynnosc: (koniec - l.koniec.unwrap_or(start)).as_secs_f64(),
+        })
+        .collect();
+    let srednia = watki.iter().map(|w| w.praca).sum::<f64>() / watki.len().max(1) as f64;
+    let maks = watki.iter().map(|w| w.praca).fold(0.0, f64::max);
+    RozkladPracy {
+        watki,
+        nierownowaga: if srednia > 0.0 { maks / srednia } else { 1.0 },
     }
 }
 
-fn liczba_slow_arc_threads(sciezka_pliku: &str, slowo: &str, liczba_watkow: usize) -> Metrics {
-    let start_cpu = get_cpu_time();
-    let start_time = Instant::now();
-    let start_mem = get_memory_usage();
+// Implementacje zliczania
+pub fn liczba_slow_sekwencyjny(sciezka_pliku: &str, slowo: &str, ustawienia: &UstawieniaLiczenia) -> Wynik<Metrics> {
+    let pomiar = Pomiar::start();
+
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let wzorzec = przygotuj_wyszukiwanie(slowo, ustawienia)?;
 
-    let buffer = Arc::new(odczytaj_caly_plik(sciezka_pliku).unwrap());
-    let fragmenty = podziel_na_fragmenty(&buffer);
-    let wzorzec = Arc::new(przygotuj_wzorzec(slowo));
+    let total = liczby_fragmentow(&buffer, &fragmenty, &wzorzec).iter().sum();
+
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.czas_dekompresji = dekompresja;
+    Ok(metrics)
+}
 
-    let chunk_size = (fragmenty.len() + liczba_watkow - 1) / liczba_watkow;
-    let wyniki = Arc::new(Mutex::new(vec![0; liczba_watkow]));
+pub fn liczba_slow_arc_threads(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let buffer = Arc::new(buffer);
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let wzorzec = Arc::new(przygotuj_wyszukiwanie(slowo, ustawienia)?);
+
+    let chunk_size = fragmenty.len().div_ceil(liczba_watkow).max(1);
+    let wyniki = Arc::new(Mutex::new(vec![(0, LicznikPracy::default(), Vec::new()); liczba_watkow]));
+    let start = Instant::now();
 
     let handles: Vec<_> = fragmenty
         .chunks(chunk_size)
@@ -230,122 +686,554 @@ fn liczba_slow_arc_threads(sciezka_pliku: &str, slowo: &str, liczba_watkow: usiz
 
             std::thread::spawn(move || {
                 let mut local_count = 0;
+                let mut praca = LicznikPracy::default();
+                let mut obszary = Vec::new();
                 
                 for (start, end) in chunk {
-                    let fragment = &buffer[start..end];
-                    local_count += liczba_slow_we_fragmencie(fragment, &wzorzec);
+                    let poczatek = Instant::now();
+                    let (count, obszar) = policz_fragment(&buffer, (start, end), &wzorzec);
+                    local_count += count;
+                    obszary.extend(obszar);
+                    praca.dodaj(end - start, poczatek);
                 }
 
-                wyniki.lock().unwrap()[id] = local_count;
+                wyniki.lock().expect("mutex wynikow zatruty")[id] = (local_count, praca, obszary);
             })
         })
         .collect();
 
     for h in handles {
-        h.join().unwrap();
+        h.join().expect("watek liczacy zakonczyl sie panika");
     }
 
-    let total: i32 = wyniki.lock().unwrap().iter().sum();
+    let mut wyniki = wyniki.lock().expect("mutex wynikow zatruty");
+    let obszary: Vec<Obszar> = wyniki.iter_mut().flat_map(|(_, _, obszary)| std::mem::take(obszary)).collect();
+    let total: i32 = wyniki.iter().map(|&(count, _, _)| count).sum::<i32>() + korekta_obszarow(&buffer, &wzorzec, obszary);
     
-    let czas = start_time.elapsed().as_secs_f64();
-    let end_cpu = get_cpu_time();
-    let end_mem = get_memory_usage();
-
-    let num_cpus = unsafe {
-        let mut sys_info = mem::zeroed();
-        GetSystemInfo(&mut sys_info);
-        sys_info.dwNumberOfProcessors as f64
-    };
-
-    Metrics {
-        count: total,
-        czas,
-        cpu_usage: (end_cpu - start_cpu) / (czas * num_cpus) * 100.0,
-        ram_usage: end_mem - start_mem,
-    }
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.czas_dekompresji = dekompresja;
+    let liczniki: Vec<LicznikPracy> = wyniki.iter().map(|&(_, praca, _)| praca).collect();
+    metrics.rozklad = Some(rozklad_pracy(start, &liczniki));
+    Ok(metrics)
 }
 
-fn liczba_slow_rayon(sciezka_pliku: &str, slowo: &str, liczba_watkow: usize) -> Metrics {
-    let start_cpu = get_cpu_time();
-    let start_time = Instant::now();
-    let start_mem = get_memory_usage();
+pub fn liczba_slow_rayon(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
 
-    let buffer = odczytaj_caly_plik(sciezka_pliku).unwrap();
-    let fragmenty = podziel_na_fragmenty(&buffer);
-    let wzorzec = przygotuj_wzorzec(slowo);
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let wzorzec = przygotuj_wyszukiwanie(slowo, ustawienia)?;
 
     // Usunięto inicjalizację puli - używamy domyślnej konfiguracji
     let pool = rayon::ThreadPoolBuilder::new()
         .num_threads(liczba_watkow)
         .build()
-        .unwrap();
+        .map_err(|e| Blad::io("tworzenie puli watkow rayon", io::Error::other(e)))?;
 
-    let total: i32 = pool.install(|| {
+    // Licznik na watek puli; fragmenty sa duze, wiec blokada na fragment nic nie kosztuje
+    let liczniki: Vec<Mutex<LicznikPracy>> = (0..liczba_watkow).map(|_| Mutex::default()).collect();
+    let start = Instant::now();
+    let wyniki: Vec<(i32, Option<Obszar>)> = pool.install(|| {
         fragmenty.par_iter()
             .map(|&(start, end)| {
-                let fragment = &buffer[start..end];
-                liczba_slow_we_fragmencie(fragment, &wzorzec)
+                let poczatek = Instant::now();
+                let wynik = policz_fragment(&buffer, (start, end), &wzorzec);
+                let watek = rayon::current_thread_index().unwrap_or(0);
+                liczniki[watek].lock().expect("mutex licznika zatruty").dodaj(end - start, poczatek);
+                wynik
+            })
+            .collect()
+    });
+    let obszary: Vec<Obszar> = wyniki.iter().filter_map(|&(_, obszar)| obszar).collect();
+    let total = wyniki.iter().map(|&(count, _)| count).sum::<i32>() + korekta_obszarow(&buffer, &wzorzec, obszary);
+
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.czas_dekompresji = dekompresja;
+    let liczniki: Vec<LicznikPracy> = liczniki
+        .into_iter()
+        .map(|l| l.into_inner().expect("mutex licznika zatruty"))
+        .collect();
+    metrics.rozklad = Some(rozklad_pracy(start, &liczniki));
+    Ok(metrics)
+}
+
+pub fn liczba_slow_scoped_threads(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let wzorzec = przygotuj_wyszukiwanie(slowo, ustawienia)?;
+
+    let chunk_size = fragmenty.len().div_ceil(liczba_watkow).max(1);
+    let start = Instant::now();
+
+    // Zasieg watkow konczy sie przed wyjsciem z scope, wiec wystarcza zwykle referencje
+    let wyniki: Vec<(i32, LicznikPracy, Vec<Obszar>)> = std::thread::scope(|s| {
+        let handles: Vec<_> = fragmenty
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let (buffer, wzorzec) = (&buffer, &wzorzec);
+                s.spawn(move || policz_fragmenty(buffer, chunk.iter().copied(), wzorzec))
             })
-            .sum()
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("watek liczacy zakonczyl sie panika"))
+            .collect()
+    });
+
+    let obszary: Vec<Obszar> = wyniki.iter().flat_map(|(_, _, obszary)| obszary.iter().copied()).collect();
+    let total: i32 = wyniki.iter().map(|(count, _, _)| count).sum::<i32>() + korekta_obszarow(&buffer, &wzorzec, obszary);
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.czas_dekompresji = dekompresja;
+    let mut liczniki = vec![LicznikPracy::default(); liczba_watkow];
+    for (licznik, (_, praca, _)) in liczniki.iter_mut().zip(&wyniki) {
+        *licznik = *praca;
+    }
+    metrics.rozklad = Some(rozklad_pracy(start, &liczniki));
+    Ok(metrics)
+}
+
+pub fn liczba_slow_kanaly(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let wzorzec = przygotuj_wyszukiwanie(slowo, ustawienia)?;
+
+    // Kolejka pracy MPMC: wolny watek bierze nastepny fragment; po zamknieciu nadawcy iteracja sie konczy
+    let (zadania_tx, zadania_rx) = crossbeam_channel::unbounded();
+    for &fragment in &fragmenty {
+        zadania_tx.send(fragment).expect("kolejka fragmentow zamknieta");
+    }
+    drop(zadania_tx);
+
+    let start = Instant::now();
+    let mut total = 0;
+    let mut obszary = Vec::new();
+    let mut liczniki = vec![LicznikPracy::default(); liczba_watkow];
+    std::thread::scope(|s| {
+        let (wyniki_tx, wyniki_rx) = mpsc::channel();
+        for watek in 0..liczba_watkow {
+            let zadania = zadania_rx.clone();
+            let wyniki = wyniki_tx.clone();
+            let (buffer, wzorzec) = (&buffer, &wzorzec);
+            s.spawn(move || {
+                let _ = wyniki.send((watek, policz_fragmenty(buffer, zadania.iter(), wzorzec)));
+            });
+        }
+        drop(wyniki_tx);
+        for (watek, (count, praca, obszary_watku)) in wyniki_rx {
+            total += count;
+            obszary.extend(obszary_watku);
+            liczniki[watek] = praca;
+        }
     });
+    total += korekta_obszarow(&buffer, &wzorzec, obszary);
+
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.czas_dekompresji = dekompresja;
+    metrics.rozklad = Some(rozklad_pracy(start, &liczniki));
+    Ok(metrics)
+}
+
+pub fn liczba_slow_atomiki(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let buffer = Arc::new(buffer);
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let wzorzec = Arc::new(przygotuj_wyszukiwanie(slowo, ustawienia)?);
+
+    let chunk_size = fragmenty.len().div_ceil(liczba_watkow).max(1);
+    let suma = Arc::new(AtomicI32::new(0));
+    let start = Instant::now();
+
+    let handles: Vec<_> = fragmenty
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let suma = Arc::clone(&suma);
+            let wzorzec = Arc::clone(&wzorzec);
+            let buffer = Arc::clone(&buffer);
+            let chunk = chunk.to_vec();
+
+            std::thread::spawn(move || {
+                let (count, praca, obszary) = policz_fragmenty(&buffer, chunk, &wzorzec);
+                suma.fetch_add(count, Ordering::Relaxed);
+                (praca, obszary)
+            })
+        })
+        .collect();
+
+    let mut liczniki = vec![LicznikPracy::default(); liczba_watkow];
+    let mut obszary = Vec::new();
+    for (licznik, h) in liczniki.iter_mut().zip(handles) {
+        let (praca, obszary_watku) = h.join().expect("watek liczacy zakonczyl sie panika");
+        *licznik = praca;
+        obszary.extend(obszary_watku);
+    }
+
+    let total = suma.load(Ordering::Relaxed) + korekta_obszarow(&buffer, &wzorzec, obszary);
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.czas_dekompresji = dekompresja;
+    metrics.rozklad = Some(rozklad_pracy(start, &liczniki));
+    Ok(metrics)
+}
+
+pub fn liczba_slow_pula_watkow(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let buffer = Arc::new(buffer);
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let wzorzec = Arc::new(przygotuj_wyszukiwanie(slowo, ustawienia)?);
+
+    let pula = PulaWatkow::nowa(liczba_watkow)?;
+    let start = Instant::now();
+
+    // Jedno zadanie na fragment; zadania wykonuja watki w kolejnosci zwalniania sie
+    let (wyniki_tx, wyniki_rx) = mpsc::channel();
+    for &fragment in &fragmenty {
+        let buffer = Arc::clone(&buffer);
+        let wzorzec = Arc::clone(&wzorzec);
+        let wyniki = wyniki_tx.clone();
+        pula.wykonaj(move |watek| {
+            let _ = wyniki.send((watek, policz_fragmenty(&buffer, [fragment], &wzorzec)));
+        });
+    }
+    drop(wyniki_tx);
+
+    let mut total = 0;
+    let mut odebrane = 0;
+    let mut obszary = Vec::new();
+    let mut liczniki = vec![LicznikPracy::default(); pula.liczba_watkow()];
+    for (watek, (count, praca, obszary_zadania)) in wyniki_rx {
+        total += count;
+        odebrane += 1;
+        obszary.extend(obszary_zadania);
+        liczniki[watek].scal(praca);
+    }
+    assert_eq!(odebrane, fragmenty.len(), "zadanie puli zakonczylo sie panika");
+    drop(pula);
+    total += korekta_obszarow(&buffer, &wzorzec, obszary);
+
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.czas_dekompresji = dekompresja;
+    metrics.rozklad = Some(rozklad_pracy(start, &liczniki));
+    Ok(metrics)
+}
+
+// Silnik asynchroniczny: tokio czyta plik porcjami po ustawienia.fragment bajtow, a skanowanie kazdej porcji
+// trafia do spawn_blocking; najwyzej liczba_watkow porcji naraz, wiec odczyt nachodzi na liczenie.
+// Plik skompresowany rozpakowuje dekoder w puli blokujacej, a porcje powstaja z kolejnych rozpakowanych blokow
+pub fn liczba_slow_tokio(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    // Jeden watek petli zdarzen; pula blokujaca o jeden wieksza na operacje tokio::fs albo dekoder
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .max_blocking_threads(liczba_watkow + 1)
+        .build()
+        .map_err(|e| Blad::io("tworzenie srodowiska tokio", e))?;
+    let wzorzec = Arc::new(przygotuj_wyszukiwanie(slowo, ustawienia)?);
+    let start = Instant::now();
+    let (total, liczniki, dekompresja) =
+        runtime.block_on(liczenie_tokio(sciezka_pliku, wzorzec, liczba_watkow, ustawienia.fragment))?;
+    drop(runtime);
+
+    let mut metrics = pomiar.zakoncz(total);
+    metrics.rozklad = Some(rozklad_pracy(start, &liczniki));
+    metrics.czas_dekompresji = dekompresja;
+    Ok(metrics)
+}
+
+// Rozmiar bloku przekazywanego z dekodera do petli zdarzen
+const BLOK_DEKODERA: usize = 64 * 1024;
+
+// Zrodlo porcji silnika tokio: plik czytany asynchronicznie albo bloki z dekodera
+enum ZrodloPorcji {
+    Plik(tokio::fs::File),
+    Dekoder {
+        bloki: tokio::sync::mpsc::Receiver<io::Result<Vec<u8>>>,
+        // Nieoddana czesc ostatniego bloku
+        reszta: Vec<u8>,
+        // Zwraca czas dekompresji po wyczerpaniu strumienia
+        zadanie: tokio::task::JoinHandle<f64>,
+    },
+}
+
+impl ZrodloPorcji {
+    async fn otworz(sciezka_pliku: &str) -> Wynik<Self> {
+        let (czytnik, kompresja) = kompresja::czytnik(sciezka_pliku)?;
+        if kompresja == kompresja::Kompresja::Brak {
+            drop(czytnik);
+            let plik = tokio::fs::File::open(sciezka_pliku)
+                .await
+                .map_err(|e| Blad::io(format!("odczyt pliku {}", sciezka_pliku), e))?;
+            return Ok(ZrodloPorcji::Plik(plik));
+        }
+        // Kolejka dwoch blokow: dekoder wyprzedza liczenie, ale nie rozpakowuje calego pliku do pamieci
+        let (nadawca, bloki) = tokio::sync::mpsc::channel(2);
+        let zadanie = tokio::task::spawn_blocking(move || {
+            let mut czytnik = kompresja::Mierzony::new(czytnik);
+            loop {
+                let mut blok = vec![0; BLOK_DEKODERA];
+                match czytnik.read(&mut blok) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        blok.truncate(n);
+                        if nadawca.blocking_send(Ok(blok)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => {
+                        let _ = nadawca.blocking_send(Err(e));
+                        break;
+                    }
+                }
+            }
+            czytnik.sekundy()
+        });
+        Ok(ZrodloPorcji::Dekoder { bloki, reszta: Vec::new(), zadanie })
+    }
+
+    // Dopisuje do `porcja` najwyzej `ile` kolejnych bajtow; 0 = koniec danych
+    async fn czytaj(&mut self, porcja: &mut Vec<u8>, ile: usize) -> io::Result<usize> {
+        use tokio::io::AsyncReadExt;
+        match self {
+            ZrodloPorcji::Plik(plik) => plik.take(ile as u64).read_to_end(porcja).await,
+            ZrodloPorcji::Dekoder { bloki, reszta, .. } => {
+                let mut nowe = 0;
+                while nowe < ile {
+                    if reszta.is_empty() {
+                        match bloki.recv().await {
+                            Some(blok) => *reszta = blok?,
+                            None => break,
+                        }
+                    }
+                    let n = (ile - nowe).min(reszta.len());
+                    porcja.extend_from_slice(&reszta[..n]);
+                    reszta.drain(..n);
+                    nowe += n;
+                }
+                Ok(nowe)
+            }
+        }
+    }
+
+    async fn czas_dekompresji(self) -> Option<f64> {
+        match self {
+            ZrodloPorcji::Plik(_) => None,
+            ZrodloPorcji::Dekoder { zadanie, .. } => Some(zadanie.await.expect("dekoder zakonczyl sie panika")),
+        }
+    }
+}
+
+// Wynik zadania silnika tokio; w trybie regex z numerem porcji, jej poczatkiem w pliku, obszarem i danymi
+type WynikPorcji = (ThreadId, i32, LicznikPracy, Option<(usize, usize, Obszar, Vec<u8>)>);
 
-    let czas = start_time.elapsed().as_secs_f64();
-    let end_cpu = get_cpu_time();
-    let end_mem = get_memory_usage();
+async fn liczenie_tokio(
+    sciezka_pliku: &str,
+    wzorzec: Arc<KmpPreprocessed>,
+    liczba_watkow: usize,
+    rozmiar_porcji: usize,
+) -> Wynik<(i32, Vec<LicznikPracy>, Option<f64>)> {
+    let rozmiar_porcji = rozmiar_porcji.max(MIN_ROZMIAR_FRAGMENTU);
 
-    let num_cpus = unsafe {
-        let mut sys_info = mem::zeroed();
-        GetSystemInfo(&mut sys_info);
-        sys_info.dwNumberOfProcessors as f64
+    let blad_odczytu = |e| Blad::io(format!("odczyt pliku {}", sciezka_pliku), e);
+    let mut zrodlo = ZrodloPorcji::otworz(sciezka_pliku).await?;
+    let mut zadania = tokio::task::JoinSet::new();
+    let mut total = 0;
+    // Watki puli blokujacej nie maja numerow - liczniki wg identyfikatora, w kolejnosci pojawienia sie
+    let mut watki: Vec<(ThreadId, LicznikPracy)> = Vec::new();
+    // Tryb regex: obszary porcji uzgadniane z jednym przejsciem w kolejnosci pliku (Wyrazenie::uzgodnij);
+    // porcja, ktora skonczyla sie przed poprzednimi, czeka tu razem z danymi
+    let mut oczekujace: BTreeMap<usize, (usize, Obszar, Vec<u8>)> = BTreeMap::new();
+    let mut nastepna = 0;
+    // Koniec ostatniego dopasowania jednego przejscia (pozycja w pliku)
+    let mut koniec_przejscia: usize = 0;
+    let wyrazenie = wzorzec.wyrazenie.clone();
+    let mut odbierz = |wynik: Result<WynikPorcji, tokio::task::JoinError>| {
+        let (watek, count, praca, brzegi) = wynik.expect("zadanie liczace zakonczylo sie panika");
+        total += count;
+        match watki.iter_mut().find(|(id, _)| *id == watek) {
+            Some((_, licznik)) => licznik.scal(praca),
+            None => watki.push((watek, praca)),
+        }
+        let (Some(wyrazenie), Some((numer, poczatek, obszar, porcja))) = (&wyrazenie, brzegi) else { return };
+        oczekujace.insert(numer, (poczatek, obszar, porcja));
+        while let Some((poczatek, obszar, porcja)) = oczekujace.remove(&nastepna) {
+            let (liczba, koniec) = wyrazenie.uzgodnij(&porcja, &obszar, koniec_przejscia.saturating_sub(poczatek));
+            total += liczba - obszar.liczba;
+            koniec_przejscia = koniec_przejscia.max(poczatek + koniec);
+            nastepna += 1;
+        }
     };
 
-    Metrics {
-        count: total,
-        czas,
-        cpu_usage: (end_cpu - start_cpu) / (czas * num_cpus) * 100.0,
-        ram_usage: end_mem - start_mem,
+    // Tryb dokladny: porcja zaczyna sie od OVERLAP_SIZE bajtow poprzedniej - te same fragmenty co podziel_na_fragmenty.
+    // Tryb regex: zakladka 2*maks, a porcja z nowymi bajtami pliku [a, b) liczy dopasowania zaczete w [a - maks, b - maks),
+    // zeby miec maks bajtow z obu stron; ostatnie maks bajtow pliku liczy porcja z sama zakladka po koncu odczytu
+    let maks = wzorzec.wyrazenie.as_ref().map(Wyrazenie::maks);
+    let dlugosc_zakladki = maks.map_or(OVERLAP_SIZE, |maks| 2 * maks);
+    let mut zakladka: Vec<u8> = Vec::new();
+    // Bajty pliku odczytane przed biezaca porcja
+    let mut przeczytane = 0;
+    let mut numer_porcji = 0;
+    loop {
+        let mut porcja = Vec::with_capacity(zakladka.len() + rozmiar_porcji);
+        porcja.extend_from_slice(&zakladka);
+        let nowe = zrodlo.czytaj(&mut porcja, rozmiar_porcji).await.map_err(blad_odczytu)?;
+        let koniec = nowe == 0;
+        if koniec && maks.is_none() {
+            break;
+        }
+        let zakladka_porcji = zakladka.len();
+        let poczatek_porcji = przeczytane - zakladka_porcji;
+        przeczytane += nowe;
+        let obszar = match maks {
+            None => (0, porcja.len()),
+            Some(maks) => {
+                let od = (przeczytane - nowe).saturating_sub(maks);
+                let do_ = if koniec { przeczytane } else { przeczytane.saturating_sub(maks).max(od) };
+                (od - poczatek_porcji, do_ - poczatek_porcji)
+            }
+        };
+        zakladka = porcja[porcja.len().saturating_sub(dlugosc_zakladki)..].to_vec();
+
+        if zadania.len() >= liczba_watkow {
+            if let Some(wynik) = zadania.join_next().await {
+                odbierz(wynik);
+            }
+        }
+        let wzorzec = Arc::clone(&wzorzec);
+        let numer = numer_porcji;
+        numer_porcji += 1;
+        zadania.spawn_blocking(move || {
+            let poczatek = Instant::now();
+            let mut praca = LicznikPracy::default();
+            let dlugosc = porcja.len();
+            let (count, brzegi) = match &wzorzec.wyrazenie {
+                Some(wyrazenie) => {
+                    let obszar = wyrazenie.dopasowania_w_obszarze(&porcja, obszar);
+                    (obszar.liczba, Some((numer, poczatek_porcji, obszar, porcja)))
+                }
+                None => (liczba_wlasnych(&porcja, zakladka_porcji, &wzorzec), None),
+            };
+            praca.dodaj(dlugosc, poczatek);
+            (std::thread::current().id(), count, praca, brzegi)
+        });
+        if koniec {
+            break;
+        }
+    }
+    while let Some(wynik) = zadania.join_next().await {
+        odbierz(wynik);
+    }
+
+    let dekompresja = zrodlo.czas_dekompresji().await;
+    Ok((total, watki.into_iter().map(|(_, licznik)| licznik).collect(), dekompresja))
+}
+
+// Uruchamia wybrana implementacje w trybie z ustawienia.tryb (dla sekwencyjnej liczba_watkow jest ignorowana)
+pub fn zmierz(
+    implementacja: Implementacja,
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    if ustawienia.tryb == TrybDopasowania::Rozmyty {
+        return rozmyte::zmierz(implementacja, sciezka_pliku, slowo, liczba_watkow, ustawienia);
+    }
+    match implementacja {
+        Implementacja::Sekwencyjny => liczba_slow_sekwencyjny(sciezka_pliku, slowo, ustawienia),
+        Implementacja::ArcThreads => liczba_slow_arc_threads(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        Implementacja::Rayon => liczba_slow_rayon(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        Implementacja::ScopedThreads => liczba_slow_scoped_threads(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        Implementacja::Kanaly => liczba_slow_kanaly(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        Implementacja::Atomiki => liczba_slow_atomiki(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        Implementacja::PulaWatkow => liczba_slow_pula_watkow(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        Implementacja::Tokio => liczba_slow_tokio(sciezka_pliku, slowo, liczba_watkow, ustawienia),
     }
 }
 
-pub fn liczenie_slow() {
+// Interaktywny pomiar wszystkich implementacji; zwraca raport do zapisania w historii
+pub fn liczenie_slow() -> Wynik<Raport> {
     // Ustawienie kodowania UTF-8 dla konsoli
     unsafe {
         winapi::um::wincon::SetConsoleOutputCP(65001);
     }
 
     // Pobierz dane od użytkownika
-    let mut nazwa_uzytkownika = String::new();
-    println!("Podaj nazwe uzytkownika: ");
-    io::stdin().read_line(&mut nazwa_uzytkownika).unwrap();
-    let nazwa_uzytkownika = nazwa_uzytkownika.trim();
+    let nazwa_uzytkownika = wczytaj_linie("Podaj nazwe uzytkownika: ")?;
 
-    let mut liczba_watkow = String::new();
-    println!("Podaj ilosc watkow: ");
-    io::stdin().read_line(&mut liczba_watkow).unwrap();
-    let liczba_watkow: usize = liczba_watkow.trim().parse().unwrap();
+    let liczba_watkow: usize = zapytaj("Podaj ilosc watkow: ", "ilosc watkow", co_najmniej(1))?;
 
-    let mut liczba_slow = String::new();
-    println!("Podaj ilosc slow do sprawdzenia: ");
-    io::stdin().read_line(&mut liczba_slow).unwrap();
-    let liczba_slow: usize = liczba_slow.trim().parse().unwrap();
+    let liczba_slow: usize = zapytaj(
+        "Podaj ilosc slow do sprawdzenia: ",
+        "ilosc slow",
+        co_najmniej(1),
+    )?;
 
     let mut slowa = Vec::with_capacity(liczba_slow);
     for i in 0..liczba_slow {
-        let mut slowo = String::new();
-        println!("Podaj {}. slowo: ", i + 1);
-        io::stdin().read_line(&mut slowo).unwrap();
-        slowa.push(slowo.trim().to_string());
+        let slowo: String = zapytaj(&format!("Podaj {}. slowo: ", i + 1), "slowo", |s: &String| {
+            if s.is_empty() {
+                Err("niepuste slowo".to_string())
+            } else {
+                Ok(())
+            }
+        })?;
+        slowa.push(slowo);
     }
 
     // Skonstruuj pełną ścieżkę do pliku
-    let mut sciezka_pliku = String::new();
-    println!("Podaj sciezke do pliku: ");
-    io::stdin().read_line(&mut sciezka_pliku).unwrap();
-    let sciezka_pliku = format!(
-        "C:\\Users\\{}\\Desktop\\{}",
-        nazwa_uzytkownika,
-        sciezka_pliku.trim()
-    );
+    let pelna_sciezka = |plik: &str| format!("C:\\Users\\{}\\Desktop\\{}", nazwa_uzytkownika, plik);
+    let plik: String = zapytaj("Podaj sciezke do pliku: ", "sciezka do pliku", |plik: &String| {
+        if Path::new(&pelna_sciezka(plik)).is_file() {
+            Ok(())
+        } else {
+            Err(format!("istniejacy plik ({} nie istnieje)", pelna_sciezka(plik)))
+        }
+    })?;
+    let sciezka_pliku = pelna_sciezka(&plik);
 
     // Zmienne do podsumowania
     let mut total_seq = 0;
@@ -360,27 +1248,80 @@ pub fn liczenie_slow() {
     let mut ram_seq = 0;
     let mut ram_arcthr = 0;
     let mut ram_ray = 0;
+    let mut rekordy = Vec::new();
 
+    let ustawienia = UstawieniaLiczenia::default();
+    // Referencja (manifest korpusu albo jedno przejscie po pliku) liczona raz, przed pomiarami
+    let referencje = Referencje::przygotuj(&sciezka_pliku, &sciezka_pliku, &slowa, &[ustawienia.tryb], &ustawienia)?;
     for slowo in &slowa {
         // Wywołaj wszystkie implementacje
-        let result_seq = liczba_slow_sekwencyjny(&sciezka_pliku, slowo);
-        let result_arcthr = liczba_slow_arc_threads(&sciezka_pliku, slowo, liczba_watkow);
-        let result_ray = liczba_slow_rayon(&sciezka_pliku, slowo, liczba_watkow);
+        let mut result_seq = liczba_slow_sekwencyjny(&sciezka_pliku, slowo, &ustawienia)?;
+        let mut result_arcthr = liczba_slow_arc_threads(&sciezka_pliku, slowo, liczba_watkow, &ustawienia)?;
+        let mut result_ray = liczba_slow_rayon(&sciezka_pliku, slowo, liczba_watkow, &ustawienia)?;
+        if let Some(referencja) = referencje.dla(slowo, ustawienia.tryb) {
+            for wynik in [&mut result_seq, &mut result_arcthr, &mut result_ray] {
+                wynik.weryfikacja =
+                    Some(weryfikacja::zweryfikuj(wynik.count, referencja, &sciezka_pliku, slowo, &ustawienia)?);
+            }
+        }
 
         // Wyświetl wyniki dla bieżącego słowa
         println!("\nSlowo: {}", slowo);
-        println!(
-            "Sekwencyjnie: {} (czas: {:.2}s, CPU: {:.1}%, RAM: {} B)",
-            result_seq.count, result_seq.czas, result_seq.cpu_usage, result_seq.ram_usage
-        );
-        println!(
-            "Arc/Threads: {} (czas: {:.2}s, CPU: {:.1}%, RAM: {} B)",
-            result_arcthr.count, result_arcthr.czas, result_arcthr.cpu_usage, result_arcthr.ram_usage
-        );
-        println!(
-            "Rayon: {} (czas: {:.2}s, CPU: {:.1}%, RAM: {} B)",
-            result_ray.count, result_ray.czas, result_ray.cpu_usage, result_ray.ram_usage
-        );
+        for (nazwa, wynik) in [
+            ("Sekwencyjnie", &result_seq),
+            ("Arc/Threads", &result_arcthr),
+            ("Rayon", &result_ray),
+        ] {
+            println!(
+                "{}: {} (czas: {:.2}s, CPU: {:.1}% / {:.1}% rdzenia, user {:.2}s, sys {:.2}s, RAM: {:+} B, szczyt {} B)",
+                nazwa,
+                wynik.count,
+                wynik.czas,
+                wynik.cpu_usage,
+                wynik.cpu_usage_abs,
+                wynik.cpu_user,
+                wynik.cpu_system,
+                wynik.ram_usage,
+                wynik.ram_szczyt
+            );
+            if let Some(w) = &wynik.weryfikacja {
+                if w.zgodna(wynik.count) {
+                    println!("    zgodny z referencja ({})", w.zrodlo.nazwa());
+                } else {
+                    println!("    NIEZGODNOSC: oczekiwano {} ({})", w.oczekiwane, w.zrodlo.nazwa());
+                    for f in w.fragmenty.iter().take(POKAZANE_FRAGMENTY) {
+                        println!(
+                            "    fragment {} [{}, {}): oczekiwano {}, policzono {}",
+                            f.indeks, f.start, f.koniec, f.oczekiwane, f.policzone
+                        );
+                    }
+                    if w.fragmenty.is_empty() {
+                        println!("    fragmenty zgodne - rozbieznosc przy scalaniu wynikow");
+                    } else if w.fragmenty.len() > POKAZANE_FRAGMENTY {
+                        println!("    ... i {} kolejnych fragmentow", w.fragmenty.len() - POKAZANE_FRAGMENTY);
+                    }
+                }
+            }
+            if let Some(a) = &wynik.alokacje {
+                println!(
+                    "    alokacje: {} ({} B zaalokowane, {} B zwolnione, szczyt sterty {} B, watki: {})",
+                    a.liczba,
+                    a.zaalokowane,
+                    a.zwolnione,
+                    a.szczyt,
+                    a.watki.len()
+                );
+            }
+            if let Some(rozklad) = &wynik.rozklad {
+                println!("    nierownowaga obciazenia: {:.2}", rozklad.nierownowaga);
+                for w in &rozklad.watki {
+                    println!(
+                        "    watek {}: {} fragm., {} B, praca {:.3}s, czekanie na join {:.3}s",
+                        w.watek, w.fragmenty, w.bajty, w.praca, w.bezczynnosc
+                    );
+                }
+            }
+        }
 
         // Aktualizuj statystyki podsumowujące
         total_seq += result_seq.count;
@@ -398,6 +1339,26 @@ pub fn liczenie_slow() {
         ram_seq += result_seq.ram_usage;
         ram_arcthr += result_arcthr.ram_usage;
         ram_ray += result_ray.ram_usage;
+
+        for (implementacja, metrics) in [
+            (Implementacja::Sekwencyjny, result_seq),
+            (Implementacja::ArcThreads, result_arcthr),
+            (Implementacja::Rayon, result_ray),
+        ] {
+            let watki = if implementacja.rownolegla() { liczba_watkow } else { 1 };
+            rekordy.push(RekordWyniku {
+                benchmark: "liczenie_slow".to_string(),
+                implementacja: implementacja.nazwa().to_string(),
+                parametry: BTreeMap::from([
+                    ("plik".to_string(), sciezka_pliku.clone()),
+                    ("slowo".to_string(), slowo.clone()),
+                    ("tryb".to_string(), TrybDopasowania::Dokladny.nazwa().to_string()),
+                    ("watki".to_string(), watki.to_string()),
+                ]),
+                powtorzenie: 1,
+                metrics,
+            });
+        }
     }
 
     // Wyświetl podsumowanie globalne
@@ -407,15 +1368,99 @@ pub fn liczenie_slow() {
 
     println!("\nPodsumowanie:");
     println!(
-        "Sekwencyjnie: {} (czas: {:.2}s, średnie CPU: {:.1}%, RAM: {} B)",
+        "Sekwencyjnie: {} (czas: {:.2}s, średnie CPU: {:.1}%, RAM: {:+} B)",
         total_seq, time_seq, avg_cpu_seq, ram_seq
     );
     println!(
-        "Threading: {} (czas: {:.2}s, średnie CPU: {:.1}%, RAM: {} B)",
+        "Threading: {} (czas: {:.2}s, średnie CPU: {:.1}%, RAM: {:+} B)",
         total_arcthr, time_arcthr, avg_cpu_arcthr, ram_arcthr
     );
     println!(
-        "Rayon: {} (czas: {:.2}s, średnie CPU: {:.1}%, RAM: {} B)",
+        "Rayon: {} (czas: {:.2}s, średnie CPU: {:.1}%, RAM: {:+} B)",
         total_ray, time_ray, avg_cpu_omp, ram_ray
     );
-}
\ No newline at end of file
+    Ok(Raport {
+        nazwa: "liczenie_slow (konsola)".to_string(),
+        seed: None,
+        srodowisko: Some(Srodowisko::wykryj()),
+        rekordy,
+    })
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+
+    // Plik tymczasowy z podana trescia, usuwany po tescie
+    struct PlikTestowy(std::path::PathBuf);
+
+    impl PlikTestowy {
+        fn nowy(nazwa: &str, tresc: &[u8]) -> Self {
+            let sciezka = std::env::temp_dir().join(format!("liczenie_slow_{}_{}", std::process::id(), nazwa));
+            std::fs::write(&sciezka, tresc).unwrap();
+            PlikTestowy(sciezka)
+        }
+
+        fn sciezka(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for PlikTestowy {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn puste_slowo_i_zero_watkow_to_bledy_parametru() {
+        let plik = PlikTestowy::nowy("parametry", b"ala ma kota");
+        let ustawienia = UstawieniaLiczenia::default();
+        for implementacja in Implementacja::WSZYSTKIE {
+            assert!(zmierz(implementacja, plik.sciezka(), "", 2, &ustawienia).is_err(), "{}", implementacja.nazwa());
+            if implementacja.rownolegla() {
+                assert!(zmierz(implementacja, plik.sciezka(), "kota", 0, &ustawienia).is_err(), "{}", implementacja.nazwa());
+            }
+        }
+    }
+
+    #[test]
+    fn pusty_wzorzec_bajtow_nie_ma_wystapien() {
+        for algorytm in [Algorytm::Kmp, Algorytm::Simd, Algorytm::Memmem] {
+            assert_eq!(liczba_slow_we_fragmencie(b"abc", &przygotuj_wzorzec_bajtow(b"", algorytm)), 0);
+        }
+    }
+
+    #[test]
+    fn silniki_licza_zakladke_i_granice_raz() {
+        // Fragmenty po 4 KiB: "kot" w zakladce, "kot" na granicy, "aaaa" na granicy
+        let mut tresc = vec![b'.'; 3 * MIN_ROZMIAR_FRAGMENTU];
+        for (poz, slowo) in [(4000, &b"kot"[..]), (8190, b"kot"), (4094, b"aaaa")] {
+            tresc[poz..poz + slowo.len()].copy_from_slice(slowo);
+        }
+        let plik = PlikTestowy::nowy("zakladka", &tresc);
+        let ustawienia = UstawieniaLiczenia { fragment: MIN_ROZMIAR_FRAGMENTU, ..UstawieniaLiczenia::default() };
+        for (slowo, oczekiwane) in [("kot", 2), ("aa", 3)] {
+            for implementacja in Implementacja::WSZYSTKIE {
+                let metrics = zmierz(implementacja, plik.sciezka(), slowo, 2, &ustawienia).unwrap();
+                assert_eq!(metrics.count, oczekiwane, "{} {}", slowo, implementacja.nazwa());
+            }
+        }
+    }
+
+    #[test]
+    fn najdluzsze_slowo_na_granicy_liczone_dluzsze_odrzucone() {
+        // Slowo od 3840 (poczatek drugiego fragmentu) do 4096 - ostatni bajt tuz za granica
+        let slowo = "x".repeat(MAKS_DLUGOSC_SLOWA);
+        let mut tresc = vec![b'.'; 2 * MIN_ROZMIAR_FRAGMENTU];
+        tresc[MIN_ROZMIAR_FRAGMENTU - OVERLAP_SIZE..][..slowo.len()].copy_from_slice(slowo.as_bytes());
+        let plik = PlikTestowy::nowy("dlugie_slowo", &tresc);
+        let ustawienia = UstawieniaLiczenia { fragment: MIN_ROZMIAR_FRAGMENTU, ..UstawieniaLiczenia::default() };
+        for implementacja in Implementacja::WSZYSTKIE {
+            let metrics = zmierz(implementacja, plik.sciezka(), &slowo, 2, &ustawienia).unwrap();
+            assert_eq!(metrics.count, 1, "{}", implementacja.nazwa());
+            assert!(zmierz(implementacja, plik.sciezka(), &format!("{}x", slowo), 2, &ustawienia).is_err());
+        }
+    }
+}
+
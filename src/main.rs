@@ -1,26 +1,26 @@
-use std::io;
+use std::env;
+use std::process;
 
-mod liczenie_slow;
-mod rsa_aes;
+// cargo build --features alokacje: kazdy pomiar dostaje liczniki alokacji sterty
+#[cfg(feature = "alokacje")]
+#[global_allocator]
+static ALOKATOR: rsa_liczenie_slow_rust::alokacje::LicznikAlokacji = rsa_liczenie_slow_rust::alokacje::LicznikAlokacji;
 
-fn main() {
-    println!("Wybierz opcje:");
-    println!("1. Liczenie słów w pliku");
-    println!("2. Szyfrowanie RSA/AES");
+use rsa_liczenie_slow_rust::cli::Argumenty;
+use rsa_liczenie_slow_rust::Blad;
 
-    let mut wybor = String::new();
-    io::stdin().read_line(&mut wybor).expect("Błąd czytania linii");
-    let wybor: i32 = match wybor.trim().parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("Nieprawidłowy wybór");
-            return;
-        }
-    };
+// Konczy program kodem wyjscia przypisanym do rodzaju bledu
+fn zakoncz_bledem(blad: Blad) -> ! {
+    eprintln!("Błąd: {}", blad);
+    process::exit(blad.kod_wyjscia());
+}
 
-    match wybor {
-        1 => liczenie_slow::liczenie_slow(),
-        2 => rsa_aes::szyfrowanie(),
-        _ => println!("Nieprawidlowy wybor"),
+fn main() {
+    // Opis argumentow i polecen w src/cli.rs
+    let argumenty = Argumenty::parsuj(env::args().skip(1)).unwrap_or_else(|blad| zakoncz_bledem(blad));
+    match argumenty.uruchom() {
+        Ok(0) => {}
+        Ok(kod) => process::exit(kod),
+        Err(blad) => zakoncz_bledem(blad),
     }
-}
\ No newline at end of file
+}
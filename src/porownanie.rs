@@ -0,0 +1,388 @@
+// src/porownanie.rs
+// Baseline: zapis raportu pod nazwa i porownanie kolejnego przebiegu (test Welcha per metryka)
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use crate::blad::{Blad, Wynik};
+use crate::wyniki::{Raport, RekordWyniku};
+
+// Katalog z zapisanymi baseline'ami, wzgledem katalogu roboczego
+pub const KATALOG_BASELINE: &str = "baseline";
+// Kod wyjscia, gdy ktoras metryka pogorszyla sie istotnie i ponad prog
+pub const KOD_REGRESJI: i32 = 5;
+// Poziom istotnosci testu Welcha
+pub const ALFA: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Werdykt {
+    Poprawa,
+    Regresja,
+    BezZmian,
+}
+
+impl Werdykt {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            Werdykt::Poprawa => "poprawa",
+            Werdykt::Regresja => "REGRESJA",
+            Werdykt::BezZmian => "bez zmian",
+        }
+    }
+}
+
+// Wynik porownania jednej metryki jednej serii (klucz z RekordWyniku::klucz)
+#[derive(Debug, Clone)]
+pub struct PorownanieMetryki {
+    pub seria: String,
+    pub metryka: &'static str,
+    pub baseline: f64,
+    pub obecnie: f64,
+    // Zmiana sredniej wzgledem baseline w procentach
+    pub zmiana: f64,
+    // None, gdy ktoras strona ma mniej niz 2 powtorzenia
+    pub p: Option<f64>,
+    pub werdykt: Werdykt,
+}
+
+#[derive(Debug, Clone)]
+pub struct WynikPorownania {
+    pub baseline: String,
+    pub prog: f64,
+    pub metryki: Vec<PorownanieMetryki>,
+    // Serie obecne tylko po jednej stronie
+    pub tylko_w_baseline: Vec<String>,
+    pub tylko_obecnie: Vec<String>,
+    // Rozne srodowiska (sprzet, kompilator, profil) - wynik moze nie byc porownywalny
+    pub uwagi: Vec<String>,
+}
+
+// Metryki porownywane dla kazdej serii; mniejsza wartosc jest zawsze lepsza.
+// Zamiast procentu CPU brany jest czas CPU (user + system), bo szybszy przebieg
+// przy tej samej pracy ma wyzszy procent uzycia, a nie jest przeciez gorszy
+type Metryka = (&'static str, fn(&RekordWyniku) -> f64);
+
+const METRYKI: [Metryka; 4] = [
+    ("czas [s]", |r| r.metrics.czas),
+    ("czas CPU [s]", czas_cpu),
+    ("RAM [B]", |r| r.metrics.ram_usage as f64),
+    ("szczyt RAM [B]", |r| r.metrics.ram_szczyt as f64),
+];
+
+// Baseline sprzed podzialu na user/system ma tylko procent CPU - czas odtwarzany z niego
+// (z dokladnoscia do liczby rdzeni, stalej na tym samym sprzecie)
+fn czas_cpu(r: &RekordWyniku) -> f64 {
+    let m = &r.metrics;
+    if m.cpu_user > 0.0 || m.cpu_system > 0.0 || m.cpu_usage_abs > 0.0 {
+        m.cpu_user + m.cpu_system
+    } else {
+        m.cpu_usage / 100.0 * m.czas
+    }
+}
+
+fn sciezka_baseline(nazwa: &str) -> Wynik<PathBuf> {
+    let poprawna = !nazwa.is_empty()
+        && nazwa.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !nazwa.starts_with('.');
+    if !poprawna {
+        return Err(Blad::parametr("baseline", nazwa, "nazwa z liter, cyfr, '-', '_' lub '.'"));
+    }
+    Ok(PathBuf::from(KATALOG_BASELINE).join(format!("{}.json", nazwa)))
+}
+
+pub fn zapisz_baseline(nazwa: &str, raport: &Raport) -> Wynik<PathBuf> {
+    let sciezka = sciezka_baseline(nazwa)?;
+    std::fs::create_dir_all(KATALOG_BASELINE)
+        .map_err(|e| Blad::io(format!("tworzenie katalogu {}", KATALOG_BASELINE), e))?;
+    let json = serde_json::to_string_pretty(raport).map_err(|e| Blad::io("serializacja baseline", e.into()))?;
+    std::fs::write(&sciezka, json).map_err(|e| Blad::io(format!("zapis baseline {}", sciezka.display()), e))?;
+    Ok(sciezka)
+}
+
+pub fn wczytaj_baseline(nazwa: &str) -> Wynik<Raport> {
+    let sciezka = sciezka_baseline(nazwa)?;
+    let tekst = std::fs::read_to_string(&sciezka)
+        .map_err(|e| Blad::io(format!("odczyt baseline {}", sciezka.display()), e))?;
+    serde_json::from_str(&tekst).map_err(|e| Blad::Parsowanie {
+        pole: format!("baseline {}", sciezka.display()),
+        wartosc: format!("linia {}, kolumna {}", e.line(), e.column()),
+        zrodlo: e.to_string(),
+    })
+}
+
+fn serie(raport: &Raport) -> BTreeMap<String, Vec<&RekordWyniku>> {
+    let mut serie: BTreeMap<String, Vec<&RekordWyniku>> = BTreeMap::new();
+    for r in &raport.rekordy {
+        serie.entry(r.klucz()).or_default().push(r);
+    }
+    serie
+}
+
+fn srednia(x: &[f64]) -> f64 {
+    x.iter().sum::<f64>() / x.len() as f64
+}
+
+// Wariancja z proby (dzielnik n-1)
+fn wariancja(x: &[f64]) -> f64 {
+    let m = srednia(x);
+    x.iter().map(|v| (v - m) * (v - m)).sum::<f64>() / (x.len() - 1) as f64
+}
+
+// Logarytm funkcji gamma (przyblizenie Lanczosa, g=7)
+fn ln_gamma(x: f64) -> f64 {
+    const C: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut a = C[0];
+    let t = x + 7.5;
+    for (i, &c) in C.iter().enumerate().skip(1) {
+        a += c / (x + i as f64);
+    }
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+// Ulamek lancuchowy funkcji beta niepelnej (metoda Lentza)
+fn beta_ulamek(a: f64, b: f64, x: f64) -> f64 {
+    const MALE: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < MALE {
+        d = MALE;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        let aa = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 + aa * d;
+        if d.abs() < MALE {
+            d = MALE;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < MALE {
+            c = MALE;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+        let aa = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 + aa * d;
+        if d.abs() < MALE {
+            d = MALE;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < MALE {
+            c = MALE;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+// Regularyzowana funkcja beta niepelna I_x(a, b)
+fn beta_niepelna(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_bt = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_bt.exp() * beta_ulamek(a, b, x) / a
+    } else {
+        1.0 - ln_bt.exp() * beta_ulamek(b, a, 1.0 - x) / b
+    }
+}
+
+// Dwustronne p testu Welcha; None przy mniej niz 2 probkach po ktorejs stronie
+pub fn test_welcha(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+    let (va, vb) = (wariancja(a) / a.len() as f64, wariancja(b) / b.len() as f64);
+    let roznica = srednia(a) - srednia(b);
+    if va + vb == 0.0 {
+        // Obie serie stale: roznica jest pewna albo jej nie ma
+        return Some(if roznica == 0.0 { 1.0 } else { 0.0 });
+    }
+    let t = roznica / (va + vb).sqrt();
+    let df = (va + vb).powi(2) / (va * va / (a.len() - 1) as f64 + vb * vb / (b.len() - 1) as f64);
+    Some(beta_niepelna(df / 2.0, 0.5, df / (df + t * t)))
+}
+
+// Regresja wymaga istotnosci (p < ALFA) i pogorszenia o wiecej niz `prog` procent.
+// Inny wynik liczenia (count) niz w baseline jest zawsze regresja - o ile baseline ma te sama WERSJA_LICZENIA
+pub fn porownaj(baseline: &Raport, obecny: &Raport, nazwa_baseline: &str, prog: f64) -> WynikPorownania {
+    let serie_baseline = serie(baseline);
+    let serie_obecne = serie(obecny);
+    let mut metryki = Vec::new();
+    let mut uwagi = Vec::new();
+    let wersja = |raport: &Raport| raport.srodowisko.as_ref().map_or(0, |s| s.wersja_liczenia);
+    let porownaj_count = wersja(baseline) == wersja(obecny);
+    if !porownaj_count {
+        uwagi.push(format!(
+            "baseline z innej wersji regul liczenia ({} vs {}) - count pominiety, zapisz baseline ponownie",
+            wersja(baseline),
+            wersja(obecny)
+        ));
+    }
+
+    for (klucz, obecne) in &serie_obecne {
+        let Some(bazowe) = serie_baseline.get(klucz) else { continue };
+
+        if porownaj_count {
+            let count_b: Vec<f64> = bazowe.iter().map(|r| r.metrics.count as f64).collect();
+            let count_o: Vec<f64> = obecne.iter().map(|r| r.metrics.count as f64).collect();
+            let zgodne = bazowe.iter().chain(obecne.iter()).all(|r| r.metrics.count == bazowe[0].metrics.count);
+            metryki.push(PorownanieMetryki {
+                seria: klucz.clone(),
+                metryka: "count",
+                baseline: srednia(&count_b),
+                obecnie: srednia(&count_o),
+                zmiana: zmiana_procentowa(srednia(&count_b), srednia(&count_o)),
+                p: None,
+                werdykt: if zgodne { Werdykt::BezZmian } else { Werdykt::Regresja },
+            });
+        }
+
+        for (metryka, wartosc) in METRYKI {
+            let b: Vec<f64> = bazowe.iter().map(|r| wartosc(r)).collect();
+            let o: Vec<f64> = obecne.iter().map(|r| wartosc(r)).collect();
+            let (sb, so) = (srednia(&b), srednia(&o));
+            let zmiana = zmiana_procentowa(sb, so);
+            let p = test_welcha(&b, &o);
+            let istotna = p.is_some_and(|p| p < ALFA);
+            let werdykt = if !istotna || zmiana.is_nan() || zmiana.abs() <= prog {
+                Werdykt::BezZmian
+            } else if zmiana > 0.0 {
+                Werdykt::Regresja
+            } else {
+                Werdykt::Poprawa
+            };
+            metryki.push(PorownanieMetryki {
+                seria: klucz.clone(),
+                metryka,
+                baseline: sb,
+                obecnie: so,
+                zmiana,
+                p,
+                werdykt,
+            });
+        }
+    }
+
+    if let (Some(b), Some(o)) = (&baseline.srodowisko, &obecny.srodowisko) {
+        if !b.ten_sam_sprzet(o) {
+            uwagi.push(format!(
+                "baseline z innego sprzetu: {} / {} vs {} / {}",
+                b.host, b.model_cpu, o.host, o.model_cpu
+            ));
+        }
+        if b.rustc != o.rustc || b.profil != o.profil {
+            uwagi.push(format!(
+                "inna kompilacja: {} {} vs {} {}",
+                b.rustc, b.profil, o.rustc, o.profil
+            ));
+        }
+        if !b.simd.is_empty() && b.simd != o.simd {
+            uwagi.push(format!("inny wariant algorytmu simd: {} vs {}", b.simd, o.simd));
+        }
+    }
+
+    WynikPorownania {
+        baseline: nazwa_baseline.to_string(),
+        prog,
+        metryki,
+        tylko_w_baseline: serie_baseline.keys().filter(|k| !serie_obecne.contains_key(*k)).cloned().collect(),
+        tylko_obecnie: serie_obecne.keys().filter(|k| !serie_baseline.contains_key(*k)).cloned().collect(),
+        uwagi,
+    }
+}
+
+// Wzgledem |baseline|, bo zmiana pamieci moze byc ujemna - wzrost zawsze daje wynik dodatni
+fn zmiana_procentowa(baseline: f64, obecnie: f64) -> f64 {
+    if baseline == 0.0 {
+        if obecnie == 0.0 {
+            0.0
+        } else {
+            f64::NAN
+        }
+    } else {
+        (obecnie - baseline) / baseline.abs() * 100.0
+    }
+}
+
+impl WynikPorownania {
+    pub fn regresje(&self) -> usize {
+        self.metryki.iter().filter(|m| m.werdykt == Werdykt::Regresja).count()
+    }
+
+    pub fn jako_tekst(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "=== Porownanie z baseline '{}' (prog {:.1}%, alfa {}) ===",
+            self.baseline, self.prog, ALFA
+        );
+        let _ = writeln!(
+            out,
+            "{:<64} {:<13} {:>14} {:>14} {:>9} {:>8}  werdykt",
+            "seria", "metryka", "baseline", "obecnie", "zmiana", "p"
+        );
+        let mut za_malo_powtorzen = false;
+        for m in &self.metryki {
+            let p = match m.p {
+                Some(p) => format!("{:.4}", p),
+                None if m.metryka == "count" => "-".to_string(),
+                None => {
+                    za_malo_powtorzen = true;
+                    "n<2".to_string()
+                }
+            };
+            let _ = writeln!(
+                out,
+                "{:<64} {:<13} {:>14.6} {:>14.6} {:>8.1}% {:>8}  {}",
+                m.seria,
+                m.metryka,
+                m.baseline,
+                m.obecnie,
+                m.zmiana,
+                p,
+                m.werdykt.nazwa()
+            );
+        }
+        for seria in &self.tylko_w_baseline {
+            let _ = writeln!(out, "{:<64} brak w obecnym przebiegu", seria);
+        }
+        for seria in &self.tylko_obecnie {
+            let _ = writeln!(out, "{:<64} brak w baseline", seria);
+        }
+        for uwaga in &self.uwagi {
+            let _ = writeln!(out, "Uwaga: {}", uwaga);
+        }
+        if za_malo_powtorzen {
+            let _ = writeln!(out, "Uwaga: test istotnosci wymaga co najmniej 2 powtorzen po obu stronach (powtorzenia w scenariuszu)");
+        }
+        let poprawy = self.metryki.iter().filter(|m| m.werdykt == Werdykt::Poprawa).count();
+        let _ = writeln!(out, "Regresje: {}, poprawy: {}", self.regresje(), poprawy);
+        out
+    }
+}
@@ -0,0 +1,357 @@
+// src/pozycje.rs
+// Pozycje trafien (offset, linia, kolumna) i kontekst w stylu grep -n -C.
+// Fragmenty nachodza na siebie (OVERLAP_SIZE), wiec kazdy fragment zglasza tylko trafienia,
+// ktorych poprzedni fragment nie mogl zobaczyc w calosci - wynik bez duplikatow, w kolejnosci pliku
+use std::sync::Arc;
+use std::thread;
+
+use memchr::{memchr_iter, memrchr};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::blad::{Blad, Wynik};
+use crate::kompresja;
+use crate::liczenie_slow::{
+    podziel_na_fragmenty_po, pozycje_we_fragmencie, przygotuj_wzorzec, sprawdz_dlugosc_slowa, sprawdz_slowo, sprawdz_watki, Implementacja,
+    KmpPreprocessed, UstawieniaLiczenia,
+};
+use crate::zasoby::{Metrics, Pomiar};
+
+// Silniki obslugujace zbieranie pozycji
+pub const IMPLEMENTACJE: [Implementacja; 3] = [Implementacja::Sekwencyjny, Implementacja::ArcThreads, Implementacja::Rayon];
+
+// Linia i kolumna liczone od 1; kolumna w znakach UTF-8, offset w bajtach od poczatku pliku
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trafienie {
+    pub offset: u64,
+    pub linia: u64,
+    pub kolumna: u64,
+}
+
+// Wynik w raporcie: liczba trafien bez duplikatow, pierwsze trafienia i ich kontekst
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trafienia {
+    pub liczba: u64,
+    pub pierwsze: Vec<Trafienie>,
+    pub kontekst: Vec<String>,
+}
+
+// Kolumna trafienia (znaki przed nim w linii): znana, gdy poczatek linii lezy we fragmencie,
+// albo liczona od poczatku fragmentu - wtedy scal dodaje znaki linii sprzed fragmentu
+#[derive(Debug, Clone, Copy)]
+enum Kolumna {
+    Znana(u64),
+    OdStartu(u64),
+}
+
+// Trafienia jednego fragmentu: (offset, linia wzgledem wlasnego poczatku fragmentu, kolumna);
+// linia moze byc ujemna dla trafien zaczetych w zakladce
+struct TrafieniaFragmentu {
+    trafienia: Vec<(u64, i64, Kolumna)>,
+    start: usize,
+    wlasny_poczatek: usize,
+    // Znaki nowej linii w wlasnym obszarze fragmentu (od konca poprzedniego do konca tego)
+    nowe_linie: u64,
+    // Znaki wlasnego obszaru za ostatnia nowa linia, a bez nowej linii - wszystkie jego znaki
+    ogon: u64,
+    // Znaki zakladki [start, wlasny_poczatek), jesli nie ma w niej nowej linii
+    zakladka: Option<u64>,
+}
+
+// Znaki UTF-8: bajty kontynuacji (10xxxxxx) nie zaczynaja znaku
+fn znaki(bajty: &[u8]) -> u64 {
+    bajty.iter().filter(|&&b| b & 0xC0 != 0x80).count() as u64
+}
+
+// `wlasny_poczatek` = koniec poprzedniego fragmentu; trafienia mieszczace sie w calosci przed nim
+// zglosil juz poprzedni fragment. Linia i kolumna przesuwane od poprzedniego trafienia - kazdy bajt
+// fragmentu czytany staly raz niezaleznie od liczby trafien
+fn trafienia_fragmentu(
+    buffer: &[u8],
+    (start, end): (usize, usize),
+    wlasny_poczatek: usize,
+    wzorzec: &KmpPreprocessed,
+) -> TrafieniaFragmentu {
+    let m = wzorzec.dlugosc();
+    let mut trafienia = Vec::new();
+    let mut linia: i64 = 0;
+    let mut poz_linii = wlasny_poczatek;
+    let mut kolumna = Kolumna::OdStartu(0);
+    let mut poz_kolumny = start;
+    for p in pozycje_we_fragmencie(&buffer[start..end], wzorzec) {
+        let offset = start + p;
+        if offset + m <= wlasny_poczatek {
+            continue;
+        }
+        if offset >= poz_linii {
+            linia += memchr_iter(b'\n', &buffer[poz_linii..offset]).count() as i64;
+        } else {
+            linia -= memchr_iter(b'\n', &buffer[offset..poz_linii]).count() as i64;
+        }
+        poz_linii = offset;
+        // Trafienia rosnaco - kolumna tylko w przod
+        let odcinek = &buffer[poz_kolumny..offset];
+        kolumna = match (memrchr(b'\n', odcinek), kolumna) {
+            (Some(n), _) => Kolumna::Znana(znaki(&odcinek[n + 1..])),
+            (None, Kolumna::Znana(k)) => Kolumna::Znana(k + znaki(odcinek)),
+            (None, Kolumna::OdStartu(k)) => Kolumna::OdStartu(k + znaki(odcinek)),
+        };
+        poz_kolumny = offset;
+        trafienia.push((offset as u64, linia, kolumna));
+    }
+    let wlasny = &buffer[wlasny_poczatek.min(end)..end];
+    let zakladka = &buffer[start..wlasny_poczatek.clamp(start, end)];
+    TrafieniaFragmentu {
+        trafienia,
+        start,
+        wlasny_poczatek,
+        nowe_linie: memchr_iter(b'\n', wlasny).count() as u64,
+        ogon: znaki(&wlasny[memrchr(b'\n', wlasny).map_or(0, |n| n + 1)..]),
+        zakladka: memchr::memchr(b'\n', zakladka).is_none().then(|| znaki(zakladka)),
+    }
+}
+
+// Scalanie w kolejnosci fragmentow: linie przesuniete o znaki nowej linii wczesniejszych fragmentow,
+// kolumny liczone od poczatku fragmentu - o znaki jego linii sprzed fragmentu
+fn scal(buffer: &[u8], czesci: Vec<TrafieniaFragmentu>) -> Vec<Trafienie> {
+    let mut wynik = Vec::with_capacity(czesci.iter().map(|c| c.trafienia.len()).sum());
+    let mut linie_przed: u64 = 0;
+    // Wlasny poczatek poprzedniego fragmentu i znaki od ostatniej nowej linii przed nim
+    let mut poprzedni: (usize, u64) = (0, 0);
+    // Znaki od ostatniej nowej linii przed wlasnym poczatkiem biezacego fragmentu
+    let mut przed_wlasnym: u64 = 0;
+    for czesc in czesci {
+        // Znaki od ostatniej nowej linii przed startem fragmentu: z zakladki bez nowej linii, a gdy ja ma -
+        // z wlasnego obszaru poprzedniego fragmentu, w ktorym lezy start (tylko dla trafien przed ta linia)
+        let przed_startem = || match czesc.zakladka {
+            Some(zakladka) => przed_wlasnym.saturating_sub(zakladka),
+            None => {
+                let (od, znaki_od) = poprzedni;
+                let odcinek = &buffer[od.min(czesc.start)..czesc.start];
+                match memrchr(b'\n', odcinek) {
+                    Some(n) => znaki(&odcinek[n + 1..]),
+                    None => znaki_od + znaki(odcinek),
+                }
+            }
+        };
+        let przed_startem = czesc
+            .trafienia
+            .iter()
+            .any(|(_, _, k)| matches!(k, Kolumna::OdStartu(_)))
+            .then(przed_startem)
+            .unwrap_or(0);
+        wynik.extend(czesc.trafienia.into_iter().map(|(offset, linia, kolumna)| Trafienie {
+            offset,
+            linia: (linie_przed as i64 + linia + 1) as u64,
+            kolumna: match kolumna {
+                Kolumna::Znana(k) => k + 1,
+                Kolumna::OdStartu(k) => przed_startem + k + 1,
+            },
+        }));
+        linie_przed += czesc.nowe_linie;
+        poprzedni = (czesc.wlasny_poczatek, przed_wlasnym);
+        przed_wlasnym = if czesc.nowe_linie > 0 { czesc.ogon } else { przed_wlasnym + czesc.ogon };
+    }
+    wynik
+}
+
+// Wlasny poczatek kazdego fragmentu: koniec poprzedniego (0 dla pierwszego)
+fn z_wlasnym_poczatkiem(fragmenty: &[(usize, usize)]) -> Vec<((usize, usize), usize)> {
+    fragmenty
+        .iter()
+        .enumerate()
+        .map(|(i, &f)| (f, if i == 0 { 0 } else { fragmenty[i - 1].1 }))
+        .collect()
+}
+
+// Zbiera wszystkie trafienia wybrana implementacja; count w Metrics = liczba trafien bez duplikatow
+pub fn zbierz(
+    implementacja: Implementacja,
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<(Metrics, Vec<Trafienie>, Arc<Vec<u8>>)> {
+    sprawdz_slowo(slowo)?;
+    sprawdz_dlugosc_slowa(slowo, slowo.len())?;
+    if implementacja.rownolegla() {
+        sprawdz_watki(liczba_watkow)?;
+    }
+    let pomiar = Pomiar::start();
+
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let buffer = Arc::new(buffer);
+    let fragmenty = z_wlasnym_poczatkiem(&podziel_na_fragmenty_po(&buffer, ustawienia.fragment));
+    let wzorzec = Arc::new(przygotuj_wzorzec(slowo, ustawienia.algorytm));
+
+    let czesci: Vec<TrafieniaFragmentu> = match implementacja {
+        Implementacja::Sekwencyjny => fragmenty
+            .iter()
+            .map(|&(f, wlasny)| trafienia_fragmentu(&buffer, f, wlasny, &wzorzec))
+            .collect(),
+        // Ciagle zakresy fragmentow na watek; wyniki watkow laczone w kolejnosci zakresow
+        Implementacja::ArcThreads => {
+            let chunk_size = fragmenty.len().div_ceil(liczba_watkow.max(1)).max(1);
+            let watki: Vec<_> = fragmenty
+                .chunks(chunk_size)
+                .map(|zakres| {
+                    let buffer = Arc::clone(&buffer);
+                    let wzorzec = Arc::clone(&wzorzec);
+                    let zakres = zakres.to_vec();
+                    thread::spawn(move || {
+                        zakres
+                            .into_iter()
+                            .map(|(f, wlasny)| trafienia_fragmentu(&buffer, f, wlasny, &wzorzec))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            watki
+                .into_iter()
+                .flat_map(|watek| watek.join().expect("watek zbierajacy pozycje zakonczyl sie panika"))
+                .collect()
+        }
+        // collect na rownoleglym iteratorze indeksowanym zachowuje kolejnosc fragmentow
+        Implementacja::Rayon => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(liczba_watkow)
+                .build()
+                .map_err(|e| Blad::parametr("liczba watkow rayon", liczba_watkow, e.to_string()))?;
+            pool.install(|| {
+                fragmenty
+                    .par_iter()
+                    .map(|&(f, wlasny)| trafienia_fragmentu(&buffer, f, wlasny, &wzorzec))
+                    .collect()
+            })
+        }
+        inna => {
+            return Err(Blad::parametr("implementacja pozycji", inna.nazwa(), "sekwencyjny, arc_threads lub rayon"))
+        }
+    };
+    let trafienia = scal(&buffer, czesci);
+
+    let mut metrics = pomiar.zakoncz(i32::try_from(trafienia.len()).unwrap_or(i32::MAX));
+    metrics.czas_dekompresji = dekompresja;
+    Ok((metrics, trafienia, buffer))
+}
+
+// Granice linii zawierajacej offset: [poczatek, koniec) bez znaku nowej linii
+fn granice_linii(buffer: &[u8], offset: usize) -> (usize, usize) {
+    let poczatek = memrchr(b'\n', &buffer[..offset]).map_or(0, |n| n + 1);
+    let koniec = memchr::memchr(b'\n', &buffer[offset..]).map_or(buffer.len(), |n| offset + n);
+    (poczatek, koniec)
+}
+
+// Kontekst jak grep -n -C: "linia:tekst" dla linii z trafieniem, "linia-tekst" dla kontekstu,
+// "--" miedzy rozlacznymi grupami; linia z kilkoma trafieniami wypisana raz
+pub fn kontekst(buffer: &[u8], trafienia: &[Trafienie], linie: usize) -> Vec<String> {
+    let mut wynik = Vec::new();
+    let wypisz = |wynik: &mut Vec<String>, numer: u64, (poczatek, koniec): (usize, usize), znak: char| {
+        let tekst = String::from_utf8_lossy(&buffer[poczatek..koniec]);
+        wynik.push(format!("{}{}{}", numer, znak, tekst.trim_end_matches('\r')));
+    };
+    // Numer ostatniej wypisanej linii
+    let mut ostatnia: Option<u64> = None;
+    for (i, t) in trafienia.iter().enumerate() {
+        if ostatnia.is_some_and(|n| n >= t.linia) {
+            continue;
+        }
+        let linia_trafienia = granice_linii(buffer, t.offset as usize);
+
+        // Kontekst przed: najwyzej `linie` wstecz, bez linii juz wypisanych
+        let mut przed = Vec::new();
+        let (mut numer, mut poczatek) = (t.linia, linia_trafienia.0);
+        while przed.len() < linie && poczatek > 0 && ostatnia.is_none_or(|n| numer - 1 > n) {
+            let granice = granice_linii(buffer, poczatek - 1);
+            numer -= 1;
+            przed.push((numer, granice));
+            poczatek = granice.0;
+        }
+        if ostatnia.is_some_and(|n| numer > n + 1) {
+            wynik.push("--".to_string());
+        }
+        for &(numer, granice) in przed.iter().rev() {
+            wypisz(&mut wynik, numer, granice, '-');
+        }
+        wypisz(&mut wynik, t.linia, linia_trafienia, ':');
+
+        // Kontekst po: najwyzej `linie` w przod, bez wchodzenia na nastepna linie z trafieniem
+        let nastepna = trafienia[i + 1..].iter().map(|n| n.linia).find(|&n| n > t.linia);
+        let (mut numer, mut koniec) = (t.linia, linia_trafienia.1);
+        for _ in 0..linie {
+            if koniec + 1 >= buffer.len() || nastepna == Some(numer + 1) {
+                break;
+            }
+            let granice = granice_linii(buffer, koniec + 1);
+            numer += 1;
+            wypisz(&mut wynik, numer, granice, '-');
+            koniec = granice.1;
+        }
+        ostatnia = Some(numer);
+    }
+    wynik
+}
+
+// Podsumowanie do raportu: liczba trafien, pierwsze `limit` (0 = wszystkie) i ich kontekst
+pub fn podsumuj(buffer: &[u8], trafienia: &[Trafienie], limit: usize, linie_kontekstu: usize) -> Trafienia {
+    let pierwsze = if limit == 0 { trafienia } else { &trafienia[..limit.min(trafienia.len())] };
+    Trafienia {
+        liczba: trafienia.len() as u64,
+        pierwsze: pierwsze.to_vec(),
+        kontekst: kontekst(buffer, pierwsze, linie_kontekstu),
+    }
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+    use crate::liczenie_slow::MIN_ROZMIAR_FRAGMENTU;
+
+    // Linia i kolumna kazdego wystapienia liczone bajt po bajcie od poczatku bufora
+    fn oczekiwane(buffer: &[u8], slowo: &[u8]) -> Vec<Trafienie> {
+        let (mut linia, mut kolumna) = (1, 1);
+        let mut wynik = Vec::new();
+        for (i, &b) in buffer.iter().enumerate() {
+            if buffer[i..].starts_with(slowo) {
+                wynik.push(Trafienie { offset: i as u64, linia, kolumna });
+            }
+            if b == b'\n' {
+                (linia, kolumna) = (linia + 1, 1);
+            } else if buffer.get(i + 1).is_none_or(|&n| n & 0xC0 != 0x80) {
+                kolumna += 1;
+            }
+        }
+        wynik
+    }
+
+    fn sprawdz(nazwa: &str, tresc: &[u8], slowo: &str) {
+        let sciezka = std::env::temp_dir().join(format!("pozycje_{}_{}", std::process::id(), nazwa));
+        std::fs::write(&sciezka, tresc).unwrap();
+        let ustawienia = UstawieniaLiczenia { fragment: MIN_ROZMIAR_FRAGMENTU, ..UstawieniaLiczenia::default() };
+        let spodziewane = oczekiwane(tresc, slowo.as_bytes());
+        for implementacja in IMPLEMENTACJE {
+            let (_, trafienia, _) = zbierz(implementacja, sciezka.to_str().unwrap(), slowo, 3, &ustawienia).unwrap();
+            assert_eq!(trafienia, spodziewane, "{} {}", nazwa, implementacja.nazwa());
+        }
+        let _ = std::fs::remove_file(&sciezka);
+    }
+
+    #[test]
+    fn kolumny_bez_nowych_linii() {
+        // Jedna linia przez wszystkie fragmenty, znaki dwubajtowe, trafienia w zakladkach i na granicach
+        let tresc = "żółw ala ".repeat(3000);
+        sprawdz("bez_linii", tresc.as_bytes(), "ala");
+        sprawdz("bez_linii_zolw", tresc.as_bytes(), "żółw");
+    }
+
+    #[test]
+    fn kolumny_z_nowa_linia_w_zakladce() {
+        // Nowe linie co 4000 bajtow wypadaja w zakladkach kolejnych fragmentow
+        let mut tresc = Vec::new();
+        for i in 0..12 {
+            tresc.extend(std::iter::repeat_n(b'a', 3999));
+            tresc.push(if i % 2 == 0 { b'\n' } else { b'a' });
+        }
+        sprawdz("zakladka", &tresc, "aa");
+    }
+}
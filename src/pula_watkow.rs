@@ -0,0 +1,61 @@
+// src/pula_watkow.rs
+// Wlasna pula o stalej liczbie watkow: wspolna kolejka zadan (mpsc + Mutex), bez kradziezy pracy
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::blad::{Blad, Wynik};
+
+// Zadanie dostaje numer watku puli, ktory je wykonuje
+type Zadanie = Box<dyn FnOnce(usize) + Send + 'static>;
+
+pub struct PulaWatkow {
+    nadawca: Option<mpsc::Sender<Zadanie>>,
+    watki: Vec<JoinHandle<()>>,
+}
+
+impl PulaWatkow {
+    pub fn nowa(liczba_watkow: usize) -> Wynik<Self> {
+        let (nadawca, odbiorca) = mpsc::channel::<Zadanie>();
+        let odbiorca = Arc::new(Mutex::new(odbiorca));
+        let watki = (0..liczba_watkow.max(1))
+            .map(|id| {
+                let odbiorca = Arc::clone(&odbiorca);
+                thread::Builder::new()
+                    .name(format!("pula-{}", id))
+                    .spawn(move || loop {
+                        // Blokada tylko na czas odebrania zadania, nie jego wykonania
+                        let zadanie = odbiorca.lock().expect("mutex kolejki zatruty").recv();
+                        match zadanie {
+                            Ok(zadanie) => zadanie(id),
+                            Err(_) => break,
+                        }
+                    })
+                    .map_err(|e| Blad::io("tworzenie watku puli", e))
+            })
+            .collect::<Wynik<Vec<_>>>()?;
+        Ok(PulaWatkow {
+            nadawca: Some(nadawca),
+            watki,
+        })
+    }
+
+    pub fn liczba_watkow(&self) -> usize {
+        self.watki.len()
+    }
+
+    pub fn wykonaj(&self, zadanie: impl FnOnce(usize) + Send + 'static) {
+        if let Some(nadawca) = &self.nadawca {
+            nadawca.send(Box::new(zadanie)).expect("wszystkie watki puli zakonczyly sie panika");
+        }
+    }
+}
+
+impl Drop for PulaWatkow {
+    // Zamkniecie kanalu konczy petle watkow po wykonaniu zadan, ktore zostaly w kolejce
+    fn drop(&mut self) {
+        self.nadawca.take();
+        for watek in self.watki.drain(..) {
+            let _ = watek.join();
+        }
+    }
+}
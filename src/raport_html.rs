@@ -0,0 +1,723 @@
+// src/raport_html.rs
+// Raport HTML z wykresami SVG osadzonymi w jednym pliku (bez zewnetrznych skryptow i stylow)
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use crate::liczenie_slow::opis_rozmiaru;
+use crate::wyciek_czasowy::PROG_T;
+use crate::wyniki::{Raport, RekordWyniku, POKAZANE_FRAGMENTY};
+
+const SZEROKOSC: f64 = 820.0;
+const WYSOKOSC: f64 = 340.0;
+const MARGINES_LEWY: f64 = 80.0;
+const MARGINES_PRAWY: f64 = 200.0;
+const MARGINES_GORNY: f64 = 20.0;
+const MARGINES_DOLNY: f64 = 60.0;
+const KOLORY: [&str; 8] = ["#4e79a7", "#f28e2b", "#59a14f", "#e15759", "#76b7b2", "#edc948", "#b07aa1", "#9c755f"];
+
+fn html_escape(tekst: &str) -> String {
+    tekst
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Gorna granica osi: najblizsza "okragla" wartosc 1/2/2.5/5 x 10^n nie mniejsza niz maksimum
+fn zaokraglij_w_gore(x: f64) -> f64 {
+    if !x.is_finite() || x <= 0.0 {
+        return 1.0;
+    }
+    let rzad = 10f64.powf(x.log10().floor());
+    [1.0, 2.0, 2.5, 5.0, 10.0].iter().map(|m| m * rzad).find(|&v| v >= x).unwrap_or(10.0 * rzad)
+}
+
+fn etykieta_liczby(x: f64) -> String {
+    if x == 0.0 {
+        "0".to_string()
+    } else if x.abs() >= 1e6 {
+        format!("{:.1e}", x)
+    } else if x.abs() >= 100.0 {
+        format!("{:.0}", x)
+    } else if x.abs() >= 1.0 {
+        format!("{:.2}", x)
+    } else {
+        format!("{:.4}", x)
+    }
+}
+
+// Os Y od `min` (0 albo wartosc ujemna) do `maks` z podzialka i siatka;
+// zwraca funkcje przeliczajaca wartosc na wspolrzedna
+fn os_y(svg: &mut String, opis: &str, min: f64, maks: f64) -> impl Fn(f64) -> f64 {
+    let dol = WYSOKOSC - MARGINES_DOLNY;
+    let wysokosc = dol - MARGINES_GORNY;
+    for i in 0..=5 {
+        let v = min + (maks - min) * i as f64 / 5.0;
+        let y = dol - wysokosc * i as f64 / 5.0;
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{l}" x2="{p}" y1="{y:.1}" y2="{y:.1}" stroke="#ddd"/><text x="{t}" y="{ty:.1}" text-anchor="end" font-size="11">{v}</text>"##,
+            l = MARGINES_LEWY,
+            p = SZEROKOSC - MARGINES_PRAWY,
+            t = MARGINES_LEWY - 6.0,
+            ty = y + 4.0,
+            v = etykieta_liczby(v)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text transform="translate(16 {y:.1}) rotate(-90)" text-anchor="middle" font-size="12">{o}</text>"#,
+        y = MARGINES_GORNY + wysokosc / 2.0,
+        o = html_escape(opis)
+    );
+    move |v: f64| dol - wysokosc * ((v - min) / (maks - min)).clamp(0.0, 1.0)
+}
+
+fn legenda(svg: &mut String, serie: &[String]) {
+    for (i, nazwa) in serie.iter().enumerate() {
+        let y = MARGINES_GORNY + 8.0 + i as f64 * 18.0;
+        let x = SZEROKOSC - MARGINES_PRAWY + 16.0;
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x}" y="{y:.1}" width="12" height="12" fill="{k}"/><text x="{tx}" y="{ty:.1}" font-size="11">{n}</text>"#,
+            k = KOLORY[i % KOLORY.len()],
+            tx = x + 18.0,
+            ty = y + 10.0,
+            n = html_escape(nazwa)
+        );
+    }
+}
+
+fn otworz_svg(tytul: &str) -> String {
+    format!(
+        "<figure><figcaption>{}</figcaption>\n<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\">\n",
+        html_escape(tytul),
+        SZEROKOSC,
+        WYSOKOSC,
+        SZEROKOSC,
+        WYSOKOSC
+    )
+}
+
+// Wykres slupkowy grupowany: jedna grupa na kategorie, jeden slupek na serie. NaN = brak slupka
+fn slupki(tytul: &str, opis_y: &str, kategorie: &[String], serie: &[String], wartosci: &[Vec<f64>]) -> String {
+    let mut svg = otworz_svg(tytul);
+    let skonczone = || wartosci.iter().flatten().copied().filter(|v| v.is_finite());
+    let maks = zaokraglij_w_gore(skonczone().fold(0.0, f64::max));
+    let min = skonczone().fold(0.0, f64::min);
+    let min = if min < 0.0 { -zaokraglij_w_gore(-min) } else { 0.0 };
+    let y = os_y(&mut svg, opis_y, min, maks);
+
+    let szerokosc_grupy = (SZEROKOSC - MARGINES_LEWY - MARGINES_PRAWY) / kategorie.len().max(1) as f64;
+    let szerokosc_slupka = szerokosc_grupy * 0.8 / serie.len().max(1) as f64;
+    for (k, kategoria) in kategorie.iter().enumerate() {
+        let x0 = MARGINES_LEWY + k as f64 * szerokosc_grupy + szerokosc_grupy * 0.1;
+        for (s, seria) in serie.iter().enumerate() {
+            let v = wartosci[s][k];
+            if !v.is_finite() {
+                continue;
+            }
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x:.1}" y="{y:.1}" width="{w:.1}" height="{h:.1}" fill="{c}"><title>{t}: {v}</title></rect>"#,
+                x = x0 + s as f64 * szerokosc_slupka,
+                y = y(v).min(y(0.0)),
+                w = (szerokosc_slupka - 1.0).max(1.0),
+                h = (y(0.0) - y(v)).abs(),
+                c = KOLORY[s % KOLORY.len()],
+                t = html_escape(&format!("{} / {}", kategoria, seria)),
+                v = etykieta_liczby(v)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x:.1}" y="{ty:.1}" text-anchor="middle" font-size="11">{n}</text>"#,
+            x = x0 + szerokosc_grupy * 0.4,
+            ty = WYSOKOSC - MARGINES_DOLNY + 16.0,
+            n = html_escape(kategoria)
+        );
+    }
+    legenda(&mut svg, serie);
+    svg.push_str("</svg></figure>\n");
+    svg
+}
+
+// Wykres liniowy z punktami (x, y); os X liniowa, podzialka w kazdej wystepujacej wartosci x
+fn linie(tytul: &str, opis_x: &str, opis_y: &str, serie: &[(String, Vec<(f64, f64)>)]) -> String {
+    let mut svg = otworz_svg(tytul);
+    let punkty = serie.iter().flat_map(|(_, p)| p.iter()).filter(|(_, y)| y.is_finite());
+    let maks_y = zaokraglij_w_gore(punkty.clone().map(|&(_, y)| y).fold(0.0, f64::max));
+    let xs: BTreeSet<u64> = punkty.map(|&(x, _)| x.to_bits()).collect();
+    let xs: Vec<f64> = xs.into_iter().map(f64::from_bits).collect();
+    let (min_x, maks_x) = (
+        xs.iter().copied().fold(f64::INFINITY, f64::min),
+        xs.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    );
+    let y = os_y(&mut svg, opis_y, 0.0, maks_y);
+    let szerokosc = SZEROKOSC - MARGINES_LEWY - MARGINES_PRAWY;
+    let x = |v: f64| {
+        if maks_x > min_x {
+            MARGINES_LEWY + 10.0 + (szerokosc - 20.0) * (v - min_x) / (maks_x - min_x)
+        } else {
+            MARGINES_LEWY + szerokosc / 2.0
+        }
+    };
+
+    for &v in &xs {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x:.1}" y="{ty:.1}" text-anchor="middle" font-size="11">{v}</text>"#,
+            x = x(v),
+            ty = WYSOKOSC - MARGINES_DOLNY + 16.0,
+            v = etykieta_liczby(v)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text x="{x:.1}" y="{ty:.1}" text-anchor="middle" font-size="12">{o}</text>"#,
+        x = MARGINES_LEWY + szerokosc / 2.0,
+        ty = WYSOKOSC - 20.0,
+        o = html_escape(opis_x)
+    );
+
+    for (s, (nazwa, punkty)) in serie.iter().enumerate() {
+        let kolor = KOLORY[s % KOLORY.len()];
+        let punkty: Vec<(f64, f64)> = punkty.iter().copied().filter(|(_, v)| v.is_finite()).collect();
+        let sciezka: Vec<String> = punkty.iter().map(|&(px, py)| format!("{:.1},{:.1}", x(px), y(py))).collect();
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+            sciezka.join(" "),
+            kolor
+        );
+        for &(px, py) in &punkty {
+            let _ = writeln!(
+                svg,
+                r#"<circle cx="{:.1}" cy="{:.1}" r="3.5" fill="{}"><title>{}: {} -> {}</title></circle>"#,
+                x(px),
+                y(py),
+                kolor,
+                html_escape(nazwa),
+                etykieta_liczby(px),
+                etykieta_liczby(py)
+            );
+        }
+    }
+    let nazwy: Vec<String> = serie.iter().map(|(n, _)| n.clone()).collect();
+    legenda(&mut svg, &nazwy);
+    svg.push_str("</svg></figure>\n");
+    svg
+}
+
+// Srednia po powtorzeniach dla kazdej pary (kategoria, seria)
+struct Tabela {
+    kategorie: Vec<String>,
+    serie: Vec<String>,
+    wartosci: Vec<Vec<f64>>,
+}
+
+fn tabela<'a>(
+    rekordy: impl Iterator<Item = &'a RekordWyniku>,
+    klucz: impl Fn(&RekordWyniku) -> (String, String),
+    wartosc: impl Fn(&RekordWyniku) -> f64,
+) -> Tabela {
+    let mut sumy: BTreeMap<(String, String), (f64, usize)> = BTreeMap::new();
+    for r in rekordy {
+        let suma = sumy.entry(klucz(r)).or_insert((0.0, 0));
+        suma.0 += wartosc(r);
+        suma.1 += 1;
+    }
+    let kategorie: Vec<String> = sumy.keys().map(|(k, _)| k.clone()).collect::<BTreeSet<_>>().into_iter().collect();
+    let serie: Vec<String> = sumy.keys().map(|(_, s)| s.clone()).collect::<BTreeSet<_>>().into_iter().collect();
+    let wartosci = serie
+        .iter()
+        .map(|s| {
+            kategorie
+                .iter()
+                .map(|k| match sumy.get(&(k.clone(), s.clone())) {
+                    Some(&(suma, n)) => suma / n as f64,
+                    None => f64::NAN,
+                })
+                .collect()
+        })
+        .collect();
+    Tabela { kategorie, serie, wartosci }
+}
+
+fn parametr<'a>(r: &'a RekordWyniku, nazwa: &str) -> &'a str {
+    r.parametry.get(nazwa).map(String::as_str).unwrap_or("")
+}
+
+fn watki(r: &RekordWyniku) -> usize {
+    parametr(r, "watki").parse().unwrap_or(1)
+}
+
+// Kategoria wykresow liczenia: slowo, a przy kilku plikach takze nazwa pliku
+fn kategoria_liczenia(r: &RekordWyniku, wiele_plikow: bool) -> String {
+    if wiele_plikow {
+        let plik = parametr(r, "plik");
+        let nazwa = plik.rsplit(['/', '\\']).next().unwrap_or(plik);
+        format!("{} @ {}", parametr(r, "slowo"), nazwa)
+    } else {
+        parametr(r, "slowo").to_string()
+    }
+}
+
+// Parametry spoza wartosci domyslnych (tryb inny niz dokladny, algorytm inny niz kmp, fragment inny niz 2 MiB,
+// zimny cache) jako dopisek do nazwy serii
+fn dopisek(r: &RekordWyniku) -> String {
+    let mut czesci = Vec::new();
+    match parametr(r, "tryb") {
+        "" | "dokladny" => {}
+        "rozmyty" => czesci.push(format!("rozmyty k={}", parametr(r, "odleglosc"))),
+        tryb => czesci.push(tryb.to_string()),
+    }
+    if !parametr(r, "algorytm").is_empty() {
+        czesci.push(parametr(r, "algorytm").to_string());
+    }
+    if let Ok(fragment) = parametr(r, "fragment").parse::<usize>() {
+        let dobor = if parametr(r, "dobor_fragmentu").is_empty() { "fragment" } else { "auto" };
+        czesci.push(format!("{} {}", dobor, opis_rozmiaru(fragment)));
+    }
+    if !parametr(r, "cache").is_empty() {
+        czesci.push(format!("{} cache", parametr(r, "cache")));
+    }
+    if czesci.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", czesci.join(", "))
+    }
+}
+
+fn seria_liczenia(r: &RekordWyniku) -> String {
+    let seria = if r.implementacja == "sekwencyjny" {
+        r.implementacja.clone()
+    } else {
+        format!("{} x{}", r.implementacja, watki(r))
+    };
+    seria + &dopisek(r)
+}
+
+// Przepustowosc w kB/s; teksty maja losowa dlugosc z przedzialu "min-max", przyjmowana srednia
+fn przepustowosc(r: &RekordWyniku) -> f64 {
+    let wiadomosci: f64 = parametr(r, "wiadomosci").parse().unwrap_or(f64::NAN);
+    let dlugosc = parametr(r, "dlugosc")
+        .split_once('-')
+        .and_then(|(a, b)| Some((a.parse::<f64>().ok()? + b.parse::<f64>().ok()?) / 2.0))
+        .unwrap_or(f64::NAN);
+    wiadomosci * dlugosc / r.metrics.czas / 1024.0
+}
+
+// Wykresy licznika alokacji (cecha `alokacje`) dla rekordow, ktore go maja
+fn wykresy_alokacji(out: &mut String, rekordy: &[&RekordWyniku], klucz: impl Fn(&RekordWyniku) -> (String, String) + Copy) {
+    let z_alokacjami: Vec<&RekordWyniku> = rekordy.iter().copied().filter(|r| r.metrics.alokacje.is_some()).collect();
+    if z_alokacjami.is_empty() {
+        return;
+    }
+    let alokacje = |r: &RekordWyniku| r.metrics.alokacje.clone().unwrap_or_default();
+    let t = tabela(z_alokacjami.iter().copied(), klucz, |r| alokacje(r).liczba as f64);
+    out.push_str(&slupki("Liczba alokacji sterty", "alokacje", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(z_alokacjami.iter().copied(), klucz, |r| alokacje(r).zaalokowane as f64);
+    out.push_str(&slupki("Bajty zaalokowane na stercie", "[B]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(z_alokacjami.iter().copied(), klucz, |r| alokacje(r).szczyt as f64);
+    out.push_str(&slupki("Szczyt zajetej sterty (ponad stan poczatkowy)", "[B]", &t.kategorie, &t.serie, &t.wartosci));
+}
+
+fn sekcja_liczenia(out: &mut String, raport: &Raport) {
+    let liczenie: Vec<&RekordWyniku> = raport.rekordy.iter().filter(|r| r.benchmark == "liczenie_slow").collect();
+    if liczenie.is_empty() {
+        return;
+    }
+    let wiele_plikow = liczenie.iter().map(|r| parametr(r, "plik")).collect::<BTreeSet<_>>().len() > 1;
+    let klucz = |r: &RekordWyniku| (kategoria_liczenia(r, wiele_plikow), seria_liczenia(r));
+    out.push_str("<h2>Liczenie slow</h2>\n");
+
+    let t = tabela(liczenie.iter().copied(), klucz, |r| r.metrics.czas);
+    out.push_str(&slupki("Czas na implementacje i slowo", "czas [s]", &t.kategorie, &t.serie, &t.wartosci));
+
+    // Pliki skompresowane: czas pomiaru rozbity na dekompresje i wyszukiwanie
+    let skompresowane: Vec<&RekordWyniku> =
+        liczenie.iter().copied().filter(|r| r.metrics.czas_dekompresji.is_some()).collect();
+    if !skompresowane.is_empty() {
+        let dekompresja = |r: &RekordWyniku| r.metrics.czas_dekompresji.unwrap_or(0.0);
+        let t = tabela(skompresowane.iter().copied(), klucz, dekompresja);
+        out.push_str(&slupki("Czas dekompresji", "czas [s]", &t.kategorie, &t.serie, &t.wartosci));
+        let t = tabela(skompresowane.iter().copied(), klucz, |r| (r.metrics.czas - dekompresja(r)).max(0.0));
+        out.push_str(&slupki("Czas wyszukiwania (bez dekompresji)", "czas [s]", &t.kategorie, &t.serie, &t.wartosci));
+    }
+
+    // Skalowanie: linia na (implementacja, slowo); sekwencyjny jako pozioma linia odniesienia
+    let rownolegle: Vec<&RekordWyniku> = liczenie.iter().copied().filter(|r| r.implementacja != "sekwencyjny").collect();
+    if !rownolegle.is_empty() {
+        let zakres: BTreeSet<usize> = rownolegle.iter().map(|r| watki(r)).collect();
+        let (min_w, maks_w) = (*zakres.first().unwrap_or(&1) as f64, *zakres.last().unwrap_or(&1) as f64);
+        let mut sumy: BTreeMap<(String, usize), (f64, usize)> = BTreeMap::new();
+        for r in &liczenie {
+            let nazwa = format!("{}{} / {}", r.implementacja, dopisek(r), kategoria_liczenia(r, wiele_plikow));
+            let suma = sumy.entry((nazwa, watki(r))).or_insert((0.0, 0));
+            suma.0 += r.metrics.czas;
+            suma.1 += 1;
+        }
+        let mut serie: BTreeMap<String, Vec<(f64, f64)>> = BTreeMap::new();
+        for ((nazwa, w), (suma, n)) in sumy {
+            let srednia = suma / n as f64;
+            if nazwa.starts_with("sekwencyjny") {
+                serie.entry(nazwa).or_default().extend([(min_w, srednia), (maks_w, srednia)]);
+            } else {
+                serie.entry(nazwa).or_default().push((w as f64, srednia));
+            }
+        }
+        let serie: Vec<(String, Vec<(f64, f64)>)> = serie.into_iter().collect();
+        out.push_str(&linie("Skalowanie wzgledem liczby watkow", "liczba watkow", "czas [s]", &serie));
+    }
+
+    // Przeglad rozmiarow fragmentu: os X logarytmiczna (log2 z rozmiaru w KiB)
+    let wplyw = raport.wplyw_fragmentu();
+    if !wplyw.is_empty() {
+        let serie: Vec<(String, Vec<(f64, f64)>)> = wplyw
+            .iter()
+            .map(|w| {
+                let punkty = w.czasy.iter().map(|&(fragment, czas)| ((fragment as f64 / 1024.0).log2(), czas)).collect();
+                (format!("{} [{}]", w.implementacja, w.parametry), punkty)
+            })
+            .collect();
+        out.push_str(&linie(
+            "Czas wzgledem rozmiaru fragmentu",
+            "log2(rozmiar fragmentu [KiB]) - 6 = 64 KiB, 11 = 2 MiB",
+            "czas [s]",
+            &serie,
+        ));
+    }
+
+    let t = tabela(liczenie.iter().copied(), klucz, |r| r.metrics.cpu_usage);
+    out.push_str(&slupki("Uzycie CPU (100% = wszystkie rdzenie)", "CPU [%]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(liczenie.iter().copied(), klucz, |r| r.metrics.cpu_user + r.metrics.cpu_system);
+    out.push_str(&slupki("Czas CPU (user + system)", "CPU [s]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(liczenie.iter().copied(), klucz, |r| r.metrics.ram_szczyt as f64);
+    out.push_str(&slupki("Szczyt pamieci rezydentnej", "RAM [B]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(liczenie.iter().copied(), klucz, |r| r.metrics.ram_usage as f64);
+    out.push_str(&slupki("Zmiana pamieci prywatnej", "RAM [B]", &t.kategorie, &t.serie, &t.wartosci));
+    wykresy_alokacji(out, &liczenie, klucz);
+
+    // Podzial pracy silnikow rownoleglych: nierownowaga i praca kolejnych watkow
+    let z_rozkladem: Vec<&RekordWyniku> = liczenie.iter().copied().filter(|r| r.metrics.rozklad.is_some()).collect();
+    if !z_rozkladem.is_empty() {
+        let nierownowaga = |r: &RekordWyniku| r.metrics.rozklad.as_ref().map_or(f64::NAN, |rozklad| rozklad.nierownowaga);
+        let t = tabela(z_rozkladem.iter().copied(), klucz, nierownowaga);
+        out.push_str(&slupki(
+            "Nierownowaga obciazenia (najdluzsza / srednia praca watku)",
+            "nierownowaga",
+            &t.kategorie,
+            &t.serie,
+            &t.wartosci,
+        ));
+        out.push_str("<table>\n<tr><th>implementacja</th><th>slowo</th><th>powt</th><th>watek</th><th>fragmenty</th><th>bajty</th><th>praca [s]</th><th>czekanie na join [s]</th></tr>\n");
+        for r in z_rozkladem {
+            for w in r.metrics.rozklad.iter().flat_map(|rozklad| &rozklad.watki) {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.4}</td><td>{:.4}</td></tr>",
+                    html_escape(&seria_liczenia(r)),
+                    html_escape(&kategoria_liczenia(r, wiele_plikow)),
+                    r.powtorzenie,
+                    w.watek,
+                    w.fragmenty,
+                    w.bajty,
+                    w.praca,
+                    w.bezczynnosc
+                );
+            }
+        }
+        out.push_str("</table>\n");
+    }
+}
+
+fn sekcja_szyfrowania(out: &mut String, raport: &Raport) {
+    let szyfrowanie: Vec<&RekordWyniku> = raport.rekordy.iter().filter(|r| r.benchmark == "szyfrowanie").collect();
+    if szyfrowanie.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Szyfrowanie</h2>\n");
+    let klucz = |r: &RekordWyniku| {
+        (
+            format!("{} B x {}", parametr(r, "dlugosc"), parametr(r, "wiadomosci")),
+            format!("{} {}-bit", r.implementacja, parametr(r, "klucz")),
+        )
+    };
+    // Osobny wykres na algorytm - AES i RSA roznia sie przepustowoscia o rzedy wielkosci
+    let algorytmy: BTreeSet<&str> = szyfrowanie.iter().map(|r| r.implementacja.as_str()).collect();
+    for algorytm in algorytmy {
+        let t = tabela(szyfrowanie.iter().copied().filter(|r| r.implementacja == algorytm), klucz, przepustowosc);
+        out.push_str(&slupki(
+            &format!("Przepustowosc {} (dlugosc wiadomosci x liczba wiadomosci)", algorytm),
+            "kB/s (srednia dlugosc)",
+            &t.kategorie,
+            &t.serie,
+            &t.wartosci,
+        ));
+    }
+    let t = tabela(szyfrowanie.iter().copied(), klucz, |r| r.metrics.cpu_usage);
+    out.push_str(&slupki("Uzycie CPU (100% = wszystkie rdzenie)", "CPU [%]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(szyfrowanie.iter().copied(), klucz, |r| r.metrics.cpu_user + r.metrics.cpu_system);
+    out.push_str(&slupki("Czas CPU (user + system)", "CPU [s]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(szyfrowanie.iter().copied(), klucz, |r| r.metrics.ram_szczyt as f64);
+    out.push_str(&slupki("Szczyt pamieci rezydentnej", "RAM [B]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(szyfrowanie.iter().copied(), klucz, |r| r.metrics.ram_usage as f64);
+    out.push_str(&slupki("Zmiana pamieci prywatnej", "RAM [B]", &t.kategorie, &t.serie, &t.wartosci));
+    wykresy_alokacji(out, &szyfrowanie, klucz);
+}
+
+// Analiza wycieku czasowego: tabela srednich czasow klas i max |t|
+fn sekcja_wycieku(out: &mut String, raport: &Raport) {
+    let analizy: Vec<_> = raport.rekordy.iter().filter_map(|r| r.metrics.wyciek.as_ref().map(|w| (r, w))).collect();
+    if analizy.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "<h2>Analiza wycieku czasowego</h2>\n<p>Test t Welcha (dudect); |t| &gt; {} oznacza zaleznosc czasu od danych.</p>", PROG_T);
+    out.push_str("<table>\n<tr><th>implementacja</th><th>parametry</th><th>pomiary</th><th>klasa 0 [cykle]</th><th>klasa 1 [cykle]</th><th>max |t|</th><th>werdykt</th></tr>\n");
+    for (r, w) in analizy {
+        let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}</td><td>{:.1}</td><td>{:.2}</td><td>{}</td></tr>",
+            html_escape(&r.implementacja),
+            html_escape(&parametry.join(" ")),
+            r.metrics.count,
+            w.srednie[0],
+            w.srednie[1],
+            w.max_t.abs(),
+            if w.wykryty() { "WYCIEK" } else { "brak" }
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+fn sekcja_pozycji(out: &mut String, raport: &Raport) {
+    let pozycje: Vec<&RekordWyniku> = raport.rekordy.iter().filter(|r| r.benchmark == "pozycje_slow").collect();
+    if pozycje.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Pozycje trafien</h2>\n");
+    let wiele_plikow = pozycje.iter().map(|r| parametr(r, "plik")).collect::<BTreeSet<_>>().len() > 1;
+    let klucz = |r: &RekordWyniku| (kategoria_liczenia(r, wiele_plikow), seria_liczenia(r));
+    let t = tabela(pozycje.iter().copied(), klucz, |r| r.metrics.czas);
+    out.push_str(&slupki("Czas zbierania pozycji", "czas [s]", &t.kategorie, &t.serie, &t.wartosci));
+    for (parametry, rekordy) in raport.grupy_pozycji() {
+        let Some(t) = rekordy[0].metrics.trafienia.as_ref() else { continue };
+        let _ = writeln!(
+            out,
+            "<h3>{}</h3>\n<p>Trafienia: {} (pokazane: {})</p>",
+            html_escape(&parametry),
+            t.liczba,
+            t.pierwsze.len()
+        );
+        out.push_str("<table>\n<tr><th>offset</th><th>linia</th><th>kolumna</th></tr>\n");
+        for p in &t.pierwsze {
+            let _ = writeln!(out, "<tr><td>{}</td><td>{}</td><td>{}</td></tr>", p.offset, p.linia, p.kolumna);
+        }
+        out.push_str("</table>\n");
+        if !t.kontekst.is_empty() {
+            let _ = writeln!(out, "<pre>{}</pre>", html_escape(&t.kontekst.join("\n")));
+        }
+    }
+}
+
+// Wystapienia wg odleglosci: wykres i tabela, jeden wiersz na grupe (silniki licza to samo)
+fn sekcja_rozmytych(out: &mut String, raport: &Raport) {
+    let grupy = raport.grupy_rozmyte();
+    if grupy.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Dopasowania rozmyte</h2>\n");
+    let maks_k = grupy
+        .values()
+        .filter_map(|rekordy| rekordy[0].metrics.odleglosci.as_ref())
+        .map(|o| o.len())
+        .max()
+        .unwrap_or(1);
+    let serie: Vec<String> = (0..maks_k).map(|d| format!("odleglosc {}", d)).collect();
+    let mut kategorie = Vec::new();
+    let mut wartosci = vec![Vec::new(); maks_k];
+    out.push_str("<table>\n<tr><th>parametry</th><th>wystapienia</th>");
+    for seria in &serie {
+        let _ = write!(out, "<th>{}</th>", seria);
+    }
+    out.push_str("</tr>\n");
+    for (parametry, rekordy) in &grupy {
+        let Some(odleglosci) = rekordy[0].metrics.odleglosci.as_ref() else { continue };
+        kategorie.push(format!("{}{}", parametr(rekordy[0], "slowo"), dopisek(rekordy[0])));
+        for (d, kolumna) in wartosci.iter_mut().enumerate() {
+            kolumna.push(odleglosci.get(d).map_or(0.0, |&n| n as f64));
+        }
+        let _ = write!(
+            out,
+            "<tr><td>{}</td><td>{}</td>",
+            html_escape(parametry),
+            odleglosci.iter().sum::<u64>()
+        );
+        for d in 0..maks_k {
+            let _ = write!(out, "<td>{}</td>", odleglosci.get(d).map_or(String::new(), u64::to_string));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out.push_str(&slupki("Wystapienia wg odleglosci Levenshteina", "wystapienia", &kategorie, &serie, &wartosci));
+}
+
+// Zgodnosc z referencja; niezgodne rekordy z rozbieznymi fragmentami
+fn sekcja_weryfikacji(out: &mut String, raport: &Raport) {
+    let zweryfikowane: Vec<_> = raport
+        .rekordy
+        .iter()
+        .filter_map(|r| r.metrics.weryfikacja.as_ref().map(|w| (r, w)))
+        .collect();
+    if zweryfikowane.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Weryfikacja wynikow</h2>\n");
+    let niezgodne: Vec<_> = zweryfikowane.iter().filter(|(r, w)| !w.zgodna(r.metrics.count)).collect();
+    if niezgodne.is_empty() {
+        let _ = writeln!(out, "<p>Wszystkie wyniki ({}) zgodne z referencja.</p>", zweryfikowane.len());
+        return;
+    }
+    let _ = writeln!(out, "<p>Niezgodne z referencja: {} z {}.</p>", niezgodne.len(), zweryfikowane.len());
+    out.push_str("<table>\n<tr><th>implementacja</th><th>parametry</th><th>powt</th><th>count</th><th>oczekiwano</th><th>referencja</th><th>rozbiezne fragmenty (indeks [start, koniec): oczekiwano / policzono)</th></tr>\n");
+    for (r, w) in niezgodne {
+        let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        let mut fragmenty: Vec<String> = w
+            .fragmenty
+            .iter()
+            .take(POKAZANE_FRAGMENTY)
+            .map(|f| format!("{} [{}, {}): {} / {}", f.indeks, f.start, f.koniec, f.oczekiwane, f.policzone))
+            .collect();
+        if w.fragmenty.len() > POKAZANE_FRAGMENTY {
+            fragmenty.push(format!("... i {} kolejnych", w.fragmenty.len() - POKAZANE_FRAGMENTY));
+        }
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            html_escape(&r.implementacja),
+            html_escape(&parametry.join(" ")),
+            r.powtorzenie,
+            r.metrics.count,
+            w.oczekiwane,
+            w.zrodlo.nazwa(),
+            if fragmenty.is_empty() { "fragmenty zgodne - rozbieznosc przy scalaniu".to_string() } else { fragmenty.join("<br>") }
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+fn sekcja_czestosci(out: &mut String, raport: &Raport) {
+    let czestosc: Vec<&RekordWyniku> = raport.rekordy.iter().filter(|r| r.benchmark == "czestosc_slow").collect();
+    if czestosc.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Czestosc slow</h2>\n");
+    let nazwa_pliku = |r: &RekordWyniku| {
+        let plik = parametr(r, "plik");
+        plik.rsplit(['/', '\\']).next().unwrap_or(plik).to_string()
+    };
+    let klucz = |r: &RekordWyniku| (nazwa_pliku(r), seria_liczenia(r));
+    let t = tabela(czestosc.iter().copied(), klucz, |r| r.metrics.czas);
+    out.push_str(&slupki("Czas histogramu na implementacje i plik", "czas [s]", &t.kategorie, &t.serie, &t.wartosci));
+    let t = tabela(czestosc.iter().copied(), klucz, |r| r.metrics.ram_szczyt as f64);
+    out.push_str(&slupki("Szczyt pamieci rezydentnej", "RAM [B]", &t.kategorie, &t.serie, &t.wartosci));
+    wykresy_alokacji(out, &czestosc, klucz);
+
+    // Najczestsze slowa: jeden wykres na plik i ustawienia (pierwszy rekord - silniki licza to samo)
+    let mut pokazane = BTreeSet::new();
+    out.push_str("<table>\n<tr><th>plik</th><th>male litery</th><th>stop-slowa</th><th>slowa</th><th>slownik</th><th>hapaksy</th><th>pominiete</th></tr>\n");
+    let mut wykresy = String::new();
+    for r in &czestosc {
+        let Some(h) = &r.metrics.histogram else { continue };
+        let opis = format!("{} (male litery: {}, stop-slowa: {})", nazwa_pliku(r), parametr(r, "male_litery"), parametr(r, "stop_slowa"));
+        if !pokazane.insert(opis.clone()) {
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            html_escape(&nazwa_pliku(r)),
+            parametr(r, "male_litery"),
+            parametr(r, "stop_slowa"),
+            h.tokeny,
+            h.slownik,
+            h.hapaksy,
+            h.pominiete
+        );
+        let kategorie: Vec<String> = h.top.iter().map(|s| s.slowo.clone()).collect();
+        let wartosci = vec![h.top.iter().map(|s| s.liczba as f64).collect()];
+        wykresy.push_str(&slupki(
+            &format!("Najczestsze slowa: {}", opis),
+            "wystapienia",
+            &kategorie,
+            &[nazwa_pliku(r)],
+            &wartosci,
+        ));
+    }
+    out.push_str("</table>\n");
+    out.push_str(&wykresy);
+}
+
+fn tabela_rekordow(out: &mut String, raport: &Raport) {
+    out.push_str("<h2>Wszystkie pomiary</h2>\n<table>\n<tr><th>benchmark</th><th>implementacja</th><th>parametry</th><th>powt</th><th>count</th><th>czas [s]</th><th>CPU [%]</th><th>CPU abs [%]</th><th>user [s]</th><th>sys [s]</th><th>RAM [B]</th><th>szczyt [B]</th><th>alokacje</th><th>zaalokowane [B]</th><th>szczyt sterty [B]</th></tr>\n");
+    for r in &raport.rekordy {
+        let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.4}</td><td>{:.1}</td><td>{:.1}</td><td>{:.3}</td><td>{:.3}</td><td>{}</td><td>{}</td>{}</tr>",
+            html_escape(&r.benchmark),
+            html_escape(&r.implementacja),
+            html_escape(&parametry.join(" ")),
+            r.powtorzenie,
+            r.metrics.count,
+            r.metrics.czas,
+            r.metrics.cpu_usage,
+            r.metrics.cpu_usage_abs,
+            r.metrics.cpu_user,
+            r.metrics.cpu_system,
+            r.metrics.ram_usage,
+            r.metrics.ram_szczyt,
+            r.metrics.alokacje.as_ref().map_or_else(
+                || "<td></td><td></td><td></td>".to_string(),
+                |a| format!("<td>{}</td><td>{}</td><td>{}</td>", a.liczba, a.zaalokowane, a.szczyt)
+            )
+        );
+    }
+    out.push_str("</table>\n");
+}
+
+pub fn html(raport: &Raport) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"pl\">\n<head>\n<meta charset=\"utf-8\">\n<title>{t}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2em; color: #222; }}\n\
+         figure {{ margin: 1.5em 0; }} figcaption {{ font-weight: bold; margin-bottom: .4em; }}\n\
+         table {{ border-collapse: collapse; font-size: 13px; }} td, th {{ border: 1px solid #ccc; padding: 3px 8px; }}\n\
+         td:nth-child(n+4) {{ text-align: right; }} th {{ text-align: left; }}\n\
+         </style>\n</head>\n<body>\n<h1>Raport: {t}</h1>\n",
+        t = html_escape(&raport.nazwa)
+    );
+    if let Some(seed) = raport.seed {
+        let _ = writeln!(out, "<p>Ziarno (seed): {}</p>", seed);
+    }
+    if let Some(srodowisko) = &raport.srodowisko {
+        out.push_str("<h2>Srodowisko</h2>\n<table>\n");
+        for (etykieta, wartosc) in srodowisko.opis() {
+            let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", etykieta, html_escape(&wartosc));
+        }
+        out.push_str("</table>\n");
+    }
+    sekcja_liczenia(&mut out, raport);
+    sekcja_weryfikacji(&mut out, raport);
+    sekcja_pozycji(&mut out, raport);
+    sekcja_rozmytych(&mut out, raport);
+    sekcja_czestosci(&mut out, raport);
+    sekcja_szyfrowania(&mut out, raport);
+    sekcja_wycieku(&mut out, raport);
+    tabela_rekordow(&mut out, raport);
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,352 @@
+// src/rozmyte.rs
+// Dopasowanie przyblizone: podciagi w odleglosci Levenshteina <= k od wzorca (bitowo-rownolegly algorytm Myersa).
+// Odleglosc liczona w znakach UTF-8 (np. "ł" zamiast "l" to jedna zamiana), wzorzec do 64 znakow.
+// Kolejne pozycje konca z odlegloscia <= k tworza jedno wystapienie o najmniejszej odleglosci w serii;
+// wystapienie nalezy do fragmentu, w ktorego wlasnym obszarze seria sie zaczyna
+use std::sync::Arc;
+use std::thread;
+
+use rayon::prelude::*;
+
+use crate::blad::{Blad, Wynik};
+use crate::kompresja;
+use crate::liczenie_slow::{
+    podziel_na_fragmenty_po, sprawdz_watki, wlasny_poczatek, Implementacja, UstawieniaLiczenia,
+};
+use crate::zasoby::{Metrics, Pomiar};
+
+// Silniki obslugujace tryb rozmyty
+pub const IMPLEMENTACJE: [Implementacja; 3] = [Implementacja::Sekwencyjny, Implementacja::ArcThreads, Implementacja::Rayon];
+
+// Domyslna najwieksza odleglosc k
+pub const DOMYSLNA_ODLEGLOSC: usize = 1;
+
+// Dlugosc wzorca w znakach - jeden bit slowa u64 na znak
+pub const MAKS_DLUGOSC_WZORCA: usize = 64;
+
+// Wzorzec Myersa: maski pozycji kazdego znaku wzorca
+#[derive(Debug)]
+pub struct WzorzecRozmyty {
+    m: usize,
+    k: usize,
+    ascii: [u64; 128],
+    inne: Vec<(char, u64)>,
+}
+
+impl WzorzecRozmyty {
+    pub fn nowy(slowo: &str, k: usize) -> Wynik<Self> {
+        let m = slowo.chars().count();
+        if m == 0 || m > MAKS_DLUGOSC_WZORCA {
+            return Err(Blad::parametr(
+                "slowo trybu rozmytego",
+                slowo,
+                format!("od 1 do {} znakow", MAKS_DLUGOSC_WZORCA),
+            ));
+        }
+        // Przy k >= m kazdy podciag pasowalby po usunieciu wszystkich znakow wzorca
+        if k >= m {
+            return Err(Blad::parametr("maks_odleglosc", k, format!("mniej niz dlugosc slowa {} ({})", slowo, m)));
+        }
+        let mut wzorzec = WzorzecRozmyty { m, k, ascii: [0; 128], inne: Vec::new() };
+        for (i, c) in slowo.chars().enumerate() {
+            let bit = 1u64 << i;
+            if c.is_ascii() {
+                wzorzec.ascii[c as usize] |= bit;
+            } else {
+                match wzorzec.inne.iter_mut().find(|(z, _)| *z == c) {
+                    Some((_, maska)) => *maska |= bit,
+                    None => wzorzec.inne.push((c, bit)),
+                }
+            }
+        }
+        Ok(wzorzec)
+    }
+
+    fn maska(&self, c: char) -> u64 {
+        if c.is_ascii() {
+            self.ascii[c as usize]
+        } else {
+            self.inne.iter().find(|(z, _)| *z == c).map_or(0, |&(_, maska)| maska)
+        }
+    }
+
+    // Znaki przed obszarem potrzebne, by odleglosci <= k byly dokladne: najdluzsze dopasowanie ma m + k znakow
+    fn kontekst_bajtow(&self) -> usize {
+        4 * (self.m + self.k + 1)
+    }
+
+    // Wystapienia wg odleglosci (indeks = odleglosc 0..=k) dla serii zaczetych w buffer[od..do_];
+    // seria zaczeta w obszarze jest sledzona takze za jego koncem
+    pub fn wystapienia_w_obszarze(&self, buffer: &[u8], (od, do_): (usize, usize)) -> Vec<u64> {
+        let mut wynik = vec![0u64; self.k + 1];
+        let mut start = od.saturating_sub(self.kontekst_bajtow());
+        // Poczatek okna na granicy znaku UTF-8
+        while start < od && buffer[start] & 0xC0 == 0x80 {
+            start += 1;
+        }
+
+        let najwyzszy = 1u64 << (self.m - 1);
+        let (mut pv, mut mv, mut wynik_pozycji) = (!0u64, 0u64, self.m);
+        // Seria biezaca: najmniejsza odleglosc i czy liczy sie w tym obszarze
+        let mut seria: Option<(usize, bool)> = None;
+        let mut krok = |c: char, poz: usize| -> bool {
+            let eq = self.maska(c);
+            let xv = eq | mv;
+            let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+            let ph = mv | !(xh | pv);
+            let mh = pv & xh;
+            if ph & najwyzszy != 0 {
+                wynik_pozycji += 1;
+            } else if mh & najwyzszy != 0 {
+                wynik_pozycji -= 1;
+            }
+            let (ph, mh) = (ph << 1, mh << 1);
+            pv = mh | !(xv | ph);
+            mv = ph & xv;
+
+            if wynik_pozycji <= self.k {
+                match &mut seria {
+                    Some((najmniejsza, _)) => *najmniejsza = (*najmniejsza).min(wynik_pozycji),
+                    None => seria = Some((wynik_pozycji, (od..do_).contains(&poz))),
+                }
+            } else if let Some((najmniejsza, nasza)) = seria.take() {
+                if nasza {
+                    wynik[najmniejsza] += 1;
+                }
+            }
+            // Dalej tylko do konca obszaru albo do konca serii, ktora sie w nim zaczela
+            poz < do_ || matches!(seria, Some((_, true)))
+        };
+
+        'skan: for kawalek in buffer[start..].utf8_chunks() {
+            let poczatek = kawalek.valid().as_ptr() as usize - buffer.as_ptr() as usize;
+            for (i, c) in kawalek.valid().char_indices() {
+                if !krok(c, poczatek + i) {
+                    break 'skan;
+                }
+            }
+            // Niepoprawna sekwencja UTF-8 liczy sie jako jeden znak niepasujacy do wzorca
+            if !kawalek.invalid().is_empty() && !krok(char::REPLACEMENT_CHARACTER, poczatek + kawalek.valid().len()) {
+                break;
+            }
+        }
+        // Seria trwajaca do konca pliku
+        if let Some((najmniejsza, true)) = seria {
+            wynik[najmniejsza] += 1;
+        }
+        wynik
+    }
+}
+
+fn scal(mut a: Vec<u64>, b: Vec<u64>) -> Vec<u64> {
+    for (x, y) in a.iter_mut().zip(b) {
+        *x += y;
+    }
+    a
+}
+
+fn zakoncz(pomiar: Pomiar, odleglosci: Vec<u64>, dekompresja: Option<f64>) -> Metrics {
+    let suma: u64 = odleglosci.iter().sum();
+    let mut metrics = pomiar.zakoncz(i32::try_from(suma).unwrap_or(i32::MAX));
+    metrics.odleglosci = Some(odleglosci);
+    metrics.czas_dekompresji = dekompresja;
+    metrics
+}
+
+// Obszary wlasne fragmentow z podziel_na_fragmenty_po - kazdy bajt pliku w dokladnie jednym
+fn obszary(buffer: &[u8], fragment: usize) -> Vec<(usize, usize)> {
+    podziel_na_fragmenty_po(buffer, fragment)
+        .into_iter()
+        .map(|(start, end)| (wlasny_poczatek(start), end))
+        .collect()
+}
+
+pub fn rozmyte_sekwencyjnie(sciezka_pliku: &str, slowo: &str, ustawienia: &UstawieniaLiczenia) -> Wynik<Metrics> {
+    let pomiar = Pomiar::start();
+
+    let wzorzec = WzorzecRozmyty::nowy(slowo, ustawienia.maks_odleglosc)?;
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let odleglosci = obszary(&buffer, ustawienia.fragment)
+        .into_iter()
+        .map(|obszar| wzorzec.wystapienia_w_obszarze(&buffer, obszar))
+        .fold(vec![0; ustawienia.maks_odleglosc + 1], scal);
+
+    Ok(zakoncz(pomiar, odleglosci, dekompresja))
+}
+
+// Ciagle zakresy obszarow na watek, sumy wg odleglosci laczone po join
+pub fn rozmyte_arc_threads(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let wzorzec = Arc::new(WzorzecRozmyty::nowy(slowo, ustawienia.maks_odleglosc)?);
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let buffer = Arc::new(buffer);
+    let obszary = obszary(&buffer, ustawienia.fragment);
+    let chunk_size = obszary.len().div_ceil(liczba_watkow.max(1)).max(1);
+    let zero = vec![0; ustawienia.maks_odleglosc + 1];
+
+    let watki: Vec<_> = obszary
+        .chunks(chunk_size)
+        .map(|zakres| {
+            let buffer = Arc::clone(&buffer);
+            let wzorzec = Arc::clone(&wzorzec);
+            let zakres = zakres.to_vec();
+            let zero = zero.clone();
+            thread::spawn(move || {
+                zakres
+                    .into_iter()
+                    .map(|obszar| wzorzec.wystapienia_w_obszarze(&buffer, obszar))
+                    .fold(zero, scal)
+            })
+        })
+        .collect();
+    let odleglosci = watki
+        .into_iter()
+        .map(|watek| watek.join().expect("watek dopasowania rozmytego zakonczyl sie panika"))
+        .fold(zero, scal);
+
+    Ok(zakoncz(pomiar, odleglosci, dekompresja))
+}
+
+pub fn rozmyte_rayon(
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    sprawdz_watki(liczba_watkow)?;
+    let pomiar = Pomiar::start();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(liczba_watkow)
+        .build()
+        .map_err(|e| Blad::parametr("liczba watkow rayon", liczba_watkow, e.to_string()))?;
+    let wzorzec = WzorzecRozmyty::nowy(slowo, ustawienia.maks_odleglosc)?;
+    let (buffer, dekompresja) = kompresja::odczytaj(sciezka_pliku)?;
+    let obszary = obszary(&buffer, ustawienia.fragment);
+    let zero = || vec![0; ustawienia.maks_odleglosc + 1];
+    let odleglosci = pool.install(|| {
+        obszary
+            .par_iter()
+            .map(|&obszar| wzorzec.wystapienia_w_obszarze(&buffer, obszar))
+            .reduce(zero, scal)
+    });
+
+    Ok(zakoncz(pomiar, odleglosci, dekompresja))
+}
+
+pub fn zmierz(
+    implementacja: Implementacja,
+    sciezka_pliku: &str,
+    slowo: &str,
+    liczba_watkow: usize,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Metrics> {
+    match implementacja {
+        Implementacja::Sekwencyjny => rozmyte_sekwencyjnie(sciezka_pliku, slowo, ustawienia),
+        Implementacja::ArcThreads => rozmyte_arc_threads(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        Implementacja::Rayon => rozmyte_rayon(sciezka_pliku, slowo, liczba_watkow, ustawienia),
+        inna => Err(Blad::parametr("implementacja trybu rozmytego", inna.nazwa(), "sekwencyjny, arc_threads lub rayon")),
+    }
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+    use crate::liczenie_slow::MIN_ROZMIAR_FRAGMENTU;
+
+    // Znaki z pozycjami jak w wystapienia_w_obszarze: niepoprawna sekwencja UTF-8 to jeden znak zastepczy
+    fn znaki(buffer: &[u8]) -> Vec<(usize, char)> {
+        let mut wynik = Vec::new();
+        for kawalek in buffer.utf8_chunks() {
+            let poczatek = kawalek.valid().as_ptr() as usize - buffer.as_ptr() as usize;
+            wynik.extend(kawalek.valid().char_indices().map(|(i, c)| (poczatek + i, c)));
+            if !kawalek.invalid().is_empty() {
+                wynik.push((poczatek + kawalek.valid().len(), char::REPLACEMENT_CHARACTER));
+            }
+        }
+        wynik
+    }
+
+    // Serie (pozycja pierwszego konca, najmniejsza odleglosc) z tablicy Levenshteina liczonej po kazdym znaku
+    fn serie(buffer: &[u8], slowo: &str, k: usize) -> Vec<(usize, usize)> {
+        let wzorzec: Vec<char> = slowo.chars().collect();
+        let mut kolumna: Vec<usize> = (0..=wzorzec.len()).collect();
+        let mut wynik = Vec::new();
+        let mut seria: Option<(usize, usize)> = None;
+        for (poz, c) in znaki(buffer) {
+            let mut nowa = vec![0; kolumna.len()];
+            for i in 1..kolumna.len() {
+                nowa[i] = (kolumna[i - 1] + usize::from(wzorzec[i - 1] != c)).min(kolumna[i] + 1).min(nowa[i - 1] + 1);
+            }
+            kolumna = nowa;
+            let odleglosc = kolumna[wzorzec.len()];
+            if odleglosc <= k {
+                let (_, najmniejsza) = seria.get_or_insert((poz, odleglosc));
+                *najmniejsza = (*najmniejsza).min(odleglosc);
+            } else if let Some(zakonczona) = seria.take() {
+                wynik.push(zakonczona);
+            }
+        }
+        wynik.extend(seria);
+        wynik
+    }
+
+    fn policz(serie: &[(usize, usize)], k: usize, (od, do_): (usize, usize)) -> Vec<u64> {
+        let mut wynik = vec![0; k + 1];
+        for &(_, odleglosc) in serie.iter().filter(|&&(poz, _)| (od..do_).contains(&poz)) {
+            wynik[odleglosc] += 1;
+        }
+        wynik
+    }
+
+    fn tekst() -> Vec<u8> {
+        let mut tekst = "kłos klos kos kłosy kłoss kxłos łkłos ".as_bytes().to_vec();
+        tekst.extend_from_slice(b"k\xFFos k\xC5os ");
+        tekst.extend_from_slice("kłoskłos kłłos sołk kł".as_bytes());
+        tekst
+    }
+
+    #[test]
+    fn obszary_zgodne_z_tablica_levenshteina_przy_kazdym_podziale() {
+        let tekst = tekst();
+        for (slowo, k) in [("kłos", 1), ("kłos", 2), ("łk", 1)] {
+            let wzorzec = WzorzecRozmyty::nowy(slowo, k).unwrap();
+            let serie = serie(&tekst, slowo, k);
+            assert!(serie.iter().any(|&(_, odleglosc)| odleglosc == k), "{} {}", slowo, k);
+            let granice = znaki(&tekst).into_iter().map(|(poz, _)| poz).chain([tekst.len()]);
+            for granica in granice {
+                for obszar in [(0, granica), (granica, tekst.len())] {
+                    assert_eq!(
+                        wzorzec.wystapienia_w_obszarze(&tekst, obszar),
+                        policz(&serie, k, obszar),
+                        "{} k={} obszar {:?}",
+                        slowo,
+                        k,
+                        obszar
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn silniki_zgodne_z_tablica_levenshteina() {
+        let tresc = tekst().repeat(400);
+        let sciezka = std::env::temp_dir().join(format!("rozmyte_{}_silniki", std::process::id()));
+        std::fs::write(&sciezka, &tresc).unwrap();
+        let ustawienia = UstawieniaLiczenia { fragment: MIN_ROZMIAR_FRAGMENTU, maks_odleglosc: 2, ..UstawieniaLiczenia::default() };
+        let spodziewane = policz(&serie(&tresc, "kłos", 2), 2, (0, tresc.len()));
+        for implementacja in IMPLEMENTACJE {
+            let metrics = zmierz(implementacja, sciezka.to_str().unwrap(), "kłos", 3, &ustawienia).unwrap();
+            assert_eq!(metrics.odleglosci.as_ref(), Some(&spodziewane), "{}", implementacja.nazwa());
+        }
+        let _ = std::fs::remove_file(&sciezka);
+    }
+}
@@ -1,322 +1,323 @@
 // src/rsa_aes.rs
 #![allow(non_snake_case, non_camel_case_types, non_upper_case_globals)]
 
-use winapi::{
-    shared::minwindef::{FILETIME, DWORD},
-    um::{
-        processthreadsapi::GetProcessTimes,
-        psapi::GetProcessMemoryInfo as WinGetProcessMemoryInfo,
-        processthreadsapi::GetCurrentProcess,
-    },
-};
 use ctr::cipher::{StreamCipher, KeyIvInit};
 use rsa::{RsaPublicKey, RsaPrivateKey, Pkcs1v15Encrypt}; // Dodano Pkcs1v15Encrypt
 use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
-use rand::{Rng, thread_rng, distributions::Alphanumeric, rngs::StdRng, SeedableRng};
+use rand::{Rng, distributions::Alphanumeric, rngs::StdRng, SeedableRng};
 use hex;
-use std::time::{Instant, SystemTime};
-use std::io;
-use std::mem;
-
-const AES_BLOCK_SIZE: usize = 16;
-
-#[repr(C)]
-struct PROCESS_MEMORY_COUNTERS {
-    cb: DWORD,
-    PageFaultCount: DWORD,
-    PeakWorkingSetSize: usize,
-    WorkingSetSize: usize,
-    QuotaPeakPagedPoolUsage: usize,
-    QuotaPagedPoolUsage: usize,
-    QuotaPeakNonPagedPoolUsage: usize,
-    QuotaNonPagedPoolUsage: usize,
-    PagefileUsage: usize,
-    PeakPagefileUsage: usize,
-    PrivateUsage: usize,
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use crate::blad::{Blad, Wynik};
+use crate::srodowisko::Srodowisko;
+use crate::wejscie::{co_najmniej, jedna_z, w_zakresie, wczytaj_linie, zapytaj};
+use crate::wyniki::{Raport, RekordWyniku};
+use crate::zasoby::{Metrics, Pomiar};
+
+pub const AES_BLOCK_SIZE: usize = 16;
+pub const RSA_MIN_BITS: usize = 512;
+pub const RSA_MAX_BITS: usize = 4096;
+
+// AES-CTR (licznik 64-bit LE) - ta sama operacja szyfruje i deszyfruje
+#[allow(non_snake_case)]
+pub fn ApplyAesCtr(key: &[u8], iv: &[u8; AES_BLOCK_SIZE], buffer: &mut [u8]) -> Wynik<()> {
+    // Wybierz odpowiedni szyfr na podstawie długości klucza
+    match key.len() {
+        16 => ctr::Ctr64LE::<aes::Aes128>::new_from_slices(key, iv)?.apply_keystream(buffer),
+        24 => ctr::Ctr64LE::<aes::Aes192>::new_from_slices(key, iv)?.apply_keystream(buffer),
+        32 => ctr::Ctr64LE::<aes::Aes256>::new_from_slices(key, iv)?.apply_keystream(buffer),
+        n => return Err(Blad::parametr("dlugosc klucza AES", format!("{} B", n), "16, 24 lub 32 B")),
+    }
+    Ok(())
+}
+
+pub fn rozmiar_klucza_aes(bits: &usize) -> Result<(), String> {
+    match bits {
+        128 | 192 | 256 => Ok(()),
+        _ => Err("128, 192 lub 256".to_string()),
+    }
+}
+
+// Wynik jednego przebiegu szyfrowanie -> deszyfrowanie
+pub struct AesResult {
+    pub key: Vec<u8>,
+    pub iv: [u8; AES_BLOCK_SIZE],
+    pub ciphertext: Vec<u8>,
+    pub decrypted: Vec<u8>,
+}
+
+pub struct RsaResult {
+    pub private_key: RsaPrivateKey,
+    pub public_key: RsaPublicKey,
+    pub ciphertext: Vec<u8>,
+    pub decrypted: Vec<u8>,
 }
 
 #[allow(non_snake_case)]
-fn EncryptDecryptAES(plaintext: &str, keySize: usize) {
-    let mut rng = thread_rng();
-    
-    let key = match keySize {
-        128 | 192 | 256 => {
-            let mut key = vec![0u8; keySize / 8];
-            rng.fill(&mut key[..]);
-            key
-        }
-        _ => {
-            eprintln!("Nieprawidlowy rozmiar klucza AES.");
-            return;
-        }
-    };
+pub fn RoundTripAES(plaintext: &[u8], keySize: usize, rng: &mut StdRng) -> Wynik<AesResult> {
+    rozmiar_klucza_aes(&keySize).map_err(|oczekiwano| Blad::parametr("dlugosc klucza AES", keySize, oczekiwano))?;
+    let mut key = vec![0u8; keySize / 8];
+    rng.fill(&mut key[..]);
 
     let mut iv = [0u8; AES_BLOCK_SIZE];
     rng.fill(&mut iv[..]);
 
-    println!("Klucz AES (hex):\n{}", hex::encode(&key));
-    println!("Tekst oryginalny: {}", plaintext);
+    let mut buffer = plaintext.to_vec();
+    ApplyAesCtr(&key, &iv, &mut buffer)?;
+    let ciphertext = buffer.clone();
 
-    // Wybierz odpowiedni szyfr na podstawie długości klucza
-    match key.len() {
-        16 => { // AES-128
-            use aes::Aes128;
-            let mut cipher = ctr::Ctr64LE::<Aes128>::new_from_slices(&key, &iv).unwrap();
-            let mut buffer = plaintext.as_bytes().to_vec();
-            cipher.apply_keystream(&mut buffer);
-            println!("Zaszyfrowany tekst (hex): {}", hex::encode(&buffer));
-            
-            let mut decipher = ctr::Ctr64LE::<Aes128>::new_from_slices(&key, &iv).unwrap();
-            decipher.apply_keystream(&mut buffer);
-            println!("Tekst odszyfrowany: {}", String::from_utf8_lossy(&buffer));
-        }
-        24 => { // AES-192
-            use aes::Aes192;
-            let mut cipher = ctr::Ctr64LE::<Aes192>::new_from_slices(&key, &iv).unwrap();
-            let mut buffer = plaintext.as_bytes().to_vec();
-            cipher.apply_keystream(&mut buffer);
-            println!("Zaszyfrowany tekst (hex): {}", hex::encode(&buffer));
-            
-            let mut decipher = ctr::Ctr64LE::<Aes192>::new_from_slices(&key, &iv).unwrap();
-            decipher.apply_keystream(&mut buffer);
-            println!("Tekst odszyfrowany: {}", String::from_utf8_lossy(&buffer));
-        }
-        32 => { // AES-256
-            use aes::Aes256;
-            let mut cipher = ctr::Ctr64LE::<Aes256>::new_from_slices(&key, &iv).unwrap();
-            let mut buffer = plaintext.as_bytes().to_vec();
-            cipher.apply_keystream(&mut buffer);
-            println!("Zaszyfrowany tekst (hex): {}", hex::encode(&buffer));
-            
-            let mut decipher = ctr::Ctr64LE::<Aes256>::new_from_slices(&key, &iv).unwrap();
-            decipher.apply_keystream(&mut buffer);
-            println!("Tekst odszyfrowany: {}", String::from_utf8_lossy(&buffer));
-        }
-        _ => unreachable!(),
-    }
-    
-    println!("IV (hex):\n{}", hex::encode(iv));
+    ApplyAesCtr(&key, &iv, &mut buffer)?;
+
+    Ok(AesResult {
+        key,
+        iv,
+        ciphertext,
+        decrypted: buffer,
+    })
 }
 
 #[allow(non_snake_case)]
-fn EncryptDecryptRSA(plaintext: &str, bits: usize) {
-    let mut rng = StdRng::from_entropy();
-    let private_key = RsaPrivateKey::new(&mut rng, bits).unwrap();
+pub fn RoundTripRSA(plaintext: &[u8], bits: usize, rng: &mut StdRng) -> Wynik<RsaResult> {
+    if !(RSA_MIN_BITS..=RSA_MAX_BITS).contains(&bits) {
+        return Err(Blad::parametr(
+            "dlugosc klucza RSA",
+            bits,
+            format!("{}-{}", RSA_MIN_BITS, RSA_MAX_BITS),
+        ));
+    }
+    let private_key = RsaPrivateKey::new(rng, bits)?;
     let public_key = RsaPublicKey::from(&private_key);
 
-    println!(
-        "Prywatny klucz RSA (PEM):\n{}", 
-        *private_key.to_pkcs8_pem(LineEnding::LF).unwrap()
-    );
-    println!(
-        "Publiczny klucz RSA (PEM):\n{}", 
-        public_key.to_public_key_pem(LineEnding::LF).unwrap()
-    );
-
     // Zmiana z OAEP na PKCS#1 v1.5
     let padding = Pkcs1v15Encrypt;
-    let ciphertext = public_key.encrypt(&mut rng, padding, plaintext.as_bytes()).unwrap();
-    println!("Zaszyfrowany tekst (hex): {}", hex::encode(&ciphertext));
+    let ciphertext = public_key.encrypt(rng, padding, plaintext)?;
 
     // Odpowiednia zmiana dla deszyfrowania
-    let decrypted = private_key.decrypt(padding, &ciphertext).unwrap();
+    let decrypted = private_key.decrypt(padding, &ciphertext)?;
+
+    Ok(RsaResult {
+        private_key,
+        public_key,
+        ciphertext,
+        decrypted,
+    })
+}
 
-    println!("Tekst odszyfrowany: {}", String::from_utf8_lossy(&decrypted));
+#[allow(non_snake_case)]
+fn EncryptDecryptAES(plaintext: &str, keySize: usize, rng: &mut StdRng) -> Wynik<bool> {
+    let wynik = RoundTripAES(plaintext.as_bytes(), keySize, rng)?;
+
+    println!("Klucz AES (hex):\n{}", hex::encode(&wynik.key));
+    println!("Tekst oryginalny: {}", plaintext);
+    println!("Zaszyfrowany tekst (hex): {}", hex::encode(&wynik.ciphertext));
+    println!("Tekst odszyfrowany: {}", String::from_utf8_lossy(&wynik.decrypted));
+    println!("IV (hex):\n{}", hex::encode(wynik.iv));
+    Ok(wynik.decrypted == plaintext.as_bytes())
 }
 
+#[allow(non_snake_case)]
+fn EncryptDecryptRSA(plaintext: &str, bits: usize, rng: &mut StdRng) -> Wynik<bool> {
+    let wynik = RoundTripRSA(plaintext.as_bytes(), bits, rng)?;
+
+    println!(
+        "Prywatny klucz RSA (PEM):\n{}", 
+        *wynik
+            .private_key
+            .to_pkcs8_pem(LineEnding::LF)
+            .map_err(|e| Blad::Kryptografia(e.to_string()))?
+    );
+    println!(
+        "Publiczny klucz RSA (PEM):\n{}", 
+        wynik
+            .public_key
+            .to_public_key_pem(LineEnding::LF)
+            .map_err(|e| Blad::Kryptografia(e.to_string()))?
+    );
+    println!("Zaszyfrowany tekst (hex): {}", hex::encode(&wynik.ciphertext));
+    println!("Tekst odszyfrowany: {}", String::from_utf8_lossy(&wynik.decrypted));
+    Ok(wynik.decrypted == plaintext.as_bytes())
+}
 
+// Pomiar bez wypisywania: count to liczba wiadomosci, ktore po deszyfrowaniu
+// sa identyczne z oryginalem (kazda wiadomosc ma nowy klucz, jak w trybie interaktywnym)
 #[allow(non_snake_case)]
-fn generateRandomText(min_length: usize, max_length: usize) -> String {
-    let len = thread_rng().gen_range(min_length..=max_length);
-    thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(len)
-        .map(char::from)
-        .collect()
+pub fn BenchmarkAES(texts: &[String], keySize: usize, rng: &mut StdRng) -> Wynik<Metrics> {
+    let pomiar = Pomiar::start();
+    let mut poprawne = 0;
+    for text in texts {
+        if RoundTripAES(text.as_bytes(), keySize, rng)?.decrypted == text.as_bytes() {
+            poprawne += 1;
+        }
+    }
+    Ok(pomiar.zakoncz(poprawne))
 }
 
 #[allow(non_snake_case)]
-fn GetProcessorTimes(userTime: &mut FILETIME, kernelTime: &mut FILETIME) {
-    unsafe {
-        let mut creationTime = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        let mut exitTime = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        GetProcessTimes(
-            GetCurrentProcess(),
-            &mut creationTime,
-            &mut exitTime,
-            kernelTime,
-            userTime,
-        );
+pub fn BenchmarkRSA(texts: &[String], bits: usize, rng: &mut StdRng) -> Wynik<Metrics> {
+    let pomiar = Pomiar::start();
+    let mut poprawne = 0;
+    for text in texts {
+        if RoundTripRSA(text.as_bytes(), bits, rng)?.decrypted == text.as_bytes() {
+            poprawne += 1;
+        }
     }
+    Ok(pomiar.zakoncz(poprawne))
 }
 
-fn FileTimeToSeconds(ft: &FILETIME) -> f64 {
-    ((ft.dwHighDateTime as u64) << 32 | ft.dwLowDateTime as u64) as f64 / 1e7
+// Szyfr wraz z trybem/dopelnieniem - identyfikator uzywany w scenariuszach i raportach
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Szyfr {
+    #[serde(rename = "aes-ctr")]
+    AesCtr,
+    #[serde(rename = "rsa-pkcs1v15")]
+    RsaPkcs1v15,
 }
 
-#[allow(non_snake_case)]
-fn GetProcessMemoryInfo() -> PROCESS_MEMORY_COUNTERS {
-    unsafe {
-        let mut pmc: PROCESS_MEMORY_COUNTERS = mem::zeroed();
-        pmc.cb = mem::size_of::<PROCESS_MEMORY_COUNTERS>() as DWORD;
-        WinGetProcessMemoryInfo(
-            GetCurrentProcess(),
-            &mut pmc as *mut _ as *mut _,
-            pmc.cb,
-        );
-        pmc
+impl Szyfr {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            Szyfr::AesCtr => "aes-ctr",
+            Szyfr::RsaPkcs1v15 => "rsa-pkcs1v15",
+        }
     }
 }
 
 #[allow(non_snake_case)]
-fn PrintResourceUsage(cpuUsage: f64, pmc: &PROCESS_MEMORY_COUNTERS) {
-    println!("Uzycie procesora: {:.2}%", cpuUsage);
-    println!("Uzycie pamieci RAM: {} B", pmc.WorkingSetSize);
+pub fn BenchmarkCipher(szyfr: Szyfr, texts: &[String], keySize: usize, rng: &mut StdRng) -> Wynik<Metrics> {
+    match szyfr {
+        Szyfr::AesCtr => BenchmarkAES(texts, keySize, rng),
+        Szyfr::RsaPkcs1v15 => BenchmarkRSA(texts, keySize, rng),
+    }
 }
 
 #[allow(non_snake_case)]
-pub fn szyfrowanie() {
-    let start_time = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
-    println!("Wybierz rodzaj szyfrowania: RSA lub AES");
-    let mut choice = String::new();
-    io::stdin().read_line(&mut choice).unwrap();
-    let choice = choice.trim().to_lowercase();
-
-    if choice == "rsa" || choice == "r" {
-        let _rng = StdRng::seed_from_u64(start_time);
-
-        println!("Czy chcesz podac wlasny tekst? (T/N)");
-        let mut yn = String::new();
-        io::stdin().read_line(&mut yn).unwrap();
-        let yn = yn.trim().to_lowercase();
-
-        let start = Instant::now();
-        let mut userStart = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        let mut kernelStart = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        GetProcessorTimes(&mut userStart, &mut kernelStart);
-        let _pmcStart = GetProcessMemoryInfo();
-
-        if yn == "t" {
-            println!("Podaj tekst:");
-            let mut plaintext = String::new();
-            io::stdin().read_line(&mut plaintext).unwrap();
-            let plaintext = plaintext.trim();
-
-            println!("Podaj dlugosc klucza (512-4096):");
-            let mut bytes = String::new();
-            io::stdin().read_line(&mut bytes).unwrap();
-            let bytes: usize = bytes.trim().parse().unwrap();
-
-            EncryptDecryptRSA(plaintext, bytes);
-        } else {
-            println!("Podaj ilosc hasel:");
-            let mut ilosc = String::new();
-            io::stdin().read_line(&mut ilosc).unwrap();
-            let ilosc: usize = ilosc.trim().parse().unwrap();
-
-            println!("Podaj dlugosc klucza (512-4096):");
-            let mut bytes = String::new();
-            io::stdin().read_line(&mut bytes).unwrap();
-            let bytes: usize = bytes.trim().parse().unwrap();
-
-            println!("Podaj min dlugosc tekstu:");
-            let mut min_len = String::new();
-            io::stdin().read_line(&mut min_len).unwrap();
-            let min_len: usize = min_len.trim().parse().unwrap();
-
-            println!("Podaj max dlugosc tekstu:");
-            let mut max_len = String::new();
-            io::stdin().read_line(&mut max_len).unwrap();
-            let max_len: usize = max_len.trim().parse().unwrap();
-
-            for _ in 0..ilosc {
-                let text = generateRandomText(min_len, max_len);
-                EncryptDecryptRSA(&text, bytes);
-            }
-        }
-
-        let duration = start.elapsed().as_secs_f64();
-        let mut userEnd = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        let mut kernelEnd = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        GetProcessorTimes(&mut userEnd, &mut kernelEnd);
-        let pmcEnd = GetProcessMemoryInfo();
-
-        let userTime = FileTimeToSeconds(&userEnd) - FileTimeToSeconds(&userStart);
-        let kernelTime = FileTimeToSeconds(&kernelEnd) - FileTimeToSeconds(&kernelStart);
-        let cpuUsage = ((userTime + kernelTime) / duration) * 100.0;
-
-        println!("\n=== Statystyki wydajnosci ===");
-        println!("Czas wykonania: {:.2} s", duration);
-        PrintResourceUsage(cpuUsage, &pmcEnd);
-    } else if choice == "aes" || choice == "a" {
-        println!("Czy chcesz podac wlasny tekst? (T/N)");
-        let mut yn = String::new();
-        io::stdin().read_line(&mut yn).unwrap();
-        let yn = yn.trim().to_lowercase();
-
-        let start = Instant::now();
-        let mut userStart = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        let mut kernelStart = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        GetProcessorTimes(&mut userStart, &mut kernelStart);
-        let _pmcStart = GetProcessMemoryInfo();
-
-        if yn == "t" {
-            println!("Podaj tekst:");
-            let mut plaintext = String::new();
-            io::stdin().read_line(&mut plaintext).unwrap();
-            let plaintext = plaintext.trim();
-
-            println!("Podaj dlugosc klucza (128 | 192 | 256):");
-            let mut bytes = String::new();
-            io::stdin().read_line(&mut bytes).unwrap();
-            let bytes: usize = bytes.trim().parse().unwrap();
-
-            EncryptDecryptAES(plaintext, bytes);
-        } else {
-            println!("Podaj ilosc hasel:");
-            let mut ilosc = String::new();
-            io::stdin().read_line(&mut ilosc).unwrap();
-            let ilosc: usize = ilosc.trim().parse().unwrap();
-
-            println!("Podaj dlugosc klucza (128 | 192 | 256):");
-            let mut bytes = String::new();
-            io::stdin().read_line(&mut bytes).unwrap();
-            let bytes: usize = bytes.trim().parse().unwrap();
-
-            println!("Podaj min dlugosc tekstu:");
-            let mut min_len = String::new();
-            io::stdin().read_line(&mut min_len).unwrap();
-            let min_len: usize = min_len.trim().parse().unwrap();
-
-            println!("Podaj max dlugosc tekstu:");
-            let mut max_len = String::new();
-            io::stdin().read_line(&mut max_len).unwrap();
-            let max_len: usize = max_len.trim().parse().unwrap();
-
-            for _ in 0..ilosc {
-                let text = generateRandomText(min_len, max_len);
-                EncryptDecryptAES(&text, bytes);
-            }
-        }
+pub fn generateRandomText(min_length: usize, max_length: usize, rng: &mut StdRng) -> String {
+    let len = rng.gen_range(min_length..=max_length);
+    rng.sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
 
-        let duration = start.elapsed().as_secs_f64();
-        let mut userEnd = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        let mut kernelEnd = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
-        GetProcessorTimes(&mut userEnd, &mut kernelEnd);
-        let pmcEnd = GetProcessMemoryInfo();
+// Dopelnienie PKCS#1 v1.5 zajmuje co najmniej 11 bajtow bloku
+pub fn MaxRsaPlaintextLen(bits: usize) -> usize {
+    (bits / 8).saturating_sub(11)
+}
 
-        let userTime = FileTimeToSeconds(&userEnd) - FileTimeToSeconds(&userStart);
-        let kernelTime = FileTimeToSeconds(&kernelEnd) - FileTimeToSeconds(&kernelStart);
-        let cpuUsage = ((userTime + kernelTime) / duration) * 100.0;
+// Tryb konsolowy: teksty i parametry pobierane przed pomiarem, wynik jako rekord "szyfrowanie"
+// z tymi samymi parametrami co w scenariuszu (count = wiadomosci poprawnie odszyfrowane)
+pub fn szyfrowanie(seed: Option<u64>) -> Wynik<Raport> {
+    // Pomiary maja sens tylko dla szyfrow, ktore przechodza wektory testowe
+    if !crate::selftest::selftest(false) {
+        return Err(Blad::Kryptografia("selftest nie powiodl sie".to_string()));
+    }
 
-        println!("\n=== Statystyki wydajnosci ===");
-        println!("Czas wykonania: {:.2} s", duration);
-        PrintResourceUsage(cpuUsage, &pmcEnd);
+    // Bez --seed ziarno pochodzi z zegara, ale i tak jest wypisywane w wynikach,
+    // aby dalo sie odtworzyc identyczne klucze, IV i teksty
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    });
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let choice: String = zapytaj(
+        "Wybierz rodzaj szyfrowania: RSA lub AES",
+        "rodzaj szyfrowania",
+        jedna_z(&["rsa", "r", "aes", "a"]),
+    )?;
+    let choice = choice.to_lowercase();
+    let szyfr = if choice == "rsa" || choice == "r" { Szyfr::RsaPkcs1v15 } else { Szyfr::AesCtr };
+    let yn = wczytaj_linie("Czy chcesz podac wlasny tekst? (T/N)")?.to_lowercase();
+
+    let (texts, bytes, dlugosc) = if yn == "t" {
+        let plaintext = wczytaj_linie("Podaj tekst:")?;
+        let bytes = klucz(szyfr)?;
+        if szyfr == Szyfr::RsaPkcs1v15 && plaintext.len() > MaxRsaPlaintextLen(bytes) {
+            return Err(Blad::parametr(
+                "tekst",
+                format!("{} B", plaintext.len()),
+                format!("najwyzej {} B dla klucza {}-bit", MaxRsaPlaintextLen(bytes), bytes),
+            ));
+        }
+        let dlugosc = format!("{0}-{0}", plaintext.len());
+        (vec![plaintext], bytes, dlugosc)
     } else {
-        println!("Nieprawidlowy wybor szyfrowania!");
+        let ilosc: usize = zapytaj("Podaj ilosc hasel:", "ilosc hasel", co_najmniej(1))?;
+        let bytes = klucz(szyfr)?;
+        let (min_len, max_len): (usize, usize) = match szyfr {
+            Szyfr::RsaPkcs1v15 => {
+                let min_len = zapytaj(
+                    "Podaj min dlugosc tekstu:",
+                    "min dlugosc tekstu",
+                    w_zakresie(0, MaxRsaPlaintextLen(bytes)),
+                )?;
+                let max_len = zapytaj(
+                    "Podaj max dlugosc tekstu:",
+                    "max dlugosc tekstu",
+                    w_zakresie(min_len, MaxRsaPlaintextLen(bytes)),
+                )?;
+                (min_len, max_len)
+            }
+            Szyfr::AesCtr => {
+                let min_len = zapytaj("Podaj min dlugosc tekstu:", "min dlugosc tekstu", co_najmniej(0))?;
+                let max_len = zapytaj("Podaj max dlugosc tekstu:", "max dlugosc tekstu", co_najmniej(min_len))?;
+                (min_len, max_len)
+            }
+        };
+        let texts = (0..ilosc).map(|_| generateRandomText(min_len, max_len, &mut rng)).collect();
+        (texts, bytes, format!("{}-{}", min_len, max_len))
+    };
+
+    let pomiar = Pomiar::start();
+    let mut poprawne = 0;
+    for text in &texts {
+        let zgodny = match szyfr {
+            Szyfr::AesCtr => EncryptDecryptAES(text, bytes, &mut rng)?,
+            Szyfr::RsaPkcs1v15 => EncryptDecryptRSA(text, bytes, &mut rng)?,
+        };
+        if zgodny {
+            poprawne += 1;
+        }
     }
-}
\ No newline at end of file
+    let metrics = pomiar.zakoncz(poprawne);
+
+    println!("\n=== Statystyki wydajnosci ===");
+    println!("Czas wykonania: {:.2} s", metrics.czas);
+    println!("Ziarno (seed): {}", seed);
+    println!("Uzycie procesora: {:.2}%", metrics.cpu_usage_abs);
+    println!("Szczyt pamieci RAM: {} B", metrics.ram_szczyt);
+
+    let parametry = BTreeMap::from([
+        ("klucz".to_string(), bytes.to_string()),
+        ("wiadomosci".to_string(), texts.len().to_string()),
+        ("dlugosc".to_string(), dlugosc),
+    ]);
+    Ok(Raport {
+        nazwa: "szyfrowanie (konsola)".to_string(),
+        seed: Some(seed),
+        srodowisko: Some(Srodowisko::wykryj()),
+        rekordy: vec![RekordWyniku {
+            benchmark: "szyfrowanie".to_string(),
+            implementacja: szyfr.nazwa().to_string(),
+            parametry,
+            powtorzenie: 1,
+            metrics,
+        }],
+    })
+}
+
+// Dlugosc klucza w bitach dla wybranego szyfru
+fn klucz(szyfr: Szyfr) -> Wynik<usize> {
+    match szyfr {
+        Szyfr::RsaPkcs1v15 => zapytaj(
+            "Podaj dlugosc klucza (512-4096):",
+            "dlugosc klucza RSA",
+            w_zakresie(RSA_MIN_BITS, RSA_MAX_BITS),
+        ),
+        Szyfr::AesCtr => zapytaj("Podaj dlugosc klucza (128 | 192 | 256):", "dlugosc klucza AES", rozmiar_klucza_aes),
+    }
+}
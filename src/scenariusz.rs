@@ -0,0 +1,735 @@
+// src/scenariusz.rs
+// Deklaratywne scenariusze pomiarow (TOML): walidacja i wykonanie calego planu
+use rand::{rngs::StdRng, SeedableRng};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::blad::{Blad, Wynik};
+use crate::czestosc::{self, UstawieniaCzestosci};
+use crate::kodowanie::{self, Kodowanie, Transkodowanie};
+use crate::pozycje;
+use crate::rozmyte::{self, WzorzecRozmyty, DOMYSLNA_ODLEGLOSC};
+use crate::liczenie_slow::{
+    self, Algorytm, Implementacja, RozmiarFragmentu, StanCache, TrybDopasowania, UstawieniaLiczenia, ROZMIAR_FRAGMENTU,
+};
+use crate::srodowisko::Srodowisko;
+use crate::wyrazenia::{self, MAKS_DOPASOWANIE};
+use crate::rsa_aes::{self, Szyfr, MaxRsaPlaintextLen, RSA_MAX_BITS, RSA_MIN_BITS};
+use crate::weryfikacja::{self, Referencje};
+use crate::wyniki::{FormatRaportu, Raport, RekordWyniku};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenariusz {
+    pub nazwa: String,
+    pub seed: Option<u64>,
+    #[serde(default = "jedno_powtorzenie")]
+    pub powtorzenia: usize,
+    #[serde(default = "format_domyslny")]
+    pub format: FormatRaportu,
+    // Plik raportu; bez niego raport trafia na standardowe wyjscie
+    pub wyjscie: Option<String>,
+    pub liczenie: Option<PlanLiczenia>,
+    pub szyfrowanie: Option<PlanSzyfrowania>,
+    pub czestosc: Option<PlanCzestosci>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanLiczenia {
+    pub pliki: Vec<String>,
+    pub slowa: Vec<String>,
+    // Kodowanie plikow; bez niego wykrywane z BOM lub heurystyka dla kazdego pliku
+    pub kodowanie: Option<Kodowanie>,
+    // Plik nie w UTF-8: przekodowanie pliku (wejscie) albo slow (wzorzec, tylko tryb dokladny)
+    #[serde(default)]
+    pub transkodowanie: Transkodowanie,
+    #[serde(default = "tryby_domyslne")]
+    pub tryby: Vec<TrybDopasowania>,
+    // Tryb regex: najdluzsze dopasowanie (bajty), ktore moze przeciac granice fragmentow
+    #[serde(default = "maks_dopasowanie_domyslne")]
+    pub maks_dopasowanie: usize,
+    // Tryb rozmyty: najwieksza odleglosc Levenshteina k
+    #[serde(default = "maks_odleglosc_domyslna")]
+    pub maks_odleglosc: usize,
+    #[serde(default = "algorytmy_domyslne")]
+    pub algorytmy: Vec<Algorytm>,
+    pub watki: Vec<usize>,
+    #[serde(default = "implementacje_domyslne")]
+    pub implementacje: Vec<Implementacja>,
+    // Stan cache plikow przed kazdym pomiarem: cieply, zimny lub oba
+    #[serde(default = "cache_domyslny")]
+    pub cache: Vec<StanCache>,
+    // Rozmiary fragmentu: bajty (65536, "64K", "2M") lub "auto"; domyslnie 2 MiB
+    #[serde(default = "fragmenty_domyslne")]
+    pub fragmenty: Vec<RozmiarFragmentu>,
+    // Przeglad rozmiarow od..do (kolejne podwojenia), dopisywany do `fragmenty`
+    pub przeglad_fragmentow: Option<PrzegladFragmentow>,
+    // Zbieranie pozycji trafien (osobne rekordy pozycje_slow) dla silnikow, ktore to obsluguja
+    pub pozycje: Option<OpcjePozycji>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpcjePozycji {
+    // Linie kontekstu przed i po trafieniu (jak grep -C)
+    #[serde(default)]
+    pub kontekst: usize,
+    // Ile pierwszych trafien trafia do raportu; 0 = wszystkie
+    #[serde(default = "limit_pozycji")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrzegladFragmentow {
+    pub od: RozmiarFragmentu,
+    #[serde(rename = "do")]
+    pub do_: RozmiarFragmentu,
+}
+
+// Histogram czestosci slow dla calych plikow
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanCzestosci {
+    pub pliki: Vec<String>,
+    // Kodowanie plikow (bez niego wykrywane); plik nie w UTF-8 jest przed pomiarem przekodowany
+    pub kodowanie: Option<Kodowanie>,
+    pub watki: Vec<usize>,
+    #[serde(default = "implementacje_czestosci")]
+    pub implementacje: Vec<Implementacja>,
+    #[serde(default = "male_litery_domyslnie")]
+    pub male_litery: bool,
+    // Stop-slowa podane wprost i/lub z pliku (slowa rozdzielone bialymi znakami)
+    #[serde(default)]
+    pub stop_slowa: Vec<String>,
+    pub plik_stop_slow: Option<String>,
+    #[serde(default = "top_domyslny")]
+    pub top: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanSzyfrowania {
+    pub algorytmy: Vec<Szyfr>,
+    #[serde(default)]
+    pub klucze_aes: Vec<usize>,
+    #[serde(default)]
+    pub klucze_rsa: Vec<usize>,
+    pub liczba_wiadomosci: Vec<usize>,
+    pub dlugosc_min: usize,
+    pub dlugosc_max: usize,
+}
+
+fn jedno_powtorzenie() -> usize {
+    1
+}
+
+fn format_domyslny() -> FormatRaportu {
+    FormatRaportu::Tekst
+}
+
+fn tryby_domyslne() -> Vec<TrybDopasowania> {
+    vec![TrybDopasowania::Dokladny]
+}
+
+fn maks_dopasowanie_domyslne() -> usize {
+    MAKS_DOPASOWANIE
+}
+
+fn maks_odleglosc_domyslna() -> usize {
+    DOMYSLNA_ODLEGLOSC
+}
+
+fn algorytmy_domyslne() -> Vec<Algorytm> {
+    vec![Algorytm::Kmp]
+}
+
+fn implementacje_domyslne() -> Vec<Implementacja> {
+    Implementacja::WSZYSTKIE.to_vec()
+}
+
+fn cache_domyslny() -> Vec<StanCache> {
+    vec![StanCache::Cieply]
+}
+
+fn limit_pozycji() -> usize {
+    20
+}
+
+fn implementacje_czestosci() -> Vec<Implementacja> {
+    czestosc::IMPLEMENTACJE.to_vec()
+}
+
+fn male_litery_domyslnie() -> bool {
+    true
+}
+
+fn top_domyslny() -> usize {
+    20
+}
+
+fn fragmenty_domyslne() -> Vec<RozmiarFragmentu> {
+    vec![RozmiarFragmentu::Bajty(ROZMIAR_FRAGMENTU)]
+}
+
+fn niepusta<T>(pole: &str, lista: &[T]) -> Wynik<()> {
+    if lista.is_empty() {
+        Err(Blad::parametr(pole, "[]", "co najmniej jeden element"))
+    } else {
+        Ok(())
+    }
+}
+
+impl PlanLiczenia {
+    // Rozmiary z listy `fragmenty` i z przegladu, bez powtorzen, w kolejnosci wystapienia
+    pub fn rozmiary_fragmentow(&self) -> Vec<RozmiarFragmentu> {
+        let mut rozmiary = self.fragmenty.clone();
+        if let Some(PrzegladFragmentow {
+            od: RozmiarFragmentu::Bajty(od),
+            do_: RozmiarFragmentu::Bajty(az_do),
+        }) = self.przeglad_fragmentow
+        {
+            let mut rozmiar = od;
+            while rozmiar <= az_do {
+                rozmiary.push(RozmiarFragmentu::Bajty(rozmiar));
+                rozmiar = match rozmiar.checked_mul(2) {
+                    Some(r) => r,
+                    None => break,
+                };
+            }
+        }
+        let mut unikalne = Vec::new();
+        for rozmiar in rozmiary {
+            if !unikalne.contains(&rozmiar) {
+                unikalne.push(rozmiar);
+            }
+        }
+        unikalne
+    }
+}
+
+impl PlanSzyfrowania {
+    fn klucze(&self, szyfr: Szyfr) -> &[usize] {
+        match szyfr {
+            Szyfr::AesCtr => &self.klucze_aes,
+            Szyfr::RsaPkcs1v15 => &self.klucze_rsa,
+        }
+    }
+}
+
+impl Scenariusz {
+    pub fn wczytaj(sciezka: &str) -> Wynik<Self> {
+        let tekst = std::fs::read_to_string(sciezka)
+            .map_err(|e| Blad::io(format!("odczyt scenariusza {}", sciezka), e))?;
+        let scenariusz: Scenariusz = toml::from_str(&tekst).map_err(|e| Blad::Parsowanie {
+            pole: format!("scenariusz {}", sciezka),
+            wartosc: e.span().map(|z| tekst[z].to_string()).unwrap_or_default(),
+            zrodlo: e.message().to_string(),
+        })?;
+        scenariusz.waliduj()?;
+        Ok(scenariusz)
+    }
+
+    // Wszystkie bledy planu zglaszane przed pierwszym pomiarem
+    pub fn waliduj(&self) -> Wynik<()> {
+        if self.powtorzenia == 0 {
+            return Err(Blad::parametr("powtorzenia", 0, "liczba >= 1"));
+        }
+        if self.liczenie.is_none() && self.szyfrowanie.is_none() && self.czestosc.is_none() {
+            return Err(Blad::parametr(
+                "scenariusz",
+                &self.nazwa,
+                "sekcja [liczenie], [szyfrowanie] lub [czestosc]",
+            ));
+        }
+
+        if let Some(plan) = &self.liczenie {
+            niepusta("liczenie.pliki", &plan.pliki)?;
+            niepusta("liczenie.slowa", &plan.slowa)?;
+            niepusta("liczenie.tryby", &plan.tryby)?;
+            niepusta("liczenie.algorytmy", &plan.algorytmy)?;
+            niepusta("liczenie.watki", &plan.watki)?;
+            niepusta("liczenie.implementacje", &plan.implementacje)?;
+            niepusta("liczenie.cache", &plan.cache)?;
+            if let Some(przeglad) = &plan.przeglad_fragmentow {
+                match (przeglad.od, przeglad.do_) {
+                    (RozmiarFragmentu::Bajty(od), RozmiarFragmentu::Bajty(az_do)) if od <= az_do => {}
+                    (od, az_do) => {
+                        return Err(Blad::parametr(
+                            "liczenie.przeglad_fragmentow",
+                            format!("{}..{}", od.nazwa(), az_do.nazwa()),
+                            "rozmiary w bajtach, od <= do",
+                        ))
+                    }
+                }
+            }
+            if plan.przeglad_fragmentow.is_none() {
+                niepusta("liczenie.fragmenty", &plan.fragmenty)?;
+            }
+            if plan.pozycje.is_some() && !plan.implementacje.iter().any(|i| pozycje::IMPLEMENTACJE.contains(i)) {
+                return Err(Blad::parametr(
+                    "liczenie.pozycje",
+                    "implementacje bez obslugi pozycji",
+                    "w liczenie.implementacje co najmniej jedna z: sekwencyjny, arc_threads, rayon",
+                ));
+            }
+            if plan.transkodowanie == Transkodowanie::Wzorzec {
+                if let Some(tryb) = plan.tryby.iter().find(|&&t| t != TrybDopasowania::Dokladny) {
+                    return Err(Blad::parametr(
+                        "liczenie.tryby",
+                        tryb.nazwa(),
+                        "tylko dokladny przy transkodowanie = \"wzorzec\"",
+                    ));
+                }
+            }
+            if plan.pozycje.is_some() && !plan.tryby.contains(&TrybDopasowania::Dokladny) {
+                return Err(Blad::parametr("liczenie.pozycje", "tryby bez dokladny", "tryb dokladny w liczenie.tryby"));
+            }
+            // Skladnia wyrazen i ich najdluzsze dopasowanie sprawdzane przed pomiarami
+            if plan.tryby.contains(&TrybDopasowania::Regex) {
+                for slowo in &plan.slowa {
+                    wyrazenia::sprawdz_maks(slowo, plan.maks_dopasowanie)?;
+                }
+            }
+            if plan.tryby.contains(&TrybDopasowania::Rozmyty) {
+                if !plan.implementacje.iter().any(|i| rozmyte::IMPLEMENTACJE.contains(i)) {
+                    return Err(Blad::parametr(
+                        "liczenie.tryby",
+                        "rozmyty",
+                        "w liczenie.implementacje co najmniej jedna z: sekwencyjny, arc_threads, rayon",
+                    ));
+                }
+                for slowo in &plan.slowa {
+                    WzorzecRozmyty::nowy(slowo, plan.maks_odleglosc)?;
+                }
+            }
+            for plik in &plan.pliki {
+                if !Path::new(plik).is_file() {
+                    return Err(Blad::parametr("liczenie.pliki", plik, "istniejacy plik"));
+                }
+            }
+            if let Some(slowo) = plan.slowa.iter().find(|s| s.is_empty()) {
+                return Err(Blad::parametr("liczenie.slowa", format!("\"{}\"", slowo), "niepuste slowa"));
+            }
+            // Dlugosc slowa w bajtach pliku; przy wykrywanym kodowaniu najkrotsza mozliwa (jeden bajt na znak),
+            // dokladnie sprawdza ja przygotuj_wyszukiwanie po odczycie pliku
+            if plan.tryby.contains(&TrybDopasowania::Dokladny) {
+                for slowo in &plan.slowa {
+                    let bajty = match (plan.transkodowanie, plan.kodowanie) {
+                        (Transkodowanie::Wzorzec, Some(kodowanie)) => kodowanie::zakoduj_wzorzec(slowo, kodowanie)?.len(),
+                        (Transkodowanie::Wzorzec, None) => slowo.chars().count(),
+                        _ => slowo.len(),
+                    };
+                    liczenie_slow::sprawdz_dlugosc_slowa(slowo, bajty).map_err(|_| {
+                        Blad::parametr(
+                            "liczenie.slowa",
+                            format!("{} ({} B)", slowo, bajty),
+                            format!("w trybie dokladnym najwyzej {} B", liczenie_slow::MAKS_DLUGOSC_SLOWA),
+                        )
+                    })?;
+                }
+            }
+            if let Some(&w) = plan.watki.iter().find(|&&w| w == 0) {
+                return Err(Blad::parametr("liczenie.watki", w, "liczby >= 1"));
+            }
+        }
+
+        if let Some(plan) = &self.czestosc {
+            niepusta("czestosc.pliki", &plan.pliki)?;
+            niepusta("czestosc.watki", &plan.watki)?;
+            niepusta("czestosc.implementacje", &plan.implementacje)?;
+            for plik in plan.pliki.iter().chain(&plan.plik_stop_slow) {
+                if !Path::new(plik).is_file() {
+                    return Err(Blad::parametr("czestosc.pliki", plik, "istniejacy plik"));
+                }
+            }
+            if let Some(&w) = plan.watki.iter().find(|&&w| w == 0) {
+                return Err(Blad::parametr("czestosc.watki", w, "liczby >= 1"));
+            }
+            if let Some(inna) = plan.implementacje.iter().find(|i| !czestosc::IMPLEMENTACJE.contains(i)) {
+                return Err(Blad::parametr(
+                    "czestosc.implementacje",
+                    inna.nazwa(),
+                    "sekwencyjny, arc_threads lub rayon",
+                ));
+            }
+            if plan.top == 0 {
+                return Err(Blad::parametr("czestosc.top", 0, "liczba >= 1"));
+            }
+        }
+
+        if let Some(plan) = &self.szyfrowanie {
+            niepusta("szyfrowanie.algorytmy", &plan.algorytmy)?;
+            niepusta("szyfrowanie.liczba_wiadomosci", &plan.liczba_wiadomosci)?;
+            if plan.dlugosc_min > plan.dlugosc_max {
+                return Err(Blad::parametr(
+                    "szyfrowanie.dlugosc_min",
+                    plan.dlugosc_min,
+                    format!("najwyzej dlugosc_max ({})", plan.dlugosc_max),
+                ));
+            }
+            for &szyfr in &plan.algorytmy {
+                let pole = match szyfr {
+                    Szyfr::AesCtr => "szyfrowanie.klucze_aes",
+                    Szyfr::RsaPkcs1v15 => "szyfrowanie.klucze_rsa",
+                };
+                niepusta(pole, plan.klucze(szyfr))?;
+                for &bits in plan.klucze(szyfr) {
+                    match szyfr {
+                        Szyfr::AesCtr => {
+                            rsa_aes::rozmiar_klucza_aes(&bits).map_err(|o| Blad::parametr(pole, bits, o))?
+                        }
+                        Szyfr::RsaPkcs1v15 => {
+                            if !(RSA_MIN_BITS..=RSA_MAX_BITS).contains(&bits) {
+                                return Err(Blad::parametr(pole, bits, format!("{}-{}", RSA_MIN_BITS, RSA_MAX_BITS)));
+                            }
+                            if plan.dlugosc_max > MaxRsaPlaintextLen(bits) {
+                                return Err(Blad::parametr(
+                                    "szyfrowanie.dlugosc_max",
+                                    plan.dlugosc_max,
+                                    format!("najwyzej {} B dla klucza RSA {}-bit", MaxRsaPlaintextLen(bits), bits),
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Ziarno z linii polecen ma pierwszenstwo przed ziarnem zapisanym w pliku
+    pub fn uruchom(&self, seed: Option<u64>) -> Wynik<Raport> {
+        let seed = seed.or(self.seed).unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut rekordy = Vec::new();
+
+        if let Some(plan) = &self.liczenie {
+            self.uruchom_liczenie(plan, &mut rekordy)?;
+        }
+
+        if let Some(plan) = &self.czestosc {
+            self.uruchom_czestosc(plan, &mut rekordy)?;
+        }
+
+        if let Some(plan) = &self.szyfrowanie {
+            for &szyfr in &plan.algorytmy {
+                for &bits in plan.klucze(szyfr) {
+                    for &liczba in &plan.liczba_wiadomosci {
+                        for powtorzenie in 1..=self.powtorzenia {
+                            eprintln!(
+                                "szyfrowanie {} klucz={} wiadomosci={} powt={}",
+                                szyfr.nazwa(),
+                                bits,
+                                liczba,
+                                powtorzenie
+                            );
+                            let teksty: Vec<String> = (0..liczba)
+                                .map(|_| rsa_aes::generateRandomText(plan.dlugosc_min, plan.dlugosc_max, &mut rng))
+                                .collect();
+                            let metrics = rsa_aes::BenchmarkCipher(szyfr, &teksty, bits, &mut rng)?;
+                            let parametry = BTreeMap::from([
+                                ("klucz".to_string(), bits.to_string()),
+                                ("wiadomosci".to_string(), liczba.to_string()),
+                                ("dlugosc".to_string(), format!("{}-{}", plan.dlugosc_min, plan.dlugosc_max)),
+                            ]);
+                            rekordy.push(RekordWyniku {
+                                benchmark: "szyfrowanie".to_string(),
+                                implementacja: szyfr.nazwa().to_string(),
+                                parametry,
+                                powtorzenie,
+                                metrics,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Raport {
+            nazwa: self.nazwa.clone(),
+            seed: Some(seed),
+            srodowisko: Some(Srodowisko::wykryj()),
+            rekordy,
+        })
+    }
+
+    fn uruchom_liczenie(&self, plan: &PlanLiczenia, rekordy: &mut Vec<RekordWyniku>) -> Wynik<()> {
+        // Sposob liczenia: tryb dopasowania x algorytm wyszukiwania; regex i tryb rozmyty nie uzywaja algorytmu
+        let sposoby: Vec<(TrybDopasowania, Algorytm)> = plan
+            .tryby
+            .iter()
+            .flat_map(|&tryb| match tryb {
+                TrybDopasowania::Dokladny => plan.algorytmy.iter().map(|&algorytm| (tryb, algorytm)).collect(),
+                TrybDopasowania::Regex | TrybDopasowania::Rozmyty => vec![(tryb, Algorytm::default())],
+            })
+            .collect();
+        if let Some(opcje) = &plan.pozycje {
+            self.uruchom_pozycje(plan, opcje, &sposoby, rekordy)?;
+        }
+        // Warunki pomiaru: rozmiar fragmentu x stan cache
+        let warunki: Vec<(RozmiarFragmentu, StanCache)> = plan
+            .rozmiary_fragmentow()
+            .into_iter()
+            .flat_map(|rozmiar| plan.cache.iter().map(move |&cache| (rozmiar, cache)))
+            .collect();
+        for plik in &plan.pliki {
+            let wejscie = kodowanie::przygotuj(plik, plan.kodowanie, plan.transkodowanie)?;
+            let rozmiar_pliku = wejscie.rozmiar;
+            // Referencja liczona przed pomiarami - jej odczyt pliku nie wplywa na stan cache
+            let referencje = Referencje::przygotuj(
+                plik,
+                &wejscie.sciezka,
+                &plan.slowa,
+                &plan.tryby,
+                &UstawieniaLiczenia {
+                    maks_dopasowanie: plan.maks_dopasowanie,
+                    kodowanie_wzorca: wejscie.kodowanie_wzorca(),
+                    ..Default::default()
+                },
+            )?;
+            for slowo in &plan.slowa {
+                for &(tryb, algorytm) in &sposoby {
+                    for &implementacja in &plan.implementacje {
+                        // Tryb rozmyty tylko w silnikach z rozmyte.rs
+                        if tryb == TrybDopasowania::Rozmyty && !rozmyte::IMPLEMENTACJE.contains(&implementacja) {
+                            continue;
+                        }
+                        // Implementacja sekwencyjna mierzona raz, nie dla kazdej liczby watkow
+                        let watki: &[usize] = if implementacja.rownolegla() { &plan.watki } else { &[1] };
+                        for &liczba_watkow in watki {
+                            for &(rozmiar, cache) in &warunki {
+                                let ustawienia = UstawieniaLiczenia {
+                                    algorytm,
+                                    fragment: rozmiar.rozstrzygnij(rozmiar_pliku, liczba_watkow),
+                                    tryb,
+                                    maks_dopasowanie: plan.maks_dopasowanie,
+                                    maks_odleglosc: plan.maks_odleglosc,
+                                    kodowanie_wzorca: wejscie.kodowanie_wzorca(),
+                                };
+                                for powtorzenie in 1..=self.powtorzenia {
+                                    eprintln!(
+                                        "liczenie_slow {} plik={} slowo={} tryb={} algorytm={} watki={} fragment={} cache={} powt={}",
+                                        implementacja.nazwa(),
+                                        plik,
+                                        slowo,
+                                        tryb.nazwa(),
+                                        algorytm.nazwa(),
+                                        liczba_watkow,
+                                        liczenie_slow::opis_rozmiaru(ustawienia.fragment),
+                                        cache.nazwa(),
+                                        powtorzenie
+                                    );
+                                    liczenie_slow::przygotuj_cache(&wejscie.sciezka, cache)?;
+                                    let mut metrics = liczenie_slow::zmierz(
+                                        implementacja,
+                                        &wejscie.sciezka,
+                                        slowo,
+                                        liczba_watkow,
+                                        &ustawienia,
+                                    )?;
+                                    if let Some(referencja) = referencje.dla(slowo, tryb) {
+                                        let wynik = weryfikacja::zweryfikuj(
+                                            metrics.count,
+                                            referencja,
+                                            &wejscie.sciezka,
+                                            slowo,
+                                            &ustawienia,
+                                        )?;
+                                        if !wynik.zgodna(metrics.count) {
+                                            eprintln!(
+                                                "UWAGA: {} policzyl {}, oczekiwano {} ({}); rozbieznych fragmentow: {}",
+                                                implementacja.nazwa(),
+                                                metrics.count,
+                                                wynik.oczekiwane,
+                                                wynik.zrodlo.nazwa(),
+                                                wynik.fragmenty.len()
+                                            );
+                                        }
+                                        metrics.weryfikacja = Some(wynik);
+                                    }
+                                    let mut parametry = BTreeMap::from([
+                                        ("plik".to_string(), plik.clone()),
+                                        ("slowo".to_string(), slowo.clone()),
+                                        ("tryb".to_string(), tryb.nazwa().to_string()),
+                                        ("watki".to_string(), liczba_watkow.to_string()),
+                                    ]);
+                                    // Wartosci domyslne (kmp, 2 MiB, cieply cache) nie zmieniaja klucza serii -
+                                    // zgodnosc z dawnymi baseline
+                                    if algorytm != Algorytm::Kmp {
+                                        parametry.insert("algorytm".to_string(), algorytm.nazwa().to_string());
+                                    }
+                                    if tryb == TrybDopasowania::Regex && plan.maks_dopasowanie != MAKS_DOPASOWANIE {
+                                        parametry
+                                            .insert("maks_dopasowanie".to_string(), plan.maks_dopasowanie.to_string());
+                                    }
+                                    if tryb == TrybDopasowania::Rozmyty {
+                                        parametry.insert("odleglosc".to_string(), plan.maks_odleglosc.to_string());
+                                    }
+                                    if rozmiar != RozmiarFragmentu::Bajty(ROZMIAR_FRAGMENTU) {
+                                        parametry.insert("fragment".to_string(), ustawienia.fragment.to_string());
+                                    }
+                                    if rozmiar == RozmiarFragmentu::Auto {
+                                        parametry.insert("dobor_fragmentu".to_string(), "auto".to_string());
+                                    }
+                                    if cache != StanCache::Cieply {
+                                        parametry.insert("cache".to_string(), cache.nazwa().to_string());
+                                    }
+                                    wejscie.dopisz_parametry(&mut parametry);
+                                    rekordy.push(RekordWyniku {
+                                        benchmark: "liczenie_slow".to_string(),
+                                        implementacja: implementacja.nazwa().to_string(),
+                                        parametry,
+                                        powtorzenie,
+                                        metrics,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Pozycje trafien dla kazdego pliku, slowa i sposobu trybu dokladnego; fragment jak pierwszy rozmiar z planu
+    fn uruchom_pozycje(
+        &self,
+        plan: &PlanLiczenia,
+        opcje: &OpcjePozycji,
+        sposoby: &[(TrybDopasowania, Algorytm)],
+        rekordy: &mut Vec<RekordWyniku>,
+    ) -> Wynik<()> {
+        let rozmiar = plan.rozmiary_fragmentow().first().copied().unwrap_or(RozmiarFragmentu::Bajty(ROZMIAR_FRAGMENTU));
+        let implementacje = plan.implementacje.iter().filter(|i| pozycje::IMPLEMENTACJE.contains(i));
+        for plik in &plan.pliki {
+            // Kolumny w znakach i kontekst wymagaja tekstu UTF-8 - zawsze przekodowanie pliku
+            let wejscie = kodowanie::przygotuj(plik, plan.kodowanie, Transkodowanie::Wejscie)?;
+            let rozmiar_pliku = wejscie.rozmiar;
+            for slowo in &plan.slowa {
+                for &(tryb, algorytm) in sposoby.iter().filter(|(tryb, _)| *tryb == TrybDopasowania::Dokladny) {
+                    for &implementacja in implementacje.clone() {
+                        let watki: &[usize] = if implementacja.rownolegla() { &plan.watki } else { &[1] };
+                        for &liczba_watkow in watki {
+                            let ustawienia = UstawieniaLiczenia {
+                                algorytm,
+                                fragment: rozmiar.rozstrzygnij(rozmiar_pliku, liczba_watkow),
+                                ..Default::default()
+                            };
+                            for powtorzenie in 1..=self.powtorzenia {
+                                eprintln!(
+                                    "pozycje_slow {} plik={} slowo={} algorytm={} watki={} powt={}",
+                                    implementacja.nazwa(),
+                                    plik,
+                                    slowo,
+                                    algorytm.nazwa(),
+                                    liczba_watkow,
+                                    powtorzenie
+                                );
+                                let (mut metrics, trafienia, buffer) =
+                                    pozycje::zbierz(implementacja, &wejscie.sciezka, slowo, liczba_watkow, &ustawienia)?;
+                                metrics.trafienia =
+                                    Some(pozycje::podsumuj(&buffer, &trafienia, opcje.limit, opcje.kontekst));
+                                let mut parametry = BTreeMap::from([
+                                    ("plik".to_string(), plik.clone()),
+                                    ("slowo".to_string(), slowo.clone()),
+                                    ("tryb".to_string(), tryb.nazwa().to_string()),
+                                    ("watki".to_string(), liczba_watkow.to_string()),
+                                ]);
+                                if algorytm != Algorytm::Kmp {
+                                    parametry.insert("algorytm".to_string(), algorytm.nazwa().to_string());
+                                }
+                                wejscie.dopisz_parametry(&mut parametry);
+                                rekordy.push(RekordWyniku {
+                                    benchmark: "pozycje_slow".to_string(),
+                                    implementacja: implementacja.nazwa().to_string(),
+                                    parametry,
+                                    powtorzenie,
+                                    metrics,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn uruchom_czestosc(&self, plan: &PlanCzestosci, rekordy: &mut Vec<RekordWyniku>) -> Wynik<()> {
+        let mut stop_slowa = plan.stop_slowa.clone();
+        if let Some(plik) = &plan.plik_stop_slow {
+            stop_slowa.extend(czestosc::wczytaj_stop_slowa(plik)?);
+        }
+        let ustawienia = UstawieniaCzestosci::nowe(plan.male_litery, stop_slowa, plan.top);
+        for plik in &plan.pliki {
+            let wejscie = kodowanie::przygotuj(plik, plan.kodowanie, Transkodowanie::Wejscie)?;
+            for &implementacja in &plan.implementacje {
+                let watki: &[usize] = if implementacja.rownolegla() { &plan.watki } else { &[1] };
+                for &liczba_watkow in watki {
+                    for powtorzenie in 1..=self.powtorzenia {
+                        eprintln!(
+                            "czestosc_slow {} plik={} watki={} powt={}",
+                            implementacja.nazwa(),
+                            plik,
+                            liczba_watkow,
+                            powtorzenie
+                        );
+                        let metrics = czestosc::zmierz(implementacja, &wejscie.sciezka, liczba_watkow, &ustawienia)?;
+                        let mut parametry = czestosc::parametry(plik, liczba_watkow, &ustawienia);
+                        wejscie.dopisz_parametry(&mut parametry);
+                        rekordy.push(RekordWyniku {
+                            benchmark: "czestosc_slow".to_string(),
+                            implementacja: implementacja.nazwa().to_string(),
+                            parametry,
+                            powtorzenie,
+                            metrics,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Wczytuje, waliduje i wykonuje scenariusz; raport do pliku `wyjscie` lub na stdout.
+// Kodowanie z linii polecen zastepuje kodowanie (lub wykrywanie) wszystkich plikow scenariusza
+pub fn uruchom_scenariusz(sciezka: &str, seed: Option<u64>, kodowanie: Option<Kodowanie>) -> Wynik<Raport> {
+    let mut scenariusz = Scenariusz::wczytaj(sciezka)?;
+    if kodowanie.is_some() {
+        if let Some(plan) = &mut scenariusz.liczenie {
+            plan.kodowanie = kodowanie;
+        }
+        if let Some(plan) = &mut scenariusz.czestosc {
+            plan.kodowanie = kodowanie;
+        }
+        // Dlugosc slow w bajtach zalezy od kodowania - plan sprawdzany ponownie przed pomiarami
+        scenariusz.waliduj()?;
+    }
+
+    if scenariusz.szyfrowanie.is_some() && !crate::selftest::selftest(false) {
+        return Err(Blad::Kryptografia("selftest nie powiodl sie".to_string()));
+    }
+
+    let raport = scenariusz.uruchom(seed)?;
+    let tekst = raport.formatuj(scenariusz.format)?;
+    match &scenariusz.wyjscie {
+        Some(plik) => {
+            std::fs::write(plik, tekst).map_err(|e| Blad::io(format!("zapis raportu {}", plik), e))?;
+            eprintln!("Raport zapisany do {}", plik);
+        }
+        None => print!("{}", tekst),
+    }
+    Ok(raport)
+}
\ No newline at end of file
@@ -0,0 +1,335 @@
+// src/selftest.rs
+// Testy znanych odpowiedzi (KAT) - sprawdzenie poprawnosci szyfrow przed pomiarami.
+// Zrodla wektorow: AES - FIPS-197 i NIST SP 800-38A, SHA-2 - przyklady NIST do FIPS 180-4, HMAC - RFC 4231,
+// RSA - klucz i wektory z zestawu testow Go crypto/rsa (schematy PKCS#1 v1.5 z RFC 8017, ale nie wektory RFC ani NIST).
+// Te same wektory sprawdzaja testy jednostkowe na dole pliku (cargo test)
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit, KeyIvInit, StreamCipher};
+use hmac::{Hmac, Mac};
+use rsa::{BigUint, Pkcs1v15Encrypt, Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+
+use crate::rsa_aes::{ApplyAesCtr, AES_BLOCK_SIZE};
+
+// Wektory wbudowane w plik wykonywalny (format zblizony do plikow .rsp NIST CAVP)
+const WEKTORY_AES: &str = include_str!("wektory/aes.rsp");
+const WEKTORY_SHA: &str = include_str!("wektory/sha.rsp");
+const WEKTORY_RSA: &str = include_str!("wektory/rsa.rsp");
+const WEKTORY_HMAC: &str = include_str!("wektory/hmac.rsp");
+
+// Jeden rekord: sekcja [..] i pary KLUCZ = wartosc az do pustej linii
+struct Rekord {
+    sekcja: String,
+    pola: HashMap<String, String>,
+}
+
+impl Rekord {
+    fn tekst(&self, nazwa: &str) -> Result<&str, String> {
+        self.pola
+            .get(nazwa)
+            .map(|s| s.as_str())
+            .ok_or_else(|| format!("brak pola {} w sekcji [{}]", nazwa, self.sekcja))
+    }
+
+    fn bajty(&self, nazwa: &str) -> Result<Vec<u8>, String> {
+        let tekst = self.tekst(nazwa)?;
+        hex::decode(tekst).map_err(|e| format!("pole {} nie jest poprawnym hex: {}", nazwa, e))
+    }
+
+    fn opis(&self) -> String {
+        match self.pola.get("COUNT") {
+            Some(count) => format!("[{}] COUNT = {}", self.sekcja, count),
+            None => format!("[{}]", self.sekcja),
+        }
+    }
+}
+
+fn wczytaj_wektory(tekst: &str) -> Vec<Rekord> {
+    let mut rekordy = Vec::new();
+    let mut sekcja = String::new();
+    let mut pola = HashMap::new();
+
+    for linia in tekst.lines().map(str::trim).chain(std::iter::once("")) {
+        if linia.starts_with('#') {
+            continue;
+        }
+
+        if linia.is_empty() || linia.starts_with('[') {
+            if !pola.is_empty() {
+                rekordy.push(Rekord {
+                    sekcja: sekcja.clone(),
+                    pola: std::mem::take(&mut pola),
+                });
+            }
+            if let Some(nazwa) = linia.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sekcja = nazwa.to_string();
+            }
+            continue;
+        }
+
+        if let Some((klucz, wartosc)) = linia.split_once('=') {
+            pola.insert(klucz.trim().to_string(), wartosc.trim().to_string());
+        }
+    }
+
+    rekordy
+}
+
+fn porownaj(co: &str, oczekiwane: &[u8], otrzymane: &[u8]) -> Result<(), String> {
+    if oczekiwane == otrzymane {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}: oczekiwano {}, otrzymano {}",
+            co,
+            hex::encode(oczekiwane),
+            hex::encode(otrzymane)
+        ))
+    }
+}
+
+// AES
+fn aes_ecb<C: BlockEncrypt + KeyInit>(key: &[u8], dane: &mut [u8]) {
+    let cipher = C::new_from_slice(key).unwrap();
+    for blok in dane.chunks_mut(AES_BLOCK_SIZE) {
+        cipher.encrypt_block(GenericArray::from_mut_slice(blok));
+    }
+}
+
+// Wzorcowy CTR z SP 800-38A (licznik 128-bit BE)
+fn aes_ctr128(key: &[u8], iv: &[u8], dane: &mut [u8]) -> Result<(), String> {
+    let blad = |_| format!("niepoprawny klucz ({} B) lub IV ({} B)", key.len(), iv.len());
+    match key.len() {
+        16 => ctr::Ctr128BE::<aes::Aes128>::new_from_slices(key, iv).map_err(blad)?.apply_keystream(dane),
+        24 => ctr::Ctr128BE::<aes::Aes192>::new_from_slices(key, iv).map_err(blad)?.apply_keystream(dane),
+        32 => ctr::Ctr128BE::<aes::Aes256>::new_from_slices(key, iv).map_err(blad)?.apply_keystream(dane),
+        n => return Err(format!("nieobslugiwana dlugosc klucza AES: {} B", n)),
+    }
+    Ok(())
+}
+
+fn test_aes(rekord: &Rekord) -> Result<(), String> {
+    let key = rekord.bajty("KEY")?;
+    let plaintext = rekord.bajty("PLAINTEXT")?;
+    let ciphertext = rekord.bajty("CIPHERTEXT")?;
+
+    let tryb = rekord.sekcja.rsplit('-').next().unwrap_or("");
+    let mut dane = plaintext.clone();
+
+    match (tryb, key.len()) {
+        ("ECB", 16) => aes_ecb::<aes::Aes128>(&key, &mut dane),
+        ("ECB", 24) => aes_ecb::<aes::Aes192>(&key, &mut dane),
+        ("ECB", 32) => aes_ecb::<aes::Aes256>(&key, &mut dane),
+        ("CTR", _) => {
+            let iv = rekord.bajty("IV")?;
+            aes_ctr128(&key, &iv, &mut dane)?;
+
+            // Program uzywa licznika 64-bit LE - pierwszy blok strumienia klucza
+            // jest identyczny jak w SP 800-38A, wiec sprawdzamy go tym samym wektorem
+            let iv: [u8; AES_BLOCK_SIZE] = iv
+                .as_slice()
+                .try_into()
+                .map_err(|_| format!("IV musi miec {} B", AES_BLOCK_SIZE))?;
+            let mut pierwszy_blok = plaintext[..AES_BLOCK_SIZE].to_vec();
+            ApplyAesCtr(&key, &iv, &mut pierwszy_blok).map_err(|e| e.to_string())?;
+            porownaj("CTR64LE (1. blok)", &ciphertext[..AES_BLOCK_SIZE], &pierwszy_blok)?;
+        }
+        (tryb, n) => return Err(format!("nieobslugiwany tryb AES: {} ({} B klucza)", tryb, n)),
+    }
+
+    porownaj("szyfrogram", &ciphertext, &dane)
+}
+
+// SHA-2
+fn test_sha(rekord: &Rekord) -> Result<(), String> {
+    let len: usize = rekord
+        .tekst("LEN")?
+        .parse()
+        .map_err(|_| "pole LEN nie jest liczba".to_string())?;
+    let msg = rekord.bajty("MSG")?;
+    let msg = msg
+        .get(..len / 8)
+        .ok_or_else(|| format!("MSG krotsze niz LEN = {}", len))?;
+    let md = rekord.bajty("MD")?;
+
+    let wynik = match rekord.sekcja.as_str() {
+        "SHA-256" => Sha256::digest(msg).to_vec(),
+        "SHA-512" => Sha512::digest(msg).to_vec(),
+        inny => return Err(format!("nieobslugiwany algorytm: {}", inny)),
+    };
+
+    porownaj("skrot", &md, &wynik)
+}
+
+// HMAC
+fn test_hmac(rekord: &Rekord) -> Result<(), String> {
+    let key = rekord.bajty("KEY")?;
+    let msg = rekord.bajty("MSG")?;
+    let oczekiwany = rekord.bajty("MAC")?;
+
+    let wynik = match rekord.sekcja.as_str() {
+        "HMAC-SHA256" => {
+            let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key).unwrap();
+            mac.update(&msg);
+            mac.finalize().into_bytes().to_vec()
+        }
+        inny => return Err(format!("nieobslugiwany algorytm: {}", inny)),
+    };
+
+    porownaj("MAC", &oczekiwany, &wynik)
+}
+
+// RSA
+fn klucz_rsa(rekord: &Rekord) -> Result<RsaPrivateKey, String> {
+    let liczba = |nazwa: &str| -> Result<BigUint, String> {
+        BigUint::parse_bytes(rekord.tekst(nazwa)?.as_bytes(), 10)
+            .ok_or_else(|| format!("pole {} nie jest liczba dziesietna", nazwa))
+    };
+
+    RsaPrivateKey::from_components(
+        liczba("N")?,
+        liczba("E")?,
+        liczba("D")?,
+        vec![liczba("P")?, liczba("Q")?],
+    )
+    .map_err(|e| format!("niepoprawny klucz RSA: {}", e))
+}
+
+fn test_rsa(rekord: &Rekord, klucz: &RsaPrivateKey) -> Result<(), String> {
+    let message = rekord.bajty("MESSAGE")?;
+
+    match rekord.sekcja.as_str() {
+        "RSAES-PKCS1-v1_5" => {
+            let ciphertext = rekord.bajty("CIPHERTEXT")?;
+            let wynik = klucz
+                .decrypt(Pkcs1v15Encrypt, &ciphertext)
+                .map_err(|e| format!("deszyfrowanie nie powiodlo sie: {}", e))?;
+            porownaj("tekst jawny", &message, &wynik)
+        }
+        "RSASSA-PKCS1-v1_5-SHA256" => {
+            let signature = rekord.bajty("SIGNATURE")?;
+            let digest = Sha256::digest(&message);
+            let wynik = klucz
+                .sign(Pkcs1v15Sign::new::<Sha256>(), &digest)
+                .map_err(|e| format!("podpisywanie nie powiodlo sie: {}", e))?;
+            porownaj("podpis", &signature, &wynik)?;
+
+            RsaPublicKey::from(klucz)
+                .verify(Pkcs1v15Sign::new::<Sha256>(), &digest, &signature)
+                .map_err(|e| format!("weryfikacja podpisu nie powiodla sie: {}", e))
+        }
+        inny => Err(format!("nieobslugiwany schemat RSA: {}", inny)),
+    }
+}
+
+fn test_rsa_plik(rekordy: &[Rekord]) -> Vec<(String, Result<(), String>)> {
+    let klucz = rekordy
+        .iter()
+        .find(|r| r.sekcja == "KLUCZ")
+        .ok_or_else(|| "brak sekcji [KLUCZ]".to_string())
+        .and_then(klucz_rsa);
+
+    rekordy
+        .iter()
+        .filter(|r| r.sekcja != "KLUCZ")
+        .map(|r| {
+            let wynik = match &klucz {
+                Ok(klucz) => test_rsa(r, klucz),
+                Err(e) => Err(e.clone()),
+            };
+            (r.opis(), wynik)
+        })
+        .collect()
+}
+
+// Uruchamia wszystkie wektory; zwraca false, jesli ktorykolwiek sie nie zgadza
+pub fn selftest(gadatliwy: bool) -> bool {
+    let mut wyniki: Vec<(String, Result<(), String>)> = Vec::new();
+
+    for rekord in wczytaj_wektory(WEKTORY_AES) {
+        wyniki.push((rekord.opis(), test_aes(&rekord)));
+    }
+    for rekord in wczytaj_wektory(WEKTORY_SHA) {
+        let opis = format!("{} LEN = {}", rekord.opis(), rekord.tekst("LEN").unwrap_or("?"));
+        wyniki.push((opis, test_sha(&rekord)));
+    }
+    for rekord in wczytaj_wektory(WEKTORY_HMAC) {
+        wyniki.push((rekord.opis(), test_hmac(&rekord)));
+    }
+    wyniki.extend(test_rsa_plik(&wczytaj_wektory(WEKTORY_RSA)));
+
+    let mut bledy = 0;
+    for (opis, wynik) in &wyniki {
+        match wynik {
+            Ok(()) => {
+                if gadatliwy {
+                    println!("OK    {}", opis);
+                }
+            }
+            Err(e) => {
+                bledy += 1;
+                eprintln!("BLAD  {}: {}", opis, e);
+            }
+        }
+    }
+
+    if bledy == 0 {
+        // W trybie cichym podsumowanie na stderr, zeby nie mieszac go z raportem
+        if gadatliwy {
+            println!("Selftest: {} wektorow poprawnych", wyniki.len());
+        } else {
+            eprintln!("Selftest: {} wektorow poprawnych", wyniki.len());
+        }
+        true
+    } else {
+        eprintln!(
+            "Selftest NIEUDANY: {} z {} wektorow niezgodnych - wyniki pomiarow nie sa wiarygodne!",
+            bledy,
+            wyniki.len()
+        );
+        false
+    }
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+
+    // Kazdy wektor z listy musi przejsc; pusta lista oznaczalaby zepsuty plik wektorow
+    fn wszystkie_poprawne(wyniki: Vec<(String, Result<(), String>)>) {
+        assert!(!wyniki.is_empty(), "brak wektorow");
+        let bledy: Vec<String> = wyniki
+            .into_iter()
+            .filter_map(|(opis, wynik)| wynik.err().map(|e| format!("{}: {}", opis, e)))
+            .collect();
+        assert!(bledy.is_empty(), "niezgodne wektory:\n{}", bledy.join("\n"));
+    }
+
+    #[test]
+    fn wektory_aes() {
+        wszystkie_poprawne(wczytaj_wektory(WEKTORY_AES).iter().map(|r| (r.opis(), test_aes(r))).collect());
+    }
+
+    #[test]
+    fn wektory_sha2() {
+        wszystkie_poprawne(wczytaj_wektory(WEKTORY_SHA).iter().map(|r| (r.opis(), test_sha(r))).collect());
+    }
+
+    #[test]
+    fn wektory_hmac() {
+        wszystkie_poprawne(wczytaj_wektory(WEKTORY_HMAC).iter().map(|r| (r.opis(), test_hmac(r))).collect());
+    }
+
+    #[test]
+    fn wektory_rsa() {
+        wszystkie_poprawne(test_rsa_plik(&wczytaj_wektory(WEKTORY_RSA)));
+    }
+
+    #[test]
+    fn zmieniony_wektor_wykryty() {
+        let mut rekordy = wczytaj_wektory(WEKTORY_SHA);
+        let rekord = rekordy.first_mut().expect("brak wektorow SHA");
+        rekord.pola.insert("MD".to_string(), "00".repeat(32));
+        assert!(test_sha(rekord).is_err());
+    }
+}
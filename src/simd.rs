@@ -0,0 +1,185 @@
+// src/simd.rs
+// Zliczanie wystapien wzorca filtrem pierwszego i ostatniego bajtu (AVX2 / SSE2).
+// Wariant wybierany w czasie wykonania; bez wsparcia procesora - wersja skalarna.
+// Liczone sa wszystkie wystapienia, rowniez nakladajace sie (jak w KMP); pozycje() zwraca ich poczatki
+
+// Wariant uzyty na tym procesorze: "avx2", "sse2" albo "skalarny"
+pub fn wariant() -> &'static str {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return "avx2";
+        }
+        if is_x86_feature_detected!("sse2") {
+            return "sse2";
+        }
+    }
+    "skalarny"
+}
+
+pub fn wystapienia(tekst: &[u8], wzorzec: &[u8]) -> i32 {
+    let mut count = 0;
+    przegladaj(tekst, wzorzec, &mut |_| count += 1);
+    count
+}
+
+// Pozycje poczatkow wszystkich wystapien, rosnaco
+pub fn pozycje(tekst: &[u8], wzorzec: &[u8]) -> Vec<usize> {
+    let mut pozycje = Vec::new();
+    przegladaj(tekst, wzorzec, &mut |poz| pozycje.push(poz));
+    pozycje
+}
+
+// Wywoluje `trafienie` z pozycja kazdego wystapienia, w kolejnosci rosnacej
+fn przegladaj(tekst: &[u8], wzorzec: &[u8], trafienie: &mut impl FnMut(usize)) {
+    if wzorzec.is_empty() || tekst.len() < wzorzec.len() {
+        return;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { x86::przegladaj_avx2(tekst, wzorzec, trafienie) };
+        }
+        if is_x86_feature_detected!("sse2") {
+            return unsafe { x86::przegladaj_sse2(tekst, wzorzec, trafienie) };
+        }
+    }
+    przegladaj_skalarnie(tekst, wzorzec, trafienie)
+}
+
+fn przegladaj_skalarnie(tekst: &[u8], wzorzec: &[u8], trafienie: &mut impl FnMut(usize)) {
+    if wzorzec.is_empty() {
+        return;
+    }
+    for (poz, okno) in tekst.windows(wzorzec.len()).enumerate() {
+        if okno == wzorzec {
+            trafienie(poz);
+        }
+    }
+}
+
+// Srodek wzorca sprawdzany tylko na pozycjach, gdzie zgadzaja sie pierwszy i ostatni bajt
+fn zgodny_srodek(tekst: &[u8], poz: usize, wzorzec: &[u8]) -> bool {
+    let m = wzorzec.len();
+    m <= 2 || tekst[poz + 1..poz + m - 1] == wzorzec[1..m - 1]
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    use super::{przegladaj_skalarnie, zgodny_srodek};
+
+    // Blok 32 pozycji startowych: maska bitow, gdzie pierwszy i ostatni bajt pasuja
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn przegladaj_avx2(tekst: &[u8], wzorzec: &[u8], trafienie: &mut impl FnMut(usize)) {
+        let m = wzorzec.len();
+        let pierwszy = _mm256_set1_epi8(wzorzec[0] as i8);
+        let ostatni = _mm256_set1_epi8(wzorzec[m - 1] as i8);
+        let mut i = 0;
+        while i + 32 + m - 1 <= tekst.len() {
+            let a = _mm256_loadu_si256(tekst.as_ptr().add(i) as *const __m256i);
+            let b = _mm256_loadu_si256(tekst.as_ptr().add(i + m - 1) as *const __m256i);
+            let rowne = _mm256_and_si256(_mm256_cmpeq_epi8(a, pierwszy), _mm256_cmpeq_epi8(b, ostatni));
+            let mut maska = _mm256_movemask_epi8(rowne) as u32;
+            while maska != 0 {
+                let poz = i + maska.trailing_zeros() as usize;
+                if zgodny_srodek(tekst, poz, wzorzec) {
+                    trafienie(poz);
+                }
+                maska &= maska - 1;
+            }
+            i += 32;
+        }
+        // Reszta: wystapienia zaczynajace sie od i mieszcza sie w calosci w tekst[i..]
+        przegladaj_skalarnie(&tekst[i..], wzorzec, &mut |poz| trafienie(i + poz));
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn przegladaj_sse2(tekst: &[u8], wzorzec: &[u8], trafienie: &mut impl FnMut(usize)) {
+        let m = wzorzec.len();
+        let pierwszy = _mm_set1_epi8(wzorzec[0] as i8);
+        let ostatni = _mm_set1_epi8(wzorzec[m - 1] as i8);
+        let mut i = 0;
+        while i + 16 + m - 1 <= tekst.len() {
+            let a = _mm_loadu_si128(tekst.as_ptr().add(i) as *const __m128i);
+            let b = _mm_loadu_si128(tekst.as_ptr().add(i + m - 1) as *const __m128i);
+            let rowne = _mm_and_si128(_mm_cmpeq_epi8(a, pierwszy), _mm_cmpeq_epi8(b, ostatni));
+            let mut maska = _mm_movemask_epi8(rowne) as u32;
+            while maska != 0 {
+                let poz = i + maska.trailing_zeros() as usize;
+                if zgodny_srodek(tekst, poz, wzorzec) {
+                    trafienie(poz);
+                }
+                maska &= maska - 1;
+            }
+            i += 16;
+        }
+        przegladaj_skalarnie(&tekst[i..], wzorzec, &mut |poz| trafienie(i + poz));
+    }
+}
+
+#[cfg(test)]
+mod testy {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn skalarnie(tekst: &[u8], wzorzec: &[u8]) -> Vec<usize> {
+        let mut wynik = Vec::new();
+        przegladaj_skalarnie(tekst, wzorzec, &mut |poz| wynik.push(poz));
+        wynik
+    }
+
+    // Kazda sciezka dostepna na tym procesorze, nie tylko wybrana przez przegladaj
+    fn sciezki(tekst: &[u8], wzorzec: &[u8]) -> Vec<(&'static str, Vec<usize>)> {
+        let mut wynik = vec![(wariant(), pozycje(tekst, wzorzec))];
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx2") {
+                let mut pozycje = Vec::new();
+                unsafe { x86::przegladaj_avx2(tekst, wzorzec, &mut |poz| pozycje.push(poz)) };
+                wynik.push(("avx2", pozycje));
+            }
+            if is_x86_feature_detected!("sse2") {
+                let mut pozycje = Vec::new();
+                unsafe { x86::przegladaj_sse2(tekst, wzorzec, &mut |poz| pozycje.push(poz)) };
+                wynik.push(("sse2", pozycje));
+            }
+        }
+        wynik
+    }
+
+    #[test]
+    fn pozycje_zgodne_ze_skanem_skalarnym() {
+        let mut rng = StdRng::seed_from_u64(41);
+        for proba in 0..2000 {
+            let m = 1 + proba % 6;
+            // Dlugosci od krotszych niz wzorzec po kilka blokow 32 bajtow z reszta dowolnej dlugosci
+            let n = rng.gen_range(0..32 * 4 + m);
+            // Male alfabety daja czeste, nakladajace sie wystapienia (np. "aa" w "aaaa")
+            let alfabet: &[u8] = if proba % 3 == 0 { b"a" } else { b"ab" };
+            let tekst: Vec<u8> = (0..n).map(|_| alfabet[rng.gen_range(0..alfabet.len())]).collect();
+            let wzorzec: Vec<u8> = (0..m).map(|_| alfabet[rng.gen_range(0..alfabet.len())]).collect();
+            let spodziewane = skalarnie(&tekst, &wzorzec);
+            for (sciezka, pozycje) in sciezki(&tekst, &wzorzec) {
+                assert_eq!(pozycje, spodziewane, "{} n={} wzorzec {:?}", sciezka, n, wzorzec);
+            }
+            assert_eq!(wystapienia(&tekst, &wzorzec), spodziewane.len() as i32);
+        }
+    }
+
+    #[test]
+    fn wystapienia_w_reszcie_za_ostatnim_blokiem() {
+        // 70 bajtow: dwa bloki AVX2 i reszta, wzorzec tylko w reszcie i na granicy bloku
+        let mut tekst = vec![b'x'; 70];
+        tekst[30..33].copy_from_slice(b"abc");
+        tekst[67..70].copy_from_slice(b"abc");
+        for (sciezka, pozycje) in sciezki(&tekst, b"abc") {
+            assert_eq!(pozycje, vec![30, 67], "{}", sciezka);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+// src/srodowisko.rs
+// Odcisk srodowiska pomiaru: sprzet, system, kompilacja i ustawienia watkow, z ktorymi powstal wynik
+use serde::{Deserialize, Serialize};
+use std::mem;
+use std::process::Command;
+use std::sync::OnceLock;
+use winapi::um::sysinfoapi::{GetLogicalProcessorInformation, GlobalMemoryStatusEx, MEMORYSTATUSEX};
+use winapi::um::winnt::{
+    CacheData, CacheInstruction, CacheTrace, RelationCache, RelationProcessorCore, SYSTEM_LOGICAL_PROCESSOR_INFORMATION,
+};
+
+use crate::liczenie_slow::WERSJA_LICZENIA;
+use crate::simd;
+use crate::zasoby::liczba_procesorow;
+
+// Wartosci z chwili kompilacji (build.rs)
+pub const REWIZJA: &str = env!("GIT_REWIZJA");
+pub const RUSTC: &str = env!("RUSTC_WERSJA");
+pub const PROFIL: &str = env!("PROFIL_KOMPILACJI");
+pub const CEL: &str = env!("CEL_KOMPILACJI");
+pub const CECHY_CELU: &str = env!("CECHY_CELU");
+
+// Cechy procesora istotne dla mierzonych algorytmow (AES-NI, SHA-NI, wektory dla memchr/rayon)
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cechy_cpu() -> Vec<String> {
+    let mut cechy = Vec::new();
+    macro_rules! sprawdz {
+        ($($cecha:tt),*) => {
+            $(if is_x86_feature_detected!($cecha) {
+                cechy.push($cecha.to_string());
+            })*
+        };
+    }
+    sprawdz!("sse2", "sse4.2", "popcnt", "bmi2", "avx", "avx2", "avx512f", "aes", "pclmulqdq", "sha");
+    cechy
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn cechy_cpu() -> Vec<String> {
+    Vec::new()
+}
+
+// Nazwa procesora z rozszerzonych lisci CPUID 0x80000002..0x80000004
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)]
+fn model_cpu() -> String {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    let maks = unsafe { __cpuid(0x8000_0000) }.eax;
+    if maks < 0x8000_0004 {
+        return "nieznany".to_string();
+    }
+    let mut bajty = Vec::with_capacity(48);
+    for lisc in 0x8000_0002u32..=0x8000_0004 {
+        let r = unsafe { __cpuid(lisc) };
+        for rejestr in [r.eax, r.ebx, r.ecx, r.edx] {
+            bajty.extend_from_slice(&rejestr.to_le_bytes());
+        }
+    }
+    String::from_utf8_lossy(&bajty).trim_matches(|c: char| c == '\0' || c.is_whitespace()).to_string()
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn model_cpu() -> String {
+    "nieznany".to_string()
+}
+
+// Jeden poziom pamieci podrecznej, np. L1d 32 KiB x 8
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PamiecCache {
+    pub poziom: u8,
+    pub typ: String,
+    pub rozmiar: u32,
+    pub liczba: usize,
+}
+
+// Rdzenie fizyczne i pamieci podreczne z GetLogicalProcessorInformation
+fn topologia() -> (usize, Vec<PamiecCache>) {
+    unsafe {
+        let mut dlugosc = 0u32;
+        GetLogicalProcessorInformation(std::ptr::null_mut(), &mut dlugosc);
+        let n = dlugosc as usize / mem::size_of::<SYSTEM_LOGICAL_PROCESSOR_INFORMATION>();
+        if n == 0 {
+            return (0, Vec::new());
+        }
+        let mut wpisy: Vec<SYSTEM_LOGICAL_PROCESSOR_INFORMATION> = vec![mem::zeroed(); n];
+        if GetLogicalProcessorInformation(wpisy.as_mut_ptr(), &mut dlugosc) == 0 {
+            return (0, Vec::new());
+        }
+        wpisy.truncate(dlugosc as usize / mem::size_of::<SYSTEM_LOGICAL_PROCESSOR_INFORMATION>());
+
+        let rdzenie = wpisy.iter().filter(|w| w.Relationship == RelationProcessorCore).count();
+        let mut cache: Vec<PamiecCache> = Vec::new();
+        for w in wpisy.iter().filter(|w| w.Relationship == RelationCache) {
+            let opis = w.u.Cache();
+            let typ = match opis.Type {
+                t if t == CacheData => "d",
+                t if t == CacheInstruction => "i",
+                t if t == CacheTrace => "t",
+                _ => "",
+            };
+            match cache
+                .iter_mut()
+                .find(|c| c.poziom == opis.Level && c.typ == typ && c.rozmiar == opis.Size)
+            {
+                Some(c) => c.liczba += 1,
+                None => cache.push(PamiecCache {
+                    poziom: opis.Level,
+                    typ: typ.to_string(),
+                    rozmiar: opis.Size,
+                    liczba: 1,
+                }),
+            }
+        }
+        cache.sort_by(|a, b| (a.poziom, &a.typ).cmp(&(b.poziom, &b.typ)));
+        (rdzenie, cache)
+    }
+}
+
+// Rozmiar jednej instancji najwyzszego poziomu cache danych (zwykle wspolny L3), do doboru fragmentu
+pub fn cache_ostatniego_poziomu() -> Option<u64> {
+    static CACHE: OnceLock<Option<u64>> = OnceLock::new();
+    *CACHE.get_or_init(|| {
+        topologia()
+            .1
+            .iter()
+            .filter(|c| c.typ == "d" || c.typ.is_empty())
+            .max_by_key(|c| c.poziom)
+            .map(|c| c.rozmiar as u64)
+            .filter(|&rozmiar| rozmiar > 0)
+    })
+}
+
+fn pamiec_fizyczna() -> u64 {
+    unsafe {
+        let mut stan: MEMORYSTATUSEX = mem::zeroed();
+        stan.dwLength = mem::size_of::<MEMORYSTATUSEX>() as u32;
+        if GlobalMemoryStatusEx(&mut stan) == 0 {
+            return 0;
+        }
+        stan.ullTotalPhys
+    }
+}
+
+// Pelna wersja systemu (z numerem kompilacji), bez zgadywania z API zaleznych od manifestu
+fn wersja_systemu() -> String {
+    let wynik = if cfg!(windows) {
+        Command::new("cmd").args(["/C", "ver"]).output()
+    } else {
+        Command::new("uname").args(["-sr"]).output()
+    };
+    wynik
+        .ok()
+        .filter(|w| w.status.success())
+        .map(|w| String::from_utf8_lossy(&w.stdout).trim().to_string())
+        .filter(|w| !w.is_empty())
+        .unwrap_or_else(|| std::env::consts::OS.to_string())
+}
+
+// Pola z #[serde(default)], zeby odczytac takze starsze, krotsze odciski z historii i baseline
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Srodowisko {
+    pub host: String,
+    pub system: String,
+    pub wersja_systemu: String,
+    pub architektura: String,
+    pub model_cpu: String,
+    pub rdzenie_fizyczne: usize,
+    // Procesory logiczne
+    pub procesory: usize,
+    pub cache: Vec<PamiecCache>,
+    pub cechy_cpu: Vec<String>,
+    pub pamiec: u64,
+    pub rustc: String,
+    pub profil: String,
+    pub cel: String,
+    pub cechy_celu: Vec<String>,
+    pub watki_rayon: usize,
+    pub rayon_num_threads: Option<String>,
+    pub rewizja: String,
+    // liczenie_slow::WERSJA_LICZENIA; 0 w odciskach sprzed jej wprowadzenia
+    pub wersja_liczenia: u32,
+    // simd::wariant() - sciezka algorytmu simd na tym procesorze; pusty w starszych odciskach
+    pub simd: String,
+}
+
+impl Srodowisko {
+    pub fn wykryj() -> Self {
+        let host = std::env::var("COMPUTERNAME")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| "nieznany".to_string());
+        let (rdzenie_fizyczne, cache) = topologia();
+        Srodowisko {
+            host,
+            system: std::env::consts::OS.to_string(),
+            wersja_systemu: wersja_systemu(),
+            architektura: std::env::consts::ARCH.to_string(),
+            model_cpu: model_cpu(),
+            rdzenie_fizyczne,
+            procesory: liczba_procesorow(),
+            cache,
+            cechy_cpu: cechy_cpu(),
+            pamiec: pamiec_fizyczna(),
+            rustc: RUSTC.to_string(),
+            profil: PROFIL.to_string(),
+            cel: CEL.to_string(),
+            cechy_celu: CECHY_CELU.split(',').filter(|c| !c.is_empty()).map(str::to_string).collect(),
+            watki_rayon: rayon::current_num_threads(),
+            rayon_num_threads: std::env::var("RAYON_NUM_THREADS").ok(),
+            rewizja: REWIZJA.to_string(),
+            wersja_liczenia: WERSJA_LICZENIA,
+            simd: simd::wariant().to_string(),
+        }
+    }
+
+    // Sprzet, na ktorym wyniki sa porownywalne - bez wersji kompilatora i rewizji kodu
+    pub fn ten_sam_sprzet(&self, inne: &Srodowisko) -> bool {
+        self.host == inne.host
+            && self.model_cpu == inne.model_cpu
+            && self.procesory == inne.procesory
+            && self.pamiec == inne.pamiec
+    }
+
+    // Pary (etykieta, wartosc) do raportow tekstowych, CSV i HTML
+    pub fn opis(&self) -> Vec<(&'static str, String)> {
+        let cache: Vec<String> = self
+            .cache
+            .iter()
+            .map(|c| format!("L{}{} {} KiB x{}", c.poziom, c.typ, c.rozmiar / 1024, c.liczba))
+            .collect();
+        vec![
+            ("host", self.host.clone()),
+            ("system", format!("{} ({})", self.wersja_systemu, self.architektura)),
+            ("cpu", self.model_cpu.clone()),
+            (
+                "rdzenie",
+                format!("{} fizycznych, {} logicznych", self.rdzenie_fizyczne, self.procesory),
+            ),
+            ("cache", cache.join(", ")),
+            ("cechy cpu", self.cechy_cpu.join(" ")),
+            ("pamiec", format!("{:.1} GiB", self.pamiec as f64 / (1u64 << 30) as f64)),
+            ("rustc", self.rustc.clone()),
+            ("kompilacja", format!("{} ({})", self.profil, self.cel)),
+            ("cechy celu", self.cechy_celu.join(" ")),
+            (
+                "rayon",
+                format!(
+                    "{} watkow w puli globalnej, RAYON_NUM_THREADS={}",
+                    self.watki_rayon,
+                    self.rayon_num_threads.as_deref().unwrap_or("-")
+                ),
+            ),
+            ("rewizja", self.rewizja.clone()),
+            ("wersja liczenia", self.wersja_liczenia.to_string()),
+            ("wariant simd", if self.simd.is_empty() { "-".to_string() } else { self.simd.clone() }),
+        ]
+    }
+}
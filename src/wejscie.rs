@@ -0,0 +1,87 @@
+// src/wejscie.rs
+// Odczyt odpowiedzi z konsoli. W trybie interaktywnym bledna wartosc powoduje
+// ponowne pytanie, w trybie wsadowym (stdin z pliku/potoku) konczy sie bledem
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+
+use crate::blad::{Blad, Wynik};
+
+pub fn interaktywny() -> bool {
+    io::stdin().is_terminal()
+}
+
+pub fn wczytaj_linie(pytanie: &str) -> Wynik<String> {
+    println!("{}", pytanie);
+    let mut linia = String::new();
+    let przeczytano = io::stdin()
+        .read_line(&mut linia)
+        .map_err(|e| Blad::io("standardowe wejscie", e))?;
+    if przeczytano == 0 {
+        return Err(Blad::io(
+            "standardowe wejscie",
+            io::Error::new(io::ErrorKind::UnexpectedEof, format!("brak odpowiedzi na: {}", pytanie)),
+        ));
+    }
+    Ok(linia.trim().to_string())
+}
+
+// Pyta az do skutku (interaktywnie) o wartosc typu T spelniajaca walidacje;
+// walidacja zwraca opis oczekiwanej wartosci, ktory trafia do komunikatu
+pub fn zapytaj<T, F>(pytanie: &str, pole: &str, walidacja: F) -> Wynik<T>
+where
+    T: FromStr,
+    T::Err: Display,
+    F: Fn(&T) -> Result<(), String>,
+{
+    loop {
+        let linia = wczytaj_linie(pytanie)?;
+        let blad = match linia.parse::<T>() {
+            Ok(wartosc) => match walidacja(&wartosc) {
+                Ok(()) => return Ok(wartosc),
+                Err(oczekiwano) => Blad::parametr(pole, &linia, oczekiwano),
+            },
+            Err(e) => Blad::Parsowanie {
+                pole: pole.to_string(),
+                wartosc: linia,
+                zrodlo: e.to_string(),
+            },
+        };
+
+        if !interaktywny() {
+            return Err(blad);
+        }
+        eprintln!("{} - sprobuj ponownie", blad);
+    }
+}
+
+// Walidacje uzywane w wielu miejscach
+pub fn co_najmniej(min: usize) -> impl Fn(&usize) -> Result<(), String> {
+    move |&x| {
+        if x >= min {
+            Ok(())
+        } else {
+            Err(format!("liczba >= {}", min))
+        }
+    }
+}
+
+pub fn w_zakresie(min: usize, max: usize) -> impl Fn(&usize) -> Result<(), String> {
+    move |&x| {
+        if (min..=max).contains(&x) {
+            Ok(())
+        } else {
+            Err(format!("liczba z zakresu {}-{}", min, max))
+        }
+    }
+}
+
+pub fn jedna_z(dozwolone: &'static [&'static str]) -> impl Fn(&String) -> Result<(), String> {
+    move |x| {
+        if dozwolone.contains(&x.to_lowercase().as_str()) {
+            Ok(())
+        } else {
+            Err(format!("jedna z: {}", dozwolone.join(", ")))
+        }
+    }
+}
\ No newline at end of file
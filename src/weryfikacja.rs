@@ -0,0 +1,224 @@
+// src/weryfikacja.rs
+// Weryfikacja wynikow liczenia: liczba kazdego silnika porownana z referencja - oczekiwana liczba z manifestu
+// korpusu (korpus.rs), a bez manifestu jedno przejscie po calym pliku bez podzialu na fragmenty.
+// Przy niezgodnosci fragmenty sa przeliczane po kolei ta sama funkcja co w silnikach i zestawiane
+// z trafieniami referencji przypisanymi do fragmentow - raport wskazuje fragmenty, w ktorych liczby sie rozchodza
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::blad::Wynik;
+use crate::kompresja;
+use crate::korpus::{self, Manifest};
+use crate::liczenie_slow::{
+    liczby_fragmentow, podziel_na_fragmenty_po, pozycje_jednego_przejscia, przygotuj_wyszukiwanie, KmpPreprocessed,
+    TrybDopasowania, UstawieniaLiczenia,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZrodloReferencji {
+    // Oczekiwana liczba z manifestu wygenerowanego korpusu
+    Manifest,
+    // Jedno przejscie po calym pliku
+    JedenPrzebieg,
+}
+
+impl ZrodloReferencji {
+    pub fn nazwa(self) -> &'static str {
+        match self {
+            ZrodloReferencji::Manifest => "manifest",
+            ZrodloReferencji::JedenPrzebieg => "jeden przebieg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Weryfikacja {
+    pub oczekiwane: u64,
+    pub zrodlo: ZrodloReferencji,
+    // Fragmenty z inna liczba niz referencja; przy niezgodnosci bez rozbieznych fragmentow
+    // blad lezy poza liczeniem fragmentu (scalanie wynikow watkow)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragmenty: Vec<RozbieznyFragment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RozbieznyFragment {
+    pub indeks: usize,
+    // Zakres fragmentu w pliku razem z zakladka
+    pub start: u64,
+    pub koniec: u64,
+    pub oczekiwane: u64,
+    pub policzone: i64,
+}
+
+impl Weryfikacja {
+    pub fn zgodna(&self, count: i32) -> bool {
+        u64::try_from(count) == Ok(self.oczekiwane)
+    }
+}
+
+// Referencje dla slow jednego pliku w trybach dokladnym i regex (rozmyty porownuje silniki miedzy soba)
+pub struct Referencje {
+    wpisy: Vec<(String, TrybDopasowania, u64, ZrodloReferencji)>,
+}
+
+impl Referencje {
+    // `plik` - sciezka podana przez uzytkownika (obok niej szukany manifest), `sciezka` - plik czytany przez
+    // silniki; plik czytany raz, przed pomiarami
+    pub fn przygotuj(
+        plik: &str,
+        sciezka: &str,
+        slowa: &[String],
+        tryby: &[TrybDopasowania],
+        ustawienia: &UstawieniaLiczenia,
+    ) -> Wynik<Self> {
+        let tryby: Vec<TrybDopasowania> = tryby.iter().copied().filter(|&t| t != TrybDopasowania::Rozmyty).collect();
+        let mut wpisy = Vec::new();
+        if tryby.is_empty() {
+            return Ok(Referencje { wpisy });
+        }
+        let manifest = wczytaj_manifest(plik);
+        let (buffer, _) = kompresja::odczytaj(sciezka)?;
+        for slowo in slowa {
+            for &tryb in &tryby {
+                let z_manifestu = manifest
+                    .as_ref()
+                    .filter(|_| tryb == TrybDopasowania::Dokladny)
+                    .and_then(|m| m.wystapienia(slowo));
+                let (liczba, zrodlo) = match z_manifestu {
+                    Some(liczba) => (liczba, ZrodloReferencji::Manifest),
+                    None => {
+                        let wzorzec = przygotuj_wyszukiwanie(slowo, &UstawieniaLiczenia { tryb, ..*ustawienia })?;
+                        (pozycje_jednego_przejscia(&buffer, &wzorzec).len() as u64, ZrodloReferencji::JedenPrzebieg)
+                    }
+                };
+                wpisy.push((slowo.clone(), tryb, liczba, zrodlo));
+            }
+        }
+        Ok(Referencje { wpisy })
+    }
+
+    pub fn dla(&self, slowo: &str, tryb: TrybDopasowania) -> Option<(u64, ZrodloReferencji)> {
+        self.wpisy
+            .iter()
+            .find(|(s, t, _, _)| s == slowo && *t == tryb)
+            .map(|&(_, _, liczba, zrodlo)| (liczba, zrodlo))
+    }
+}
+
+// Manifest obok pliku; nieczytelny pomijany z ostrzezeniem - referencja z jednego przejscia
+fn wczytaj_manifest(plik: &str) -> Option<Manifest> {
+    let sciezka = korpus::sciezka_manifestu(plik);
+    if !Path::new(&sciezka).is_file() {
+        return None;
+    }
+    match Manifest::wczytaj(&sciezka) {
+        Ok(manifest) => Some(manifest),
+        Err(blad) => {
+            eprintln!("Ostrzezenie: pominieto manifest {}: {}", sciezka, blad);
+            None
+        }
+    }
+}
+
+// Wynik pomiaru wzgledem referencji; przy niezgodnosci plik czytany ponownie (juz po pomiarze)
+// i przeliczany fragment po fragmencie
+pub fn zweryfikuj(
+    count: i32,
+    (oczekiwane, zrodlo): (u64, ZrodloReferencji),
+    sciezka: &str,
+    slowo: &str,
+    ustawienia: &UstawieniaLiczenia,
+) -> Wynik<Weryfikacja> {
+    let mut weryfikacja = Weryfikacja { oczekiwane, zrodlo, fragmenty: Vec::new() };
+    if !weryfikacja.zgodna(count) {
+        weryfikacja.fragmenty = rozbiezne_fragmenty(sciezka, slowo, ustawienia)?;
+    }
+    Ok(weryfikacja)
+}
+
+// Fragmenty podzialu z ustawien, w ktorych liczby_fragmentow daje inna liczbe niz trafienia_fragmentow
+pub fn rozbiezne_fragmenty(sciezka: &str, slowo: &str, ustawienia: &UstawieniaLiczenia) -> Wynik<Vec<RozbieznyFragment>> {
+    let (buffer, _) = kompresja::odczytaj(sciezka)?;
+    let wzorzec = przygotuj_wyszukiwanie(slowo, ustawienia)?;
+    let fragmenty = podziel_na_fragmenty_po(&buffer, ustawienia.fragment);
+    let oczekiwane = trafienia_fragmentow(&buffer, &fragmenty, &wzorzec);
+    let policzone = liczby_fragmentow(&buffer, &fragmenty, &wzorzec);
+    Ok(fragmenty
+        .iter()
+        .zip(oczekiwane)
+        .zip(policzone)
+        .enumerate()
+        .filter_map(|(indeks, ((&(start, koniec), oczekiwane), policzone))| {
+            let policzone = i64::from(policzone);
+            (policzone != oczekiwane as i64).then_some(RozbieznyFragment {
+                indeks,
+                start: start as u64,
+                koniec: koniec as u64,
+                oczekiwane,
+                policzone,
+            })
+        })
+        .collect())
+}
+
+// Trafienia jednego przejscia przypisane do fragmentow: kazde do tego, ktorego wlasny obszar zawiera
+// KmpPreprocessed::bajt_przynaleznosci
+pub fn trafienia_fragmentow(buffer: &[u8], fragmenty: &[(usize, usize)], wzorzec: &KmpPreprocessed) -> Vec<u64> {
+    let mut trafienia = vec![0u64; fragmenty.len()];
+    for poczatek in pozycje_jednego_przejscia(buffer, wzorzec) {
+        let bajt = wzorzec.bajt_przynaleznosci(poczatek);
+        if let Some(n) = trafienia.get_mut(fragmenty.partition_point(|&(_, koniec)| koniec <= bajt)) {
+            *n += 1;
+        }
+    }
+    trafienia
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+    use crate::liczenie_slow::{przygotuj_wzorzec_bajtow, Algorytm, MIN_ROZMIAR_FRAGMENTU};
+
+    // Trzy fragmenty po 4 KiB: (0, 4096), (3840, 8192), (7936, 12288)
+    fn bufor(wstawki: &[(usize, &[u8])]) -> Vec<u8> {
+        let mut buffer = vec![b'.'; 3 * MIN_ROZMIAR_FRAGMENTU];
+        for &(poz, slowo) in wstawki {
+            buffer[poz..poz + slowo.len()].copy_from_slice(slowo);
+        }
+        buffer
+    }
+
+    fn sprawdz(buffer: &[u8], slowo: &[u8], oczekiwane: &[u64]) {
+        let fragmenty = podziel_na_fragmenty_po(buffer, MIN_ROZMIAR_FRAGMENTU);
+        for algorytm in [Algorytm::Kmp, Algorytm::Simd, Algorytm::Memmem] {
+            let wzorzec = przygotuj_wzorzec_bajtow(slowo, algorytm);
+            assert_eq!(trafienia_fragmentow(buffer, &fragmenty, &wzorzec), oczekiwane, "{:?}", algorytm);
+            let policzone: Vec<u64> =
+                liczby_fragmentow(buffer, &fragmenty, &wzorzec).into_iter().map(|n| n as u64).collect();
+            assert_eq!(policzone, oczekiwane, "{:?}", algorytm);
+        }
+    }
+
+    #[test]
+    fn slowo_w_zakladce_nalezy_do_poprzedniego_fragmentu() {
+        // 4000..4003 lezy w zakladce (3840, 4096) drugiego fragmentu
+        sprawdz(&bufor(&[(4000, b"kot")]), b"kot", &[1, 0, 0]);
+    }
+
+    #[test]
+    fn slowo_na_granicy_nalezy_do_fragmentu_z_ostatnim_bajtem() {
+        // 8190..8193 przecina granice 8192 - ostatni bajt we wlasnym obszarze trzeciego fragmentu
+        sprawdz(&bufor(&[(8190, b"kot")]), b"kot", &[0, 0, 1]);
+        sprawdz(&bufor(&[(4095, b"kot"), (8191, b"kot")]), b"kot", &[0, 1, 1]);
+    }
+
+    #[test]
+    fn nakladajace_sie_wystapienia() {
+        sprawdz(&bufor(&[(100, b"aaaa")]), b"aa", &[3, 0, 0]);
+        // Wystapienia od 4094 (ostatni bajt 4095), 4095 i 4096 - dwa ostatnie w drugim fragmencie
+        sprawdz(&bufor(&[(4094, b"aaaa")]), b"aa", &[1, 2, 0]);
+    }
+}
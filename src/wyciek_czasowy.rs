@@ -0,0 +1,421 @@
+// src/wyciek_czasowy.rs
+// Wykrywanie wyciekow czasowych metoda dudect: pomiary dla dwoch klas wejsc
+// (stale / losowe) i test t Welcha - |t| > 4.5 oznacza zaleznosc czasu od danych
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+use hmac::{Hmac, Mac};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rsa::{Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::hint::black_box;
+
+use crate::blad::Wynik;
+use crate::srodowisko::Srodowisko;
+use crate::wejscie::{co_najmniej, w_zakresie, zapytaj};
+use crate::wyniki::{Raport, RekordWyniku};
+use crate::zasoby::Pomiar;
+
+// Prog z pracy "dude, is my code constant time?" (Reparaz i in., 2017)
+pub const PROG_T: f64 = 4.5;
+const LICZBA_PRZYCIEC: usize = 100;
+const ROZGRZEWKA: usize = 100;
+
+// Licznik cykli procesora (TSC), a poza x86_64 zegar monotoniczny w ns
+#[cfg(target_arch = "x86_64")]
+fn licznik() -> u64 {
+    unsafe { core::arch::x86_64::_rdtsc() }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn licznik() -> u64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
+// Srednia i wariancja liczone przyrostowo (Welford) osobno dla kazdej klasy
+#[derive(Default, Clone)]
+struct Welch {
+    n: [f64; 2],
+    srednia: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn dodaj(&mut self, klasa: usize, x: f64) {
+        self.n[klasa] += 1.0;
+        let delta = x - self.srednia[klasa];
+        self.srednia[klasa] += delta / self.n[klasa];
+        self.m2[klasa] += delta * (x - self.srednia[klasa]);
+    }
+
+    fn t(&self) -> f64 {
+        if self.n[0] < 2.0 || self.n[1] < 2.0 {
+            return 0.0;
+        }
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        let mianownik = (var0 / self.n[0] + var1 / self.n[1]).sqrt();
+        if mianownik == 0.0 {
+            return 0.0;
+        }
+        (self.srednia[0] - self.srednia[1]) / mianownik
+    }
+}
+
+struct WynikAnalizy {
+    // Identyfikator operacji w rekordach raportu
+    implementacja: &'static str,
+    nazwa: String,
+    pelny: Welch,
+    max_t: f64,
+    prog_przyciecia: Option<u64>,
+}
+
+// Wynik testu zapisywany w Metrics rekordu analizy (count = liczba pomiarow)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WyciekCzasowy {
+    // Liczba pomiarow i sredni czas [cykle] klasy 0 (stale wejscie) i 1 (losowe)
+    pub pomiary: [u64; 2],
+    pub srednie: [f64; 2],
+    pub max_t: f64,
+    // Prog odciecia, przy ktorym padlo max |t|; brak - wszystkie pomiary
+    pub prog_przyciecia: Option<u64>,
+}
+
+impl WyciekCzasowy {
+    pub fn wykryty(&self) -> bool {
+        self.max_t.abs() > PROG_T
+    }
+}
+
+impl WynikAnalizy {
+    fn wyciek(&self) -> WyciekCzasowy {
+        WyciekCzasowy {
+            pomiary: self.pelny.n.map(|n| n as u64),
+            srednie: self.pelny.srednia,
+            max_t: self.max_t,
+            prog_przyciecia: self.prog_przyciecia,
+        }
+    }
+}
+
+// Wejscia sa przygotowywane przed pomiarem, klasy losowane naprzemiennie,
+// zeby dryf temperatury/zegara nie trafial tylko do jednej z nich
+fn zmierz<W, O>(n: usize, rng: &mut StdRng, mut wejscie: W, mut operacja: O) -> Vec<(usize, u64)>
+where
+    W: FnMut(&mut StdRng, usize) -> Vec<u8>,
+    O: FnMut(&[u8]),
+{
+    let klasy: Vec<usize> = (0..n).map(|_| rng.gen_range(0..2)).collect();
+    let wejscia: Vec<Vec<u8>> = klasy.iter().map(|&k| wejscie(rng, k)).collect();
+
+    for x in wejscia.iter().take(ROZGRZEWKA) {
+        operacja(x);
+    }
+
+    let mut pomiary = Vec::with_capacity(n);
+    for (&klasa, x) in klasy.iter().zip(&wejscia) {
+        let start = licznik();
+        operacja(x);
+        let koniec = licznik();
+        pomiary.push((klasa, koniec.saturating_sub(start)));
+    }
+    pomiary
+}
+
+// Jak w dudect: test na wszystkich pomiarach oraz po odcieciu gornych
+// percentyli (przerwania, przelaczenia kontekstu), wynikiem jest max |t|
+fn analizuj(implementacja: &'static str, nazwa: &str, pomiary: &[(usize, u64)]) -> WynikAnalizy {
+    let mut pelny = Welch::default();
+    for &(klasa, czas) in pomiary {
+        pelny.dodaj(klasa, czas as f64);
+    }
+
+    let mut posortowane: Vec<u64> = pomiary.iter().map(|&(_, czas)| czas).collect();
+    posortowane.sort_unstable();
+
+    let mut max_t = pelny.t();
+    let mut prog_przyciecia = None;
+    for k in 0..LICZBA_PRZYCIEC {
+        let percentyl = 1.0 - 0.5f64.powf(10.0 * (k + 1) as f64 / LICZBA_PRZYCIEC as f64);
+        let idx = ((percentyl * posortowane.len() as f64) as usize).min(posortowane.len() - 1);
+        let prog = posortowane[idx];
+
+        let mut przyciety = Welch::default();
+        for &(klasa, czas) in pomiary.iter().filter(|&&(_, czas)| czas < prog) {
+            przyciety.dodaj(klasa, czas as f64);
+        }
+        if przyciety.t().abs() > max_t.abs() {
+            max_t = przyciety.t();
+            prog_przyciecia = Some(prog);
+        }
+    }
+
+    WynikAnalizy {
+        implementacja,
+        nazwa: nazwa.to_string(),
+        pelny,
+        max_t,
+        prog_przyciecia,
+    }
+}
+
+// Operacje badane
+fn aes_szyfrowanie(n: usize, rng: &mut StdRng) -> WynikAnalizy {
+    let mut key = [0u8; 16];
+    rng.fill(&mut key[..]);
+    let cipher = aes::Aes128::new_from_slice(&key).unwrap();
+
+    // Klasa 0: staly blok zerowy, klasa 1: losowe bloki; klucz tajny staly
+    let pomiary = zmierz(
+        n,
+        rng,
+        |rng, klasa| {
+            let mut blok = vec![0u8; 16];
+            if klasa == 1 {
+                rng.fill(&mut blok[..]);
+            }
+            blok
+        },
+        |x| {
+            let mut blok = GenericArray::clone_from_slice(x);
+            cipher.encrypt_block(&mut blok);
+            black_box(blok);
+        },
+    );
+
+    analizuj("aes-128", "AES-128 szyfrowanie bloku", &pomiary)
+}
+
+fn rsa_deszyfrowanie(n: usize, bits: usize, rng: &mut StdRng) -> Wynik<WynikAnalizy> {
+    let private_key = RsaPrivateKey::new(rng, bits)?;
+    let public_key = RsaPublicKey::from(&private_key);
+    let poprawny = public_key.encrypt(rng, Pkcs1v15Encrypt, b"dudect")?;
+    let rozmiar = poprawny.len();
+
+    // Klasa 0: poprawny szyfrogram, klasa 1: losowe bajty (prawie zawsze
+    // niepoprawne dopelnienie) - roznica czasu to wyciek typu Bleichenbacher/Marvin
+    let pomiary = zmierz(
+        n,
+        rng,
+        |rng, klasa| {
+            if klasa == 0 {
+                poprawny.clone()
+            } else {
+                let mut c = vec![0u8; rozmiar];
+                rng.fill(&mut c[1..]);
+                c
+            }
+        },
+        |x| {
+            black_box(private_key.decrypt(Pkcs1v15Encrypt, x).is_ok());
+        },
+    );
+
+    Ok(analizuj("rsa-pkcs1v15", &format!("RSA-{} deszyfrowanie PKCS#1 v1.5", bits), &pomiary))
+}
+
+fn hmac_weryfikacja(n: usize, rng: &mut StdRng, stalo_czasowa: bool) -> WynikAnalizy {
+    let mut key = [0u8; 32];
+    rng.fill(&mut key[..]);
+    let wiadomosc = b"wiadomosc uwierzytelniana kodem MAC";
+
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key).unwrap();
+    mac.update(wiadomosc);
+    let poprawny_tag = mac.finalize().into_bytes().to_vec();
+
+    // Klasa 0: poprawny tag, klasa 1: losowy tag
+    let pomiary = zmierz(
+        n,
+        rng,
+        |rng, klasa| {
+            if klasa == 0 {
+                poprawny_tag.clone()
+            } else {
+                let mut tag = vec![0u8; poprawny_tag.len()];
+                rng.fill(&mut tag[..]);
+                tag
+            }
+        },
+        |tag| {
+            let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key).unwrap();
+            mac.update(wiadomosc);
+            let zgodny = if stalo_czasowa {
+                mac.verify_slice(tag).is_ok()
+            } else {
+                mac.finalize().into_bytes().as_slice() == tag
+            };
+            black_box(zgodny);
+        },
+    );
+
+    if stalo_czasowa {
+        analizuj("hmac-sha256-verify-slice", "HMAC-SHA256 weryfikacja (verify_slice)", &pomiary)
+    } else {
+        analizuj("hmac-sha256-eq", "HMAC-SHA256 weryfikacja (porownanie ==)", &pomiary)
+    }
+}
+
+fn wypisz(wynik: &WynikAnalizy) {
+    println!("\n--- {} ---", wynik.nazwa);
+    println!(
+        "Klasa 0 (stale wejscie):  n = {}, sredni czas = {:.1} cykli",
+        wynik.pelny.n[0], wynik.pelny.srednia[0]
+    );
+    println!(
+        "Klasa 1 (losowe wejscie): n = {}, sredni czas = {:.1} cykli",
+        wynik.pelny.n[1], wynik.pelny.srednia[1]
+    );
+    match wynik.prog_przyciecia {
+        Some(prog) => println!("max |t| = {:.2} (pomiary < {} cykli)", wynik.max_t.abs(), prog),
+        None => println!("max |t| = {:.2} (wszystkie pomiary)", wynik.max_t.abs()),
+    }
+    if wynik.max_t.abs() > PROG_T {
+        println!("WYCIEK: czas wykonania zalezy od danych wejsciowych (|t| > {})", PROG_T);
+    } else {
+        println!("Brak wykrytego wycieku (|t| <= {}) przy tej liczbie pomiarow", PROG_T);
+    }
+}
+
+// Kazda analiza to rekord benchmarku "wyciek_czasowy": czas i zasoby z Pomiar, test w metrics.wyciek
+pub fn analiza_wycieku(seed: Option<u64>) -> Wynik<Raport> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let wybor: usize = zapytaj(
+        "Wybierz operacje do analizy:\n\
+         1. AES-128 szyfrowanie bloku\n\
+         2. RSA deszyfrowanie PKCS#1 v1.5\n\
+         3. HMAC-SHA256 weryfikacja\n\
+         4. Wszystkie",
+        "operacja",
+        w_zakresie(1, 4),
+    )?;
+
+    let n: usize = zapytaj(
+        "Podaj liczbe pomiarow (np. 100000):",
+        "liczba pomiarow",
+        co_najmniej(2 * ROZGRZEWKA),
+    )?;
+    let bits: Option<usize> = if wybor == 2 || wybor == 4 {
+        Some(zapytaj(
+            "Podaj dlugosc klucza RSA (512-4096):",
+            "dlugosc klucza RSA",
+            w_zakresie(512, 4096),
+        )?)
+    } else {
+        None
+    };
+
+    let mut rekordy = Vec::new();
+    let mut dodaj = |analiza: &dyn Fn(&mut StdRng) -> Wynik<WynikAnalizy>, klucz: Option<usize>| -> Wynik<()> {
+        let pomiar = Pomiar::start();
+        let wynik = analiza(&mut rng)?;
+        let mut metrics = pomiar.zakoncz(i32::try_from(n).unwrap_or(i32::MAX));
+        wypisz(&wynik);
+        metrics.wyciek = Some(wynik.wyciek());
+        let mut parametry = BTreeMap::from([("pomiary".to_string(), n.to_string())]);
+        if let Some(bits) = klucz {
+            parametry.insert("klucz".to_string(), bits.to_string());
+        }
+        rekordy.push(RekordWyniku {
+            benchmark: "wyciek_czasowy".to_string(),
+            implementacja: wynik.implementacja.to_string(),
+            parametry,
+            powtorzenie: 1,
+            metrics,
+        });
+        Ok(())
+    };
+
+    println!("\n=== Analiza wycieku czasowego (test t Welcha) ===");
+    if wybor == 1 || wybor == 4 {
+        dodaj(&|rng| Ok(aes_szyfrowanie(n, rng)), None)?;
+    }
+    if let Some(bits) = bits {
+        dodaj(&|rng| rsa_deszyfrowanie(n, bits, rng), Some(bits))?;
+    }
+    if wybor == 3 || wybor == 4 {
+        dodaj(&|rng| Ok(hmac_weryfikacja(n, rng, true)), None)?;
+        dodaj(&|rng| Ok(hmac_weryfikacja(n, rng, false)), None)?;
+    }
+
+    Ok(Raport {
+        nazwa: "wyciek_czasowy (konsola)".to_string(),
+        seed,
+        srodowisko: Some(Srodowisko::wykryj()),
+        rekordy,
+    })
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+
+    fn welch(klasa0: &[f64], klasa1: &[f64]) -> Welch {
+        let mut welch = Welch::default();
+        for &x in klasa0 {
+            welch.dodaj(0, x);
+        }
+        for &x in klasa1 {
+            welch.dodaj(1, x);
+        }
+        welch
+    }
+
+    #[test]
+    fn t_welcha_dla_znanych_srednich_i_wariancji() {
+        // Srednie 3 i 5, wariancje 2.5: t = -2 / sqrt(2.5/5 + 2.5/5) = -2
+        let w = welch(&[1.0, 2.0, 3.0, 4.0, 5.0], &[3.0, 4.0, 5.0, 6.0, 7.0]);
+        assert_eq!(w.srednia, [3.0, 5.0]);
+        assert!((w.t() + 2.0).abs() < 1e-12, "{}", w.t());
+
+        // Rozne liczebnosci i wariancje: sr. 2 (war. 1, n 3), sr. 10 (war. 5, n 5) -> t = -8 / sqrt(1/3 + 5/5)
+        let w = welch(&[1.0, 2.0, 3.0], &[7.0, 9.0, 10.0, 11.0, 13.0]);
+        assert!((w.t() + 8.0 / (1.0f64 / 3.0 + 5.0 / 5.0).sqrt()).abs() < 1e-12, "{}", w.t());
+
+        // Za malo pomiarow albo zerowa wariancja - brak decyzji
+        assert_eq!(welch(&[1.0], &[5.0, 6.0]).t(), 0.0);
+        assert_eq!(welch(&[4.0, 4.0], &[4.0, 4.0]).t(), 0.0);
+    }
+
+    #[test]
+    fn prog_decyzji() {
+        let wyciek = |max_t| WyciekCzasowy { pomiary: [10, 10], srednie: [0.0; 2], max_t, prog_przyciecia: None };
+        assert!(!wyciek(PROG_T).wykryty());
+        assert!(!wyciek(-4.4).wykryty());
+        assert!(wyciek(4.6).wykryty());
+        assert!(wyciek(-4.6).wykryty());
+    }
+
+    #[test]
+    fn analiza_rozroznia_klasy() {
+        // Te same czasy w obu klasach - brak wycieku takze po przycieciach
+        let rowne: Vec<(usize, u64)> = (0..2000).map(|i| (i % 2, 100 + (i as u64 / 2) % 10)).collect();
+        let wynik = analizuj("test", "rowne", &rowne).wyciek();
+        assert_eq!(wynik.pomiary, [1000, 1000]);
+        assert!(!wynik.wykryty(), "{}", wynik.max_t);
+
+        // Klasa 1 stale o 3 cykle wolniejsza
+        let rozne: Vec<(usize, u64)> = rowne.iter().map(|&(klasa, czas)| (klasa, czas + 3 * klasa as u64)).collect();
+        let wynik = analizuj("test", "rozne", &rozne).wyciek();
+        assert!(wynik.wykryty() && wynik.max_t < 0.0, "{}", wynik.max_t);
+    }
+
+    #[test]
+    fn przyciecie_odslania_wyciek_ukryty_przez_odstajace() {
+        // Klasy rozne o 2 cykle, ale po 10 przerwan (10^6 cykli) w kazdej zaglusza test na wszystkich pomiarach
+        let mut pomiary: Vec<(usize, u64)> = (0..2000).map(|i| (i % 2, 100 + (i as u64 / 2) % 2 + 2 * (i % 2) as u64)).collect();
+        pomiary.extend((0..20).map(|i| (i % 2, 1_000_000)));
+        let analiza = analizuj("test", "odstajace", &pomiary);
+        assert!(analiza.pelny.t().abs() < 1.0, "{}", analiza.pelny.t());
+        let wynik = analiza.wyciek();
+        assert!(wynik.wykryty(), "{}", wynik.max_t);
+        assert!(wynik.prog_przyciecia.is_some());
+    }
+}
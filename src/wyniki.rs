@@ -0,0 +1,561 @@
+// src/wyniki.rs
+// Rekordy wynikow i raport laczony (tekst / CSV / JSON / HTML)
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use crate::blad::{Blad, Wynik};
+use crate::srodowisko::Srodowisko;
+use crate::wyciek_czasowy::PROG_T;
+use crate::zasoby::Metrics;
+
+// Rozbiezne fragmenty wypisywane na jeden niezgodny rekord w raporcie tekstowym i HTML
+pub const POKAZANE_FRAGMENTY: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatRaportu {
+    Tekst,
+    Csv,
+    Json,
+    // Samodzielny plik HTML z wykresami SVG
+    Html,
+}
+
+// Jeden pomiar: co mierzono (benchmark + implementacja + parametry) i wynik
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RekordWyniku {
+    pub benchmark: String,
+    pub implementacja: String,
+    pub parametry: BTreeMap<String, String>,
+    pub powtorzenie: usize,
+    pub metrics: Metrics,
+}
+
+impl RekordWyniku {
+    // Identyfikator serii pomiarow - rekordy rozniace sie tylko powtorzeniem maja ten sam klucz
+    pub fn klucz(&self) -> String {
+        let parametry: Vec<String> = self.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        format!("{}/{}[{}]", self.benchmark, self.implementacja, parametry.join(","))
+    }
+}
+
+// Seria liczenia (implementacja + parametry poza fragmentem) i sredni czas dla kazdego rozmiaru fragmentu
+#[derive(Debug, Clone)]
+pub struct WplywFragmentu {
+    pub implementacja: String,
+    pub parametry: String,
+    pub czasy: Vec<(usize, f64)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Raport {
+    pub nazwa: String,
+    pub seed: Option<u64>,
+    // Odcisk hosta i kompilacji; brak w raportach zapisanych przed jego wprowadzeniem
+    #[serde(default)]
+    pub srodowisko: Option<Srodowisko>,
+    pub rekordy: Vec<RekordWyniku>,
+}
+
+impl Raport {
+    pub fn formatuj(&self, format: FormatRaportu) -> Wynik<String> {
+        match format {
+            FormatRaportu::Tekst => Ok(self.jako_tekst()),
+            FormatRaportu::Csv => Ok(self.jako_csv()),
+            FormatRaportu::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| Blad::io("serializacja raportu JSON", e.into())),
+            FormatRaportu::Html => Ok(crate::raport_html::html(self)),
+        }
+    }
+
+    fn jako_tekst(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "=== Raport: {} ===", self.nazwa);
+        if let Some(seed) = self.seed {
+            let _ = writeln!(out, "Ziarno (seed): {}", seed);
+        }
+        if let Some(srodowisko) = &self.srodowisko {
+            for (etykieta, wartosc) in srodowisko.opis() {
+                let _ = writeln!(out, "{:<11} {}", format!("{}:", etykieta), wartosc);
+            }
+        }
+        let _ = writeln!(
+            out,
+            "{:<14} {:<14} {:<48} {:>5} {:>10} {:>10} {:>8} {:>9} {:>9} {:>9} {:>12} {:>12}",
+            "benchmark",
+            "implementacja",
+            "parametry",
+            "powt",
+            "count",
+            "czas [s]",
+            "CPU [%]",
+            "CPU abs",
+            "user [s]",
+            "sys [s]",
+            "RAM [B]",
+            "szczyt [B]"
+        );
+        for r in &self.rekordy {
+            let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let _ = writeln!(
+                out,
+                "{:<14} {:<14} {:<48} {:>5} {:>10} {:>10.4} {:>8.1} {:>8.1}% {:>9.3} {:>9.3} {:>12} {:>12}",
+                r.benchmark,
+                r.implementacja,
+                parametry.join(" "),
+                r.powtorzenie,
+                r.metrics.count,
+                r.metrics.czas,
+                r.metrics.cpu_usage,
+                r.metrics.cpu_usage_abs,
+                r.metrics.cpu_user,
+                r.metrics.cpu_system,
+                r.metrics.ram_usage,
+                r.metrics.ram_szczyt
+            );
+        }
+        self.alokacje_tekst(&mut out);
+        self.rozklad_tekst(&mut out);
+        self.fragmenty_tekst(&mut out);
+        self.czestosc_tekst(&mut out);
+        self.pozycje_tekst(&mut out);
+        self.rozmyte_tekst(&mut out);
+        self.dekompresja_tekst(&mut out);
+        self.weryfikacja_tekst(&mut out);
+        self.wyciek_tekst(&mut out);
+        out
+    }
+
+    // Rekordy z pozycjami trafien pogrupowane wg parametrow bez liczby watkow
+    pub fn grupy_pozycji(&self) -> BTreeMap<String, Vec<&RekordWyniku>> {
+        self.grupy_bez_watkow(|m| m.trafienia.is_some())
+    }
+
+    // Rekordy trybu rozmytego (wyniki wg odleglosci) pogrupowane jak pozycje
+    pub fn grupy_rozmyte(&self) -> BTreeMap<String, Vec<&RekordWyniku>> {
+        self.grupy_bez_watkow(|m| m.odleglosci.is_some())
+    }
+
+    // Wybrane rekordy wg parametrow bez liczby watkow - w grupie wszystkie silniki powinny dac ten sam wynik
+    fn grupy_bez_watkow(&self, wybor: impl Fn(&Metrics) -> bool) -> BTreeMap<String, Vec<&RekordWyniku>> {
+        let mut grupy: BTreeMap<String, Vec<&RekordWyniku>> = BTreeMap::new();
+        for r in self.rekordy.iter().filter(|r| wybor(&r.metrics)) {
+            let parametry: Vec<String> = r
+                .parametry
+                .iter()
+                .filter(|(k, _)| k.as_str() != "watki")
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            grupy.entry(parametry.join(" ")).or_default().push(r);
+        }
+        grupy
+    }
+
+    // Pierwsze trafienia i kontekst raz na grupe; silniki z innym wynikiem oznaczone
+    fn pozycje_tekst(&self, out: &mut String) {
+        let grupy = self.grupy_pozycji();
+        if grupy.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Pozycje trafien ---");
+        for (parametry, rekordy) in grupy {
+            let Some(t) = rekordy[0].metrics.trafienia.as_ref() else { continue };
+            let _ = writeln!(out, "{}", parametry);
+            let _ = writeln!(out, "  trafienia: {} (pokazane: {})", t.liczba, t.pierwsze.len());
+            let rozne: Vec<String> = rekordy
+                .iter()
+                .filter(|r| r.metrics.trafienia.as_ref() != Some(t))
+                .map(|r| format!("{} x{} powt {}", r.implementacja, r.parametry.get("watki").map_or("1", String::as_str), r.powtorzenie))
+                .collect();
+            if !rozne.is_empty() {
+                let _ = writeln!(out, "  UWAGA: inne pozycje niz {}: {}", rekordy[0].implementacja, rozne.join(", "));
+            }
+            let _ = writeln!(out, "  {:>14} {:>10} {:>8}", "offset", "linia", "kolumna");
+            for p in &t.pierwsze {
+                let _ = writeln!(out, "  {:>14} {:>10} {:>8}", p.offset, p.linia, p.kolumna);
+            }
+            for linia in &t.kontekst {
+                let _ = writeln!(out, "  {}", linia);
+            }
+        }
+    }
+
+    // Wystapienia wg odleglosci raz na grupe; silniki z innym wynikiem oznaczone
+    fn rozmyte_tekst(&self, out: &mut String) {
+        let grupy = self.grupy_rozmyte();
+        if grupy.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Dopasowania rozmyte wg odleglosci ---");
+        for (parametry, rekordy) in grupy {
+            let Some(odleglosci) = rekordy[0].metrics.odleglosci.as_ref() else { continue };
+            let suma: u64 = odleglosci.iter().sum();
+            let _ = writeln!(out, "{}", parametry);
+            let _ = writeln!(out, "  wystapienia: {}", suma);
+            for (d, liczba) in odleglosci.iter().enumerate() {
+                let udzial = if suma > 0 { *liczba as f64 / suma as f64 * 100.0 } else { 0.0 };
+                let _ = writeln!(out, "  odleglosc {}: {:>12} {:>7.1}%", d, liczba, udzial);
+            }
+            let rozne: Vec<String> = rekordy
+                .iter()
+                .filter(|r| r.metrics.odleglosci.as_ref() != Some(odleglosci))
+                .map(|r| format!("{} x{} powt {}", r.implementacja, r.parametry.get("watki").map_or("1", String::as_str), r.powtorzenie))
+                .collect();
+            if !rozne.is_empty() {
+                let _ = writeln!(out, "  UWAGA: inne wyniki niz {}: {}", rekordy[0].implementacja, rozne.join(", "));
+            }
+        }
+    }
+
+    // Histogram raz na plik i ustawienia (wszystkie silniki licza to samo); rozbieznosc oznaczona
+    fn czestosc_tekst(&self, out: &mut String) {
+        let grupy = self.grupy_bez_watkow(|m| m.histogram.is_some());
+        if grupy.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Czestosc slow ---");
+        for (parametry, rekordy) in grupy {
+            let Some(h) = rekordy[0].metrics.histogram.as_ref() else { continue };
+            let _ = writeln!(out, "{}", parametry);
+            let _ = writeln!(
+                out,
+                "  slowa: {}, slownik: {}, hapaksy: {} ({:.1}% slownika), pominiete stop-slowa: {}",
+                h.tokeny,
+                h.slownik,
+                h.hapaksy,
+                if h.slownik > 0 { h.hapaksy as f64 / h.slownik as f64 * 100.0 } else { 0.0 },
+                h.pominiete
+            );
+            let rozne: Vec<String> = rekordy
+                .iter()
+                .filter(|r| r.metrics.histogram.as_ref() != Some(h))
+                .map(|r| format!("{} powt {}", r.implementacja, r.powtorzenie))
+                .collect();
+            if !rozne.is_empty() {
+                let _ = writeln!(out, "  UWAGA: inny histogram niz {}: {}", rekordy[0].implementacja, rozne.join(", "));
+            }
+            for (i, s) in h.top.iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "  {:>4}. {:<24} {:>12} {:>8.3}%",
+                    i + 1,
+                    s.slowo,
+                    s.liczba,
+                    if h.tokeny > 0 { s.liczba as f64 / h.tokeny as f64 * 100.0 } else { 0.0 }
+                );
+            }
+        }
+    }
+
+    // Serie liczenia zmierzone dla co najmniej dwoch jawnych rozmiarow fragmentu (przeglad);
+    // rekordy bez parametru `fragment` maja rozmiar domyslny, rekordy z doborem auto sa pomijane
+    pub fn wplyw_fragmentu(&self) -> Vec<WplywFragmentu> {
+        let mut serie: BTreeMap<(String, String), BTreeMap<usize, Vec<f64>>> = BTreeMap::new();
+        for r in self.rekordy.iter().filter(|r| r.benchmark == "liczenie_slow") {
+            if r.parametry.contains_key("dobor_fragmentu") {
+                continue;
+            }
+            let fragment = match r.parametry.get("fragment") {
+                Some(f) => match f.parse() {
+                    Ok(f) => f,
+                    Err(_) => continue,
+                },
+                None => crate::liczenie_slow::ROZMIAR_FRAGMENTU,
+            };
+            let parametry: Vec<String> = r
+                .parametry
+                .iter()
+                .filter(|(k, _)| k.as_str() != "fragment")
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            serie
+                .entry((r.implementacja.clone(), parametry.join(" ")))
+                .or_default()
+                .entry(fragment)
+                .or_default()
+                .push(r.metrics.czas);
+        }
+        serie
+            .into_iter()
+            .filter(|(_, czasy)| czasy.len() >= 2)
+            .map(|((implementacja, parametry), czasy)| WplywFragmentu {
+                implementacja,
+                parametry,
+                czasy: czasy
+                    .into_iter()
+                    .map(|(fragment, t)| (fragment, t.iter().sum::<f64>() / t.len() as f64))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    // Sredni czas dla kazdego rozmiaru fragmentu, najszybszy oznaczony gwiazdka
+    fn fragmenty_tekst(&self, out: &mut String) {
+        let wplyw = self.wplyw_fragmentu();
+        if wplyw.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Czas wzgledem rozmiaru fragmentu ---");
+        let _ = writeln!(
+            out,
+            "{:<14} {:<48} {:>12} {:>12} {:>10}",
+            "implementacja", "parametry", "fragment", "czas [s]", "wzgl."
+        );
+        for w in wplyw {
+            let najlepszy = w.czasy.iter().map(|&(_, t)| t).fold(f64::INFINITY, f64::min);
+            let _ = writeln!(out, "{:<14} {}", w.implementacja, w.parametry);
+            for (fragment, czas) in w.czasy {
+                let _ = writeln!(
+                    out,
+                    "{:<14} {:<48} {:>12} {:>12.4} {:>9.2}x{}",
+                    "",
+                    "",
+                    crate::liczenie_slow::opis_rozmiaru(fragment),
+                    czas,
+                    if najlepszy > 0.0 { czas / najlepszy } else { 1.0 },
+                    if czas == najlepszy { " *" } else { "" }
+                );
+            }
+        }
+    }
+
+    // Podzial pracy silnikow rownoleglych na watki
+    fn rozklad_tekst(&self, out: &mut String) {
+        let z_rozkladem: Vec<_> = self
+            .rekordy
+            .iter()
+            .filter_map(|r| r.metrics.rozklad.as_ref().map(|rozklad| (r, rozklad)))
+            .collect();
+        if z_rozkladem.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Rozklad pracy na watki ---");
+        let _ = writeln!(
+            out,
+            "{:<14} {:<14} {:<48} {:>5} {:>12} {:>14} {:>12} {:>12}",
+            "benchmark", "implementacja", "parametry", "powt", "fragmenty", "bajty", "praca [s]", "join [s]"
+        );
+        for (r, rozklad) in z_rozkladem {
+            let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let _ = writeln!(
+                out,
+                "{:<14} {:<14} {:<48} {:>5}   nierownowaga {:.2}",
+                r.benchmark,
+                r.implementacja,
+                parametry.join(" "),
+                r.powtorzenie,
+                rozklad.nierownowaga
+            );
+            for w in &rozklad.watki {
+                let _ = writeln!(
+                    out,
+                    "{:>84} {:>12} {:>14} {:>12.4} {:>12.4}",
+                    format!("watek {}", w.watek),
+                    w.fragmenty,
+                    w.bajty,
+                    w.praca,
+                    w.bezczynnosc
+                );
+            }
+        }
+    }
+
+    // Sekcja licznika alokacji (cecha `alokacje`); pomijana, gdy zaden rekord jej nie ma
+    fn alokacje_tekst(&self, out: &mut String) {
+        let z_alokacjami: Vec<_> = self
+            .rekordy
+            .iter()
+            .filter_map(|r| r.metrics.alokacje.as_ref().map(|a| (r, a)))
+            .collect();
+        if z_alokacjami.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Alokacje sterty ---");
+        let _ = writeln!(
+            out,
+            "{:<14} {:<14} {:<48} {:>5} {:>12} {:>14} {:>14} {:>14}",
+            "benchmark", "implementacja", "parametry", "powt", "alokacje", "zaalokowane [B]", "zwolnione [B]", "szczyt [B]"
+        );
+        for (r, a) in z_alokacjami {
+            let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let _ = writeln!(
+                out,
+                "{:<14} {:<14} {:<48} {:>5} {:>12} {:>14} {:>14} {:>14}",
+                r.benchmark,
+                r.implementacja,
+                parametry.join(" "),
+                r.powtorzenie,
+                a.liczba,
+                a.zaalokowane,
+                a.zwolnione,
+                a.szczyt
+            );
+            for w in &a.watki {
+                let _ = writeln!(
+                    out,
+                    "{:>84} {:>12} {:>14} {:>14} {:>14}",
+                    format!("watek {}", w.watek),
+                    w.liczba,
+                    w.zaalokowane,
+                    w.zwolnione,
+                    w.szczyt
+                );
+            }
+        }
+    }
+
+    // Czas dekompresji plikow skompresowanych oddzielony od wyszukiwania (reszta czasu pomiaru)
+    fn dekompresja_tekst(&self, out: &mut String) {
+        let z_dekompresja: Vec<_> = self
+            .rekordy
+            .iter()
+            .filter_map(|r| r.metrics.czas_dekompresji.map(|d| (r, d)))
+            .collect();
+        if z_dekompresja.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Dekompresja ---");
+        let _ = writeln!(
+            out,
+            "{:<14} {:<14} {:<48} {:>5} {:>12} {:>16} {:>16} {:>8}",
+            "benchmark", "implementacja", "parametry", "powt", "czas [s]", "dekompresja [s]", "wyszukiwanie [s]", "udzial"
+        );
+        for (r, dekompresja) in z_dekompresja {
+            let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let _ = writeln!(
+                out,
+                "{:<14} {:<14} {:<48} {:>5} {:>12.4} {:>16.4} {:>16.4} {:>7.1}%",
+                r.benchmark,
+                r.implementacja,
+                parametry.join(" "),
+                r.powtorzenie,
+                r.metrics.czas,
+                dekompresja,
+                (r.metrics.czas - dekompresja).max(0.0),
+                if r.metrics.czas > 0.0 { 100.0 * dekompresja / r.metrics.czas } else { 0.0 }
+            );
+        }
+    }
+
+    // Zgodnosc count z referencja; niezgodne rekordy z rozbieznymi fragmentami (najwyzej POKAZANE_FRAGMENTY)
+    fn weryfikacja_tekst(&self, out: &mut String) {
+        let zweryfikowane: Vec<_> = self
+            .rekordy
+            .iter()
+            .filter_map(|r| r.metrics.weryfikacja.as_ref().map(|w| (r, w)))
+            .collect();
+        if zweryfikowane.is_empty() {
+            return;
+        }
+        let niezgodne: Vec<_> = zweryfikowane.iter().filter(|(r, w)| !w.zgodna(r.metrics.count)).collect();
+        let _ = writeln!(out, "--- Weryfikacja wynikow ---");
+        let _ = writeln!(out, "zgodne z referencja: {}/{}", zweryfikowane.len() - niezgodne.len(), zweryfikowane.len());
+        for (r, w) in niezgodne {
+            let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let _ = writeln!(
+                out,
+                "NIEZGODNOSC {} {} {} powt {}: count {}, oczekiwano {} ({})",
+                r.benchmark,
+                r.implementacja,
+                parametry.join(" "),
+                r.powtorzenie,
+                r.metrics.count,
+                w.oczekiwane,
+                w.zrodlo.nazwa()
+            );
+            if w.fragmenty.is_empty() {
+                let _ = writeln!(out, "  fragmenty zgodne - rozbieznosc przy scalaniu wynikow");
+                continue;
+            }
+            let _ = writeln!(out, "  {:>8} {:>14} {:>14} {:>12} {:>12}", "fragment", "start", "koniec", "oczekiwano", "policzono");
+            for f in w.fragmenty.iter().take(POKAZANE_FRAGMENTY) {
+                let _ = writeln!(out, "  {:>8} {:>14} {:>14} {:>12} {:>12}", f.indeks, f.start, f.koniec, f.oczekiwane, f.policzone);
+            }
+            if w.fragmenty.len() > POKAZANE_FRAGMENTY {
+                let _ = writeln!(out, "  ... i {} kolejnych", w.fragmenty.len() - POKAZANE_FRAGMENTY);
+            }
+        }
+    }
+
+    // Analiza wycieku czasowego: srednie czasy klas i max |t| dla kazdej operacji
+    fn wyciek_tekst(&self, out: &mut String) {
+        let analizy: Vec<_> = self.rekordy.iter().filter_map(|r| r.metrics.wyciek.as_ref().map(|w| (r, w))).collect();
+        if analizy.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "--- Analiza wycieku czasowego (|t| > {} = wyciek) ---", PROG_T);
+        let _ = writeln!(
+            out,
+            "{:<26} {:<24} {:>10} {:>14} {:>14} {:>8}  werdykt",
+            "implementacja", "parametry", "pomiary", "klasa 0 [cyk]", "klasa 1 [cyk]", "max |t|"
+        );
+        for (r, w) in analizy {
+            let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let _ = writeln!(
+                out,
+                "{:<26} {:<24} {:>10} {:>14.1} {:>14.1} {:>8.2}  {}",
+                r.implementacja,
+                parametry.join(" "),
+                r.metrics.count,
+                w.srednie[0],
+                w.srednie[1],
+                w.max_t.abs(),
+                if w.wykryty() { "WYCIEK" } else { "brak" }
+            );
+        }
+    }
+
+    fn jako_csv(&self) -> String {
+        // Srodowisko w wierszach komentarza przed naglowkiem (np. pandas: comment='#')
+        let mut out = String::new();
+        if let Some(srodowisko) = &self.srodowisko {
+            for (etykieta, wartosc) in srodowisko.opis() {
+                let _ = writeln!(out, "# {}: {}", etykieta, wartosc);
+            }
+        }
+        out.push_str(
+            "benchmark,implementacja,parametry,powtorzenie,count,czas,cpu_usage,ram_usage,cpu_usage_abs,cpu_user,cpu_system,ram_szczyt,\
+             alokacje_liczba,alokacje_bajty,alokacje_zwolnione,alokacje_szczyt,nierownowaga,slownik,hapaksy,odleglosci,czas_dekompresji,oczekiwane,zgodnosc,max_t,wyciek\n",
+        );
+        for r in &self.rekordy {
+            let parametry: Vec<String> = r.parametry.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            let _ = writeln!(
+                out,
+                "{},{},\"{}\",{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                r.benchmark,
+                r.implementacja,
+                parametry.join(";").replace('"', "\"\""),
+                r.powtorzenie,
+                r.metrics.count,
+                r.metrics.czas,
+                r.metrics.cpu_usage,
+                r.metrics.ram_usage,
+                r.metrics.cpu_usage_abs,
+                r.metrics.cpu_user,
+                r.metrics.cpu_system,
+                r.metrics.ram_szczyt,
+                // Puste pola, gdy program zbudowano bez licznika alokacji
+                r.metrics.alokacje.as_ref().map_or_else(
+                    || ",,,".to_string(),
+                    |a| format!("{},{},{},{}", a.liczba, a.zaalokowane, a.zwolnione, a.szczyt)
+                ),
+                r.metrics.rozklad.as_ref().map_or_else(String::new, |rozklad| rozklad.nierownowaga.to_string()),
+                r.metrics.histogram.as_ref().map_or_else(|| ",".to_string(), |h| format!("{},{}", h.slownik, h.hapaksy)),
+                // Wystapienia dla odleglosci 0..=k rozdzielone srednikami
+                r.metrics.odleglosci.as_ref().map_or_else(String::new, |o| {
+                    o.iter().map(u64::to_string).collect::<Vec<_>>().join(";")
+                }),
+                r.metrics.czas_dekompresji.map_or_else(String::new, |d| d.to_string()),
+                r.metrics.weryfikacja.as_ref().map_or_else(String::new, |w| w.oczekiwane.to_string()),
+                r.metrics
+                    .weryfikacja
+                    .as_ref()
+                    .map_or("", |w| if w.zgodna(r.metrics.count) { "tak" } else { "nie" }),
+                r.metrics.wyciek.as_ref().map_or_else(String::new, |w| w.max_t.abs().to_string()),
+                r.metrics.wyciek.as_ref().map_or("", |w| if w.wykryty() { "tak" } else { "nie" })
+            );
+        }
+        out
+    }
+}
\ No newline at end of file
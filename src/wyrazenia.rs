@@ -0,0 +1,234 @@
+// src/wyrazenia.rs
+// Tryb wyrazen regularnych: silnik regex (czas liniowy, bez nawrotow) na bajtach pliku.
+// Fragment liczy dopasowania zaczynajace sie w jego wlasnym obszarze; przeszukiwane okno siega
+// `maks` bajtow przed obszar (wyrownanie kolejnych dopasowan) i `maks` bajtow za niego,
+// wiec dopasowanie nie dluzsze niz `maks` jest widoczne w calosci, nawet gdy przecina granice fragmentu.
+// Lancuch przylegajacych dopasowan dluzszy niz `maks` moze rozjechac wyrownanie okna z jednym przejsciem
+// (np. a{7} w ciagu liter a) - brzegi obszarow (Obszar) uzgadnia potem po kolei Wyrazenie::uzgodnij
+use regex::bytes::Regex;
+
+use crate::blad::{Blad, Wynik};
+
+// Domyslna maksymalna dlugosc dopasowania (bajty) - tyle samo co zakladka trybu dokladnego
+pub const MAKS_DOPASOWANIE: usize = 256;
+
+// Wynik obszaru: liczba wlasnych dopasowan i brzegi potrzebne do uzgodnienia z jednym przejsciem
+// (pozycje w tekscie przekazanym do dopasowania_w_obszarze)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Obszar {
+    pub od: usize,
+    pub do_: usize,
+    pub liczba: i32,
+    // Pierwsze wlasne dopasowanie (poczatek, koniec)
+    pub pierwsze: Option<(usize, usize)>,
+    // Koniec ostatniego wlasnego dopasowania
+    pub koniec_ostatniego: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Wyrazenie {
+    regex: Regex,
+    // Najdluzsze dopasowanie liczone poprawnie na granicy fragmentow
+    maks: usize,
+}
+
+impl Wyrazenie {
+    pub fn nowe(wzorzec: &str, maks: usize) -> Wynik<Self> {
+        if maks == 0 {
+            return Err(Blad::parametr("maks_dopasowanie", 0, "liczba >= 1"));
+        }
+        let regex = Regex::new(wzorzec).map_err(|e| Blad::Parsowanie {
+            pole: "wyrazenie regularne".to_string(),
+            wartosc: wzorzec.to_string(),
+            zrodlo: e.to_string(),
+        })?;
+        // Puste dopasowania nie sa wystapieniami, a kolejne dopasowanie zawsze zaczyna sie za koncem poprzedniego
+        if min_dlugosc(wzorzec) == Some(0) {
+            return Err(Blad::parametr("wyrazenie regularne", wzorzec, "wzorzec nie dopasowujacy pustego tekstu"));
+        }
+        // Okna fragmentow obcinaja dopasowanie do `maks` bajtow, a jedno przejscie (poczatki) nie - wynik
+        // nieograniczonego wzorca zalezalby od podzialu na fragmenty
+        if maks_dlugosc(wzorzec).is_none() {
+            return Err(Blad::parametr(
+                "wyrazenie regularne",
+                wzorzec,
+                "wzorzec o ograniczonej dlugosci dopasowania (np. \\d{1,20} zamiast \\d+)",
+            ));
+        }
+        Ok(Wyrazenie { regex, maks })
+    }
+
+    pub fn maks(&self) -> usize {
+        self.maks
+    }
+
+    // Pierwsze dopasowanie zaczete od `poz`; kontekst (\b, ^) z bajtow przed `poz`
+    fn nastepne(&self, tekst: &[u8], poz: usize) -> Option<(usize, usize)> {
+        self.regex.find_at(tekst, poz).map(|m| (m.start(), m.end()))
+    }
+
+    // Dopasowania zaczynajace sie w tekst[od..do_], szukane od `maks` bajtow przed obszarem; liczba moze
+    // odbiegac od jednego przejscia na poczatku obszaru - suma po Wyrazenie::korekta jest juz zgodna
+    pub fn dopasowania_w_obszarze(&self, tekst: &[u8], (od, do_): (usize, usize)) -> Obszar {
+        let tekst = &tekst[..(do_ + self.maks).min(tekst.len())];
+        let mut obszar = Obszar { od, do_, liczba: 0, pierwsze: None, koniec_ostatniego: None };
+        let mut poz = od.saturating_sub(self.maks);
+        while let Some((poczatek, koniec)) = self.nastepne(tekst, poz).filter(|&(poczatek, _)| poczatek < do_) {
+            if poczatek >= od {
+                obszar.liczba += 1;
+                obszar.pierwsze.get_or_insert((poczatek, koniec));
+                obszar.koniec_ostatniego = Some(koniec);
+            }
+            poz = koniec;
+        }
+        obszar
+    }
+
+    // Liczba dopasowan jednego przejscia zaczetych w obszarze i koniec ostatniego z nich. `koniec_poprzedniego` -
+    // koniec ostatniego dopasowania jednego przejscia sprzed obszaru (0 bez takiego). Przejscie wznowione od niego
+    // idzie obok dopasowan obszaru do pierwszego wspolnego poczatku - dalej oba sa identyczne
+    pub fn uzgodnij(&self, tekst: &[u8], obszar: &Obszar, koniec_poprzedniego: usize) -> (i32, usize) {
+        let tekst = &tekst[..(obszar.do_ + self.maks).min(tekst.len())];
+        let w_obszarze = |dopasowanie: Option<(usize, usize)>| dopasowanie.filter(|&(poczatek, _)| poczatek < obszar.do_);
+        let mut liczba = obszar.liczba;
+        let mut ostatni = koniec_poprzedniego;
+        let mut wlasne = obszar.pierwsze;
+        let mut poz = koniec_poprzedniego.max(obszar.od.saturating_sub(self.maks));
+        loop {
+            let przejscie = w_obszarze(self.nastepne(tekst, poz));
+            // Wlasne dopasowania przed dopasowaniem przejscia nie naleza do jednego przejscia
+            while let Some((poczatek, koniec)) = wlasne {
+                if przejscie.is_some_and(|(p, _)| poczatek >= p) {
+                    break;
+                }
+                liczba -= 1;
+                wlasne = w_obszarze(self.nastepne(tekst, koniec));
+            }
+            let Some((poczatek, koniec)) = przejscie else {
+                return (liczba, ostatni);
+            };
+            if wlasne.map(|(p, _)| p) == Some(poczatek) {
+                return (liczba, obszar.koniec_ostatniego.unwrap_or(koniec));
+            }
+            if poczatek >= obszar.od {
+                liczba += 1;
+            }
+            ostatni = koniec;
+            poz = koniec;
+        }
+    }
+
+    // Roznica miedzy suma liczb obszarow a jednym przejsciem; obszary pokrywaja tekst bez przerw
+    pub fn korekta(&self, tekst: &[u8], mut obszary: Vec<Obszar>) -> i32 {
+        obszary.sort_by_key(|o| o.od);
+        let mut koniec = 0;
+        let mut korekta = 0;
+        for obszar in &obszary {
+            let (liczba, koniec_obszaru) = self.uzgodnij(tekst, obszar, koniec);
+            korekta += liczba - obszar.liczba;
+            koniec = koniec_obszaru;
+        }
+        korekta
+    }
+
+    // Poczatki dopasowan jednego przejscia po calym tekscie - referencja dla podzialu na fragmenty
+    pub fn poczatki(&self, tekst: &[u8]) -> Vec<usize> {
+        self.regex.find_iter(tekst).map(|m| m.start()).collect()
+    }
+}
+
+fn wlasciwosci(wzorzec: &str) -> Option<regex_syntax::hir::Properties> {
+    regex_syntax::ParserBuilder::new()
+        .utf8(false)
+        .build()
+        .parse(wzorzec)
+        .ok()
+        .map(|hir| hir.properties().clone())
+}
+
+// Najdluzsze mozliwe dopasowanie wzorca w bajtach; None dla wzorcow nieograniczonych (np. \d+)
+pub fn maks_dlugosc(wzorzec: &str) -> Option<usize> {
+    wlasciwosci(wzorzec).and_then(|w| w.maximum_len())
+}
+
+// Najkrotsze mozliwe dopasowanie wzorca w bajtach
+pub fn min_dlugosc(wzorzec: &str) -> Option<usize> {
+    wlasciwosci(wzorzec).and_then(|w| w.minimum_len())
+}
+
+// Wzorzec dluzszy niz `maks` bylby liczony blednie na granicach fragmentow
+pub fn sprawdz_maks(wzorzec: &str, maks: usize) -> Wynik<()> {
+    Wyrazenie::nowe(wzorzec, maks)?;
+    match maks_dlugosc(wzorzec) {
+        Some(dlugosc) if dlugosc > maks => Err(Blad::parametr(
+            "maks_dopasowanie",
+            maks,
+            format!("co najmniej {} bajtow (najdluzsze dopasowanie wzorca {} w UTF-8)", dlugosc, wzorzec),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod testy {
+    use super::*;
+
+    // Suma obszarow o granicach co `krok` po korekcie
+    fn policz_obszarami(wyrazenie: &Wyrazenie, tekst: &[u8], krok: usize) -> i32 {
+        let obszary: Vec<Obszar> = (0..tekst.len())
+            .step_by(krok)
+            .map(|od| wyrazenie.dopasowania_w_obszarze(tekst, (od, (od + krok).min(tekst.len()))))
+            .collect();
+        let suma: i32 = obszary.iter().map(|o| o.liczba).sum();
+        suma + wyrazenie.korekta(tekst, obszary)
+    }
+
+    #[test]
+    fn lancuch_dluzszy_niz_maks_zgodny_z_jednym_przejsciem() {
+        let wyrazenie = Wyrazenie::nowe("a{7}", 16).unwrap();
+        let mut tekst = vec![b'a'; 10_000];
+        tekst.extend_from_slice(b" x ");
+        tekst.extend(std::iter::repeat_n(b'a', 333));
+        let jedno_przejscie = wyrazenie.poczatki(&tekst).len() as i32;
+        let mut rozjechane = 0;
+        for krok in [64, 100, 4096] {
+            let bez_korekty: i32 = (0..tekst.len())
+                .step_by(krok)
+                .map(|od| wyrazenie.dopasowania_w_obszarze(&tekst, (od, (od + krok).min(tekst.len()))).liczba)
+                .sum();
+            rozjechane += usize::from(bez_korekty != jedno_przejscie);
+            assert_eq!(policz_obszarami(&wyrazenie, &tekst, krok), jedno_przejscie, "krok {}", krok);
+        }
+        assert!(rozjechane > 0, "zaden podzial nie rozjezdza wyrownania");
+    }
+
+    #[test]
+    fn uzgodnij_pierwszy_obszar_bez_zmian() {
+        let wyrazenie = Wyrazenie::nowe(r"\bkot\b", 16).unwrap();
+        let tekst = b"kot i kot, ale nie kotek; kot";
+        let obszar = wyrazenie.dopasowania_w_obszarze(tekst, (0, tekst.len()));
+        assert_eq!(obszar.liczba, 3);
+        assert_eq!(wyrazenie.uzgodnij(tekst, &obszar, 0), (3, tekst.len()));
+    }
+
+    #[test]
+    fn wzorzec_dopasowujacy_pusty_tekst_odrzucony() {
+        assert!(Wyrazenie::nowe("a*", 16).is_err());
+        assert!(Wyrazenie::nowe("a{1,5}", 16).is_ok());
+    }
+
+    #[test]
+    fn wzorzec_nieograniczony_odrzucony() {
+        // Obejscie walidacji: fragmenty z korekta i jedno przejscie licza a+ roznie
+        let wyrazenie = Wyrazenie { regex: Regex::new("a+").unwrap(), maks: 16 };
+        let tekst = vec![b'a'; 10_000];
+        assert_eq!(wyrazenie.poczatki(&tekst).len(), 1);
+        assert_eq!(policz_obszarami(&wyrazenie, &tekst, 4096), 3);
+
+        for wzorzec in ["a+", r"\d+", r"\w{2,}"] {
+            assert!(Wyrazenie::nowe(wzorzec, 16).is_err(), "{}", wzorzec);
+            assert!(sprawdz_maks(wzorzec, 256).is_err(), "{}", wzorzec);
+        }
+        assert!(sprawdz_maks(r"\d{1,20}", 256).is_ok());
+    }
+}
@@ -0,0 +1,318 @@
+// src/zasoby.rs
+// Sonda zasobow procesu (WinAPI) i wspolny typ wyniku pomiaru
+use serde::{Deserialize, Serialize};
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use crate::alokacje::{Region, StatystykiAlokacji};
+use crate::czestosc::Histogram;
+use crate::pozycje::Trafienia;
+use crate::weryfikacja::Weryfikacja;
+use crate::wyciek_czasowy::WyciekCzasowy;
+use winapi::{
+    shared::minwindef::FILETIME,
+    um::{
+        processthreadsapi::{GetCurrentProcess, GetCurrentThread, GetProcessTimes, GetThreadTimes},
+        psapi::GetProcessMemoryInfo,
+        sysinfoapi::GetSystemInfo,
+    },
+};
+
+// Okres probkowania zbioru roboczego w trakcie pomiaru
+const OKRES_PROBKOWANIA: Duration = Duration::from_millis(1);
+
+// Wynik jednego pomiaru: count to liczba wystapien (liczenie slow)
+// albo liczba poprawnie przetworzonych wiadomosci (szyfrowanie).
+// Pola dodane pozniej maja #[serde(default)], zeby czytac starsze raporty i baseline
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Metrics {
+    pub count: i32,
+    pub czas: f64,
+    // Uzycie CPU znormalizowane do wszystkich rdzeni: 100% = kazdy rdzen zajety przez caly czas
+    pub cpu_usage: f64,
+    // Zmiana pamieci prywatnej procesu [B]; ujemna, gdy obszar zwolnil wiecej niz zajal
+    pub ram_usage: i64,
+    // Czas CPU w trybie uzytkownika i jadra [s]
+    #[serde(default)]
+    pub cpu_user: f64,
+    #[serde(default)]
+    pub cpu_system: f64,
+    // Uzycie CPU wzgledem jednego rdzenia: 400% = cztery rdzenie zajete
+    #[serde(default)]
+    pub cpu_usage_abs: f64,
+    // Najwiekszy zbior roboczy (pamiec rezydentna) w trakcie pomiaru [B]
+    #[serde(default)]
+    pub ram_szczyt: u64,
+    // Tylko przy wlaczonym liczniku alokacji (cecha `alokacje`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alokacje: Option<StatystykiAlokacji>,
+    // Tylko dla silnikow rownoleglych
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rozklad: Option<RozkladPracy>,
+    // Tylko dla histogramu czestosci slow (count = liczba policzonych slow)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<Histogram>,
+    // Tylko przy zbieraniu pozycji trafien (count = trafienia bez duplikatow z zakladek)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trafienia: Option<Trafienia>,
+    // Tylko w trybie rozmytym: wystapienia wg odleglosci Levenshteina 0..=k (count = ich suma)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub odleglosci: Option<Vec<u64>>,
+    // Tylko dla pliku skompresowanego: czesc `czas` spedzona w dekoderze [s]; reszta to wyszukiwanie
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub czas_dekompresji: Option<f64>,
+    // Tylko przy sprawdzeniu count z referencja (tryby dokladny i regex); przy niezgodnosci z rozbieznymi fragmentami
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weryfikacja: Option<Weryfikacja>,
+    // Tylko dla analizy wycieku czasowego (count = liczba pomiarow)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wyciek: Option<WyciekCzasowy>,
+}
+
+// Praca jednego watku silnika rownoleglego
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PracaWatku {
+    pub watek: usize,
+    pub fragmenty: usize,
+    pub bajty: u64,
+    // Laczny czas liczenia fragmentow [s]
+    pub praca: f64,
+    // Czekanie na join: od konca pracy watku do konca pracy ostatniego watku [s]
+    pub bezczynnosc: f64,
+}
+
+// Podzial pracy miedzy watki; nierownowaga = najdluzsza praca / srednia praca (1.0 = rowny podzial)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RozkladPracy {
+    pub watki: Vec<PracaWatku>,
+    pub nierownowaga: f64,
+}
+
+// Czas CPU procesu albo watku od jego startu [s]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CzasyCpu {
+    pub user: f64,
+    pub system: f64,
+}
+
+// Migawka pamieci procesu [B]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StanPamieci {
+    pub prywatna: usize,
+    pub zbior_roboczy: usize,
+    // Szczyt zbioru roboczego od startu procesu (licznik systemowy, nie do wyzerowania)
+    pub szczyt_zbioru_roboczego: usize,
+}
+
+// Windows API wrappers
+pub fn get_cpu_times() -> CzasyCpu {
+    unsafe {
+        let mut create_time = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let mut exit_time = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let mut kernel_time = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let mut user_time = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+
+        let process = GetCurrentProcess();
+        GetProcessTimes(
+            process,
+            &mut create_time,
+            &mut exit_time,
+            &mut kernel_time,
+            &mut user_time,
+        );
+
+        let user = (user_time.dwHighDateTime as u64) << 32 | user_time.dwLowDateTime as u64;
+        let kernel = (kernel_time.dwHighDateTime as u64) << 32 | kernel_time.dwLowDateTime as u64;
+
+        CzasyCpu {
+            user: user as f64 * 1e-7,
+            system: kernel as f64 * 1e-7,
+        }
+    }
+}
+
+// Czas CPU biezacego watku (GetThreadTimes)
+pub fn czasy_watku() -> CzasyCpu {
+    let zero = FILETIME { dwLowDateTime: 0, dwHighDateTime: 0 };
+    let (mut utworzenie, mut zakonczenie, mut jadro, mut uzytkownik) = (zero, zero, zero, zero);
+    unsafe {
+        GetThreadTimes(GetCurrentThread(), &mut utworzenie, &mut zakonczenie, &mut jadro, &mut uzytkownik);
+    }
+    let sekundy = |czas: FILETIME| ((czas.dwHighDateTime as u64) << 32 | czas.dwLowDateTime as u64) as f64 * 1e-7;
+    CzasyCpu {
+        user: sekundy(uzytkownik),
+        system: sekundy(jadro),
+    }
+}
+
+pub fn get_cpu_time() -> f64 {
+    let czasy = get_cpu_times();
+    czasy.user + czasy.system
+}
+
+pub fn get_memory_info() -> StanPamieci {
+    #[allow(non_snake_case)]
+    #[repr(C)]
+    struct PROCESS_MEMORY_COUNTERS_EX {
+        cb: u32,
+        PageFaultCount: u32,
+        PeakWorkingSetSize: usize,
+        WorkingSetSize: usize,
+        QuotaPeakPagedPoolUsage: usize,
+        QuotaPagedPoolUsage: usize,
+        QuotaPeakNonPagedPoolUsage: usize,
+        QuotaNonPagedPoolUsage: usize,
+        PagefileUsage: usize,
+        PeakPagefileUsage: usize,
+        PrivateUsage: usize,
+    }
+
+    unsafe {
+        let mut pmc: PROCESS_MEMORY_COUNTERS_EX = mem::zeroed();
+        pmc.cb = mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32;
+
+        let process = GetCurrentProcess();
+        GetProcessMemoryInfo(
+            process,
+            &mut pmc as *mut _ as *mut _,
+            mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+        );
+
+        StanPamieci {
+            prywatna: pmc.PrivateUsage,
+            zbior_roboczy: pmc.WorkingSetSize,
+            szczyt_zbioru_roboczego: pmc.PeakWorkingSetSize,
+        }
+    }
+}
+
+pub fn get_memory_usage() -> usize {
+    get_memory_info().prywatna
+}
+
+pub fn liczba_procesorow() -> usize {
+    unsafe {
+        let mut sys_info = mem::zeroed();
+        GetSystemInfo(&mut sys_info);
+        sys_info.dwNumberOfProcessors as usize
+    }
+}
+
+// Watek probkujacy zbior roboczy; lapie chwilowe szczyty, ktore zniknely przed koncem pomiaru.
+// Na Windows sleep trwa co najmniej takt zegara (~15.6 ms), wiec szczyty krotsze od niego moga umknac.
+// Watek zwraca wlasny czas CPU, odejmowany od czasu procesu - pomiar obejmuje tylko mierzony kod
+struct Probkowanie {
+    stop: Arc<AtomicBool>,
+    maks: Arc<AtomicUsize>,
+    watek: JoinHandle<CzasyCpu>,
+}
+
+impl Probkowanie {
+    fn start(poczatek: usize) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let maks = Arc::new(AtomicUsize::new(poczatek));
+        let watek = {
+            let stop = Arc::clone(&stop);
+            let maks = Arc::clone(&maks);
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    maks.fetch_max(get_memory_info().zbior_roboczy, Ordering::Relaxed);
+                    thread::sleep(OKRES_PROBKOWANIA);
+                }
+                czasy_watku()
+            })
+        };
+        Probkowanie { stop, maks, watek }
+    }
+
+    // Najwiekszy probkowany zbior roboczy i czas CPU watku probkujacego
+    fn zakoncz(self) -> (usize, CzasyCpu) {
+        self.stop.store(true, Ordering::Relaxed);
+        let czasy = self.watek.join().unwrap_or_default();
+        (self.maks.load(Ordering::Relaxed), czasy)
+    }
+}
+
+// Stan poczatkowy mierzonego obszaru: Pomiar::start() ... .zakoncz(count)
+pub struct Pomiar {
+    start_cpu: CzasyCpu,
+    start_time: Instant,
+    start_mem: StanPamieci,
+    probkowanie: Probkowanie,
+    alokacje: Option<Region>,
+}
+
+impl Pomiar {
+    pub fn start() -> Self {
+        let start_mem = get_memory_info();
+        let probkowanie = Probkowanie::start(start_mem.zbior_roboczy);
+        // Na koncu, zeby nie liczyc alokacji samego startu pomiaru
+        let alokacje = Region::start();
+        Pomiar {
+            start_cpu: get_cpu_times(),
+            start_time: Instant::now(),
+            start_mem,
+            probkowanie,
+            alokacje,
+        }
+    }
+
+    pub fn zakoncz(self, count: i32) -> Metrics {
+        let alokacje = self.alokacje.map(Region::zakoncz);
+        let czas = self.start_time.elapsed().as_secs_f64();
+        // Probkowanie zatrzymane przed odczytem czasu procesu - caly jego czas CPU miesci sie w obszarze
+        let (probka, cpu_probkowania) = self.probkowanie.zakoncz();
+        let end_cpu = get_cpu_times();
+        let end_mem = get_memory_info();
+        let num_cpus = liczba_procesorow().max(1) as f64;
+
+        let cpu_user = (end_cpu.user - self.start_cpu.user - cpu_probkowania.user).max(0.0);
+        let cpu_system = (end_cpu.system - self.start_cpu.system - cpu_probkowania.system).max(0.0);
+        let cpu_usage_abs = if czas > 0.0 { (cpu_user + cpu_system) / czas * 100.0 } else { 0.0 };
+
+        // Wzrost licznika systemowego oznacza, ze nowy szczyt procesu padl w tym obszarze -
+        // wtedy jest dokladny; w przeciwnym razie zostaje maksimum z probek
+        let ram_szczyt = if end_mem.szczyt_zbioru_roboczego > self.start_mem.szczyt_zbioru_roboczego {
+            end_mem.szczyt_zbioru_roboczego
+        } else {
+            probka.max(end_mem.zbior_roboczy)
+        };
+
+        Metrics {
+            count,
+            czas,
+            cpu_usage: cpu_usage_abs / num_cpus,
+            ram_usage: end_mem.prywatna as i64 - self.start_mem.prywatna as i64,
+            cpu_user,
+            cpu_system,
+            cpu_usage_abs,
+            ram_szczyt: ram_szczyt as u64,
+            alokacje,
+            rozklad: None,
+            histogram: None,
+            trafienia: None,
+            odleglosci: None,
+            czas_dekompresji: None,
+            weryfikacja: None,
+            wyciek: None,
+        }
+    }
+}